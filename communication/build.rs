@@ -0,0 +1,14 @@
+use std::{env, path::PathBuf};
+
+/// Tell the tests where Cargo puts the library artifacts of this build, so that the C
+/// round-trip test can link `libcommunication.a`.
+fn main() {
+    // OUT_DIR is <target dir>/<profile>/build/communication-<hash>/out.
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let artifact_dir = out_dir.ancestors().nth(3).unwrap();
+    println!(
+        "cargo:rustc-env=COMMUNICATION_ARTIFACT_DIR={}",
+        artifact_dir.display()
+    );
+    println!("cargo:rerun-if-changed=build.rs");
+}
@@ -0,0 +1,230 @@
+#ifndef COMMUNICATION_PARCEL_H
+#define COMMUNICATION_PARCEL_H
+
+/* Generated by cbindgen from communication/src/ffi. Do not edit by hand. */
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+/**
+ * Byte order of a Parcel instance. Functions take it as `uint32_t`, so that values
+ * outside of the enum are rejected instead of being undefined behavior.
+ */
+typedef enum ParcelEndian {
+  PARCEL_ENDIAN_LITTLE = 0,
+  PARCEL_ENDIAN_BIG = 1,
+} ParcelEndian;
+
+typedef enum ParcelStatus {
+  PARCEL_STATUS_OK = 0,
+  /**
+   * No complete frame is available in RX buffer.
+   */
+  PARCEL_STATUS_NO_FRAME = 1,
+  /**
+   * NULL pointer or unknown endian value.
+   */
+  PARCEL_STATUS_INVALID_ARGUMENT = -1,
+  /**
+   * TX function called in the wrong order, for example writing a value before the topic.
+   */
+  PARCEL_STATUS_INVALID_PHASE = -2,
+  PARCEL_STATUS_OUT_OF_BOUNDS = -3,
+  PARCEL_STATUS_INVALID_DATA = -4,
+} ParcelStatus;
+
+/**
+ * Opaque Parcel instance handed out to C.
+ */
+typedef struct ParcelHandle ParcelHandle;
+
+/**
+ * Called with the complete frame when `parcel_tx_send` is invoked.
+ */
+typedef void (*ParcelTxCallback)(const uint8_t *data, size_t size, void *user_data);
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Create new Parcel instance. `tx_callback` may be NULL if frames are taken
+ * with `parcel_tx_buffer` instead. Release the instance with `parcel_destroy`.
+ * Returns NULL if `endian` is not a `ParcelEndian` value.
+ */
+struct ParcelHandle *parcel_create(uint32_t endian,
+                                   ParcelTxCallback tx_callback,
+                                   void *user_data);
+
+/**
+ * Destroy Parcel instance created by `parcel_create`. NULL is ignored.
+ */
+void parcel_destroy(struct ParcelHandle *handle);
+
+enum ParcelStatus parcel_tx_clear(struct ParcelHandle *handle);
+
+enum ParcelStatus parcel_tx_write_header(struct ParcelHandle *handle);
+
+enum ParcelStatus parcel_tx_write_topic(struct ParcelHandle *handle, uint16_t topic);
+
+enum ParcelStatus parcel_tx_write_i8(struct ParcelHandle *handle, int8_t value);
+
+enum ParcelStatus parcel_tx_write_u8(struct ParcelHandle *handle, uint8_t value);
+
+enum ParcelStatus parcel_tx_write_i16(struct ParcelHandle *handle, int16_t value);
+
+enum ParcelStatus parcel_tx_write_u16(struct ParcelHandle *handle, uint16_t value);
+
+enum ParcelStatus parcel_tx_write_i32(struct ParcelHandle *handle, int32_t value);
+
+enum ParcelStatus parcel_tx_write_u32(struct ParcelHandle *handle, uint32_t value);
+
+enum ParcelStatus parcel_tx_write_i64(struct ParcelHandle *handle, int64_t value);
+
+enum ParcelStatus parcel_tx_write_u64(struct ParcelHandle *handle, uint64_t value);
+
+enum ParcelStatus parcel_tx_write_f32(struct ParcelHandle *handle, float value);
+
+enum ParcelStatus parcel_tx_write_f64(struct ParcelHandle *handle, double value);
+
+enum ParcelStatus parcel_tx_write_bool(struct ParcelHandle *handle, bool value);
+
+/**
+ * Write raw bytes, such as a string without terminator, to TX buffer.
+ */
+enum ParcelStatus parcel_tx_write_bytes(struct ParcelHandle *handle,
+                                        const uint8_t *data,
+                                        size_t size);
+
+enum ParcelStatus parcel_tx_finalize(struct ParcelHandle *handle);
+
+/**
+ * Pass the finalized frame to the TX callback.
+ */
+enum ParcelStatus parcel_tx_send(struct ParcelHandle *handle);
+
+/**
+ * Get TX buffer. The pointer stays valid until the next TX call on the instance.
+ */
+const uint8_t *parcel_tx_buffer(const struct ParcelHandle *handle, size_t *size);
+
+/**
+ * Append received bytes to RX buffer.
+ */
+enum ParcelStatus parcel_rx_feed(struct ParcelHandle *handle, const uint8_t *data, size_t size);
+
+/**
+ * Extract the next frame from RX buffer.
+ * Returns `PARCEL_STATUS_NO_FRAME` if no complete frame is available. Otherwise the payload
+ * pointer stays valid until the next `parcel_rx_read_frame` call on the instance.
+ */
+enum ParcelStatus parcel_rx_read_frame(struct ParcelHandle *handle,
+                                       uint16_t *topic,
+                                       const uint8_t **payload,
+                                       size_t *payload_size);
+
+/**
+ * Read i8 value from payload at given offset.
+ */
+enum ParcelStatus parcel_rx_read_i8(const uint8_t *payload,
+                                    size_t payload_size,
+                                    size_t offset,
+                                    uint32_t endian,
+                                    int8_t *value);
+
+/**
+ * Read u8 value from payload at given offset.
+ */
+enum ParcelStatus parcel_rx_read_u8(const uint8_t *payload,
+                                    size_t payload_size,
+                                    size_t offset,
+                                    uint32_t endian,
+                                    uint8_t *value);
+
+/**
+ * Read i16 value from payload at given offset.
+ */
+enum ParcelStatus parcel_rx_read_i16(const uint8_t *payload,
+                                     size_t payload_size,
+                                     size_t offset,
+                                     uint32_t endian,
+                                     int16_t *value);
+
+/**
+ * Read u16 value from payload at given offset.
+ */
+enum ParcelStatus parcel_rx_read_u16(const uint8_t *payload,
+                                     size_t payload_size,
+                                     size_t offset,
+                                     uint32_t endian,
+                                     uint16_t *value);
+
+/**
+ * Read i32 value from payload at given offset.
+ */
+enum ParcelStatus parcel_rx_read_i32(const uint8_t *payload,
+                                     size_t payload_size,
+                                     size_t offset,
+                                     uint32_t endian,
+                                     int32_t *value);
+
+/**
+ * Read u32 value from payload at given offset.
+ */
+enum ParcelStatus parcel_rx_read_u32(const uint8_t *payload,
+                                     size_t payload_size,
+                                     size_t offset,
+                                     uint32_t endian,
+                                     uint32_t *value);
+
+/**
+ * Read i64 value from payload at given offset.
+ */
+enum ParcelStatus parcel_rx_read_i64(const uint8_t *payload,
+                                     size_t payload_size,
+                                     size_t offset,
+                                     uint32_t endian,
+                                     int64_t *value);
+
+/**
+ * Read u64 value from payload at given offset.
+ */
+enum ParcelStatus parcel_rx_read_u64(const uint8_t *payload,
+                                     size_t payload_size,
+                                     size_t offset,
+                                     uint32_t endian,
+                                     uint64_t *value);
+
+/**
+ * Read f32 value from payload at given offset.
+ */
+enum ParcelStatus parcel_rx_read_f32(const uint8_t *payload,
+                                     size_t payload_size,
+                                     size_t offset,
+                                     uint32_t endian,
+                                     float *value);
+
+/**
+ * Read f64 value from payload at given offset.
+ */
+enum ParcelStatus parcel_rx_read_f64(const uint8_t *payload,
+                                     size_t payload_size,
+                                     size_t offset,
+                                     uint32_t endian,
+                                     double *value);
+
+/**
+ * Read bool value from payload at given offset.
+ */
+enum ParcelStatus parcel_rx_read_bool(const uint8_t *payload,
+                                      size_t payload_size,
+                                      size_t offset,
+                                      uint32_t endian,
+                                      bool *value);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* COMMUNICATION_PARCEL_H */
@@ -0,0 +1,682 @@
+use std::{
+    collections::HashMap,
+    env, fs,
+    io::{self, Read},
+    process,
+};
+
+use communication::parcel::{
+    parcel::Parcel,
+    parcel_frame::{ParcelFrame, ParcelFrameCandidate},
+};
+use core::enums::endian::Endian;
+
+const USAGE: &str = "\
+Usage: parcel_dissector [OPTIONS] [FILE]
+
+Split Parcel frames out of FILE (or stdin) and print their contents.
+
+Options:
+  --format <auto|hex|raw|pcap>  Input format (default: auto)
+  --endian <little|big>         Parcel endianness (default: little)
+  --schema <FILE>               Schema used to break payloads into fields
+  --pcap-skip <N>               Bytes to skip at the start of every pcap packet (default: 0)
+  -h, --help                    Print this help
+
+Schema file format:
+  # comment
+  [topic 16 pose]
+  x f64
+  y f64
+  label string 8";
+
+#[derive(Clone, Copy, PartialEq)]
+enum InputFormat {
+    Auto,
+    Hex,
+    Raw,
+    Pcap,
+}
+
+struct Options {
+    format: InputFormat,
+    endian: Endian,
+    schema_path: Option<String>,
+    pcap_skip: usize,
+    input_path: Option<String>,
+}
+
+#[derive(Clone, Copy)]
+enum FieldType {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    I64,
+    U64,
+    F32,
+    F64,
+    String(usize),
+}
+
+struct TopicSchema {
+    name: String,
+    fields: Vec<(String, FieldType)>,
+}
+
+fn main() {
+    let options = match parse_options(env::args().skip(1).collect()) {
+        Ok(o) => o,
+        Err(message) => exit_with_error(&message),
+    };
+
+    let schema = match &options.schema_path {
+        Some(path) => match fs::read_to_string(path) {
+            Ok(text) => match parse_schema(&text) {
+                Ok(s) => s,
+                Err(message) => exit_with_error(&format!("{}: {}", path, message)),
+            },
+            Err(e) => exit_with_error(&format!("{}: {}", path, e)),
+        },
+        None => HashMap::new(),
+    };
+
+    let input = match read_input(&options.input_path) {
+        Ok(i) => i,
+        Err(e) => exit_with_error(&e.to_string()),
+    };
+
+    let bytes = match decode_input(&input, &options) {
+        Ok(b) => b,
+        Err(message) => exit_with_error(&message),
+    };
+
+    dissect(&bytes, options.endian, &schema);
+}
+
+fn exit_with_error(message: &str) -> ! {
+    eprintln!("error: {}", message);
+    process::exit(1);
+}
+
+fn parse_options(args: Vec<String>) -> Result<Options, String> {
+    let mut options = Options {
+        format: InputFormat::Auto,
+        endian: Endian::LittleEndian,
+        schema_path: None,
+        pcap_skip: 0,
+        input_path: None,
+    };
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            "--format" => {
+                options.format = match args.next().as_deref() {
+                    Some("auto") => InputFormat::Auto,
+                    Some("hex") => InputFormat::Hex,
+                    Some("raw") => InputFormat::Raw,
+                    Some("pcap") => InputFormat::Pcap,
+                    _ => return Err(String::from("--format expects auto, hex, raw or pcap")),
+                };
+            }
+            "--endian" => {
+                options.endian = match args.next().as_deref() {
+                    Some("little") => Endian::LittleEndian,
+                    Some("big") => Endian::BigEndian,
+                    _ => return Err(String::from("--endian expects little or big")),
+                };
+            }
+            "--schema" => {
+                options.schema_path = match args.next() {
+                    Some(path) => Some(path),
+                    None => return Err(String::from("--schema expects a file")),
+                };
+            }
+            "--pcap-skip" => {
+                options.pcap_skip = match args.next().map(|n| n.parse::<usize>()) {
+                    Some(Ok(n)) => n,
+                    _ => return Err(String::from("--pcap-skip expects a byte count")),
+                };
+            }
+            _ => {
+                if arg.starts_with("--") || options.input_path.is_some() {
+                    return Err(format!("unexpected argument '{}'\n\n{}", arg, USAGE));
+                }
+                if arg != "-" {
+                    options.input_path = Some(arg);
+                }
+            }
+        }
+    }
+
+    return Ok(options);
+}
+
+fn read_input(input_path: &Option<String>) -> io::Result<Vec<u8>> {
+    return match input_path {
+        Some(path) => fs::read(path),
+        None => {
+            let mut input: Vec<u8> = Vec::new();
+            io::stdin().read_to_end(&mut input)?;
+            Ok(input)
+        }
+    };
+}
+
+fn decode_input(input: &[u8], options: &Options) -> Result<Vec<u8>, String> {
+    let format = match options.format {
+        InputFormat::Auto => {
+            if is_pcap(input) {
+                InputFormat::Pcap
+            } else if is_hex_text(input) {
+                InputFormat::Hex
+            } else {
+                InputFormat::Raw
+            }
+        }
+        f => f,
+    };
+
+    return match format {
+        InputFormat::Hex => decode_hex(input),
+        InputFormat::Pcap => decode_pcap(input, options.pcap_skip),
+        _ => Ok(input.to_vec()),
+    };
+}
+
+fn is_hex_text(input: &[u8]) -> bool {
+    return input.iter().any(|b| b.is_ascii_hexdigit())
+        && input
+            .iter()
+            .all(|b| b.is_ascii_hexdigit() || b.is_ascii_whitespace() || b",:-x".contains(b));
+}
+
+/// Decode hex text such as `55 55 10 00`, `0x55,0x55` or `55551000`.
+fn decode_hex(input: &[u8]) -> Result<Vec<u8>, String> {
+    let text = match std::str::from_utf8(input) {
+        Ok(t) => t,
+        Err(_) => return Err(String::from("hex input is not valid text")),
+    };
+
+    let mut bytes: Vec<u8> = Vec::new();
+    for token in text.split(|c: char| c.is_ascii_whitespace() || c == ',' || c == ':' || c == '-') {
+        let digits = token
+            .strip_prefix("0x")
+            .or_else(|| token.strip_prefix("0X"))
+            .unwrap_or(token);
+        // Digits are sliced by byte below, which only matches characters for ASCII text.
+        if !digits.is_ascii() {
+            return Err(format!("invalid hex byte in '{}'", token));
+        }
+        if digits.len() % 2 != 0 {
+            return Err(format!("odd number of hex digits in '{}'", token));
+        }
+
+        for i in (0..digits.len()).step_by(2) {
+            match u8::from_str_radix(&digits[i..i + 2], 16) {
+                Ok(b) => bytes.push(b),
+                Err(_) => return Err(format!("invalid hex byte in '{}'", token)),
+            }
+        }
+    }
+
+    return Ok(bytes);
+}
+
+fn is_pcap(input: &[u8]) -> bool {
+    return input.len() >= 24 && pcap_byte_order(&input[0..4]).is_some();
+}
+
+/// Returns whether a pcap file with given magic number is big endian.
+fn pcap_byte_order(magic: &[u8]) -> Option<bool> {
+    return match magic {
+        [0xD4, 0xC3, 0xB2, 0xA1] | [0x4D, 0x3C, 0xB2, 0xA1] => Some(false),
+        [0xA1, 0xB2, 0xC3, 0xD4] | [0xA1, 0xB2, 0x3C, 0x4D] => Some(true),
+        _ => None,
+    };
+}
+
+/// Concatenate the data of every packet in a pcap capture into one byte stream.
+fn decode_pcap(input: &[u8], pcap_skip: usize) -> Result<Vec<u8>, String> {
+    let is_big_endian = match input.get(0..4).and_then(pcap_byte_order) {
+        Some(b) => b,
+        None => return Err(String::from("input is not a pcap capture")),
+    };
+    let read_u32 = |offset: usize| -> u32 {
+        let bytes: [u8; 4] = [
+            input[offset],
+            input[offset + 1],
+            input[offset + 2],
+            input[offset + 3],
+        ];
+        return match is_big_endian {
+            true => u32::from_be_bytes(bytes),
+            false => u32::from_le_bytes(bytes),
+        };
+    };
+
+    let mut bytes: Vec<u8> = Vec::new();
+    let mut offset: usize = 24;
+    while offset + 16 <= input.len() {
+        let captured_size = read_u32(offset + 8) as usize;
+        let data_start = offset + 16;
+        let data_end = match data_start
+            .checked_add(captured_size)
+            .filter(|end| *end <= input.len())
+        {
+            Some(end) => end,
+            None => return Err(format!("truncated pcap packet at offset {}", offset)),
+        };
+
+        if captured_size > pcap_skip {
+            bytes.extend_from_slice(&input[data_start + pcap_skip..data_end]);
+        }
+        offset = data_end;
+    }
+
+    return Ok(bytes);
+}
+
+fn parse_schema(text: &str) -> Result<HashMap<u16, TopicSchema>, String> {
+    let mut schema: HashMap<u16, TopicSchema> = HashMap::new();
+    let mut current_topic: Option<u16> = None;
+
+    for (line_index, line) in text.lines().enumerate() {
+        let line_number = line_index + 1;
+        let line = match line.find('#') {
+            Some(i) => &line[..i],
+            None => line,
+        }
+        .trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            let tokens: Vec<&str> = section.split_whitespace().collect();
+            let topic = match tokens.as_slice() {
+                ["topic", id, ..] => parse_topic_id(id),
+                _ => None,
+            };
+            let topic = match topic {
+                Some(t) => t,
+                None => {
+                    return Err(format!(
+                        "line {}: expected [topic <id> <name>]",
+                        line_number
+                    ))
+                }
+            };
+
+            let name = tokens[2..].join(" ");
+            if schema
+                .insert(
+                    topic,
+                    TopicSchema {
+                        name: name,
+                        fields: Vec::new(),
+                    },
+                )
+                .is_some()
+            {
+                return Err(format!(
+                    "line {}: topic {} defined twice",
+                    line_number, topic
+                ));
+            }
+            current_topic = Some(topic);
+            continue;
+        }
+
+        let topic_schema = match current_topic.and_then(|t| schema.get_mut(&t)) {
+            Some(s) => s,
+            None => {
+                return Err(format!(
+                    "line {}: field outside of a topic section",
+                    line_number
+                ))
+            }
+        };
+
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let field_type = match tokens.as_slice() {
+            [_, "i8"] => Some(FieldType::I8),
+            [_, "u8"] => Some(FieldType::U8),
+            [_, "i16"] => Some(FieldType::I16),
+            [_, "u16"] => Some(FieldType::U16),
+            [_, "i32"] => Some(FieldType::I32),
+            [_, "u32"] => Some(FieldType::U32),
+            [_, "i64"] => Some(FieldType::I64),
+            [_, "u64"] => Some(FieldType::U64),
+            [_, "f32"] => Some(FieldType::F32),
+            [_, "f64"] => Some(FieldType::F64),
+            [_, "string", length] => length.parse::<usize>().ok().map(FieldType::String),
+            _ => None,
+        };
+
+        match field_type {
+            Some(t) => topic_schema.fields.push((String::from(tokens[0]), t)),
+            None => return Err(format!("line {}: invalid field '{}'", line_number, line)),
+        }
+    }
+
+    return Ok(schema);
+}
+
+fn parse_topic_id(text: &str) -> Option<u16> {
+    return match text.strip_prefix("0x") {
+        Some(hex) => u16::from_str_radix(hex, 16).ok(),
+        None => text.parse::<u16>().ok(),
+    };
+}
+
+fn dissect(bytes: &[u8], endian: Endian, schema: &HashMap<u16, TopicSchema>) {
+    let mut offset: usize = 0;
+    let mut frame_count: usize = 0;
+    let mut invalid_count: usize = 0;
+    let mut skipped_count: usize = 0;
+
+    while offset < bytes.len() {
+        match Parcel::rx_inspect_frame(bytes[offset..].iter(), endian) {
+            ParcelFrameCandidate::Incomplete => break,
+            ParcelFrameCandidate::OutOfOrder => {
+                skipped_count += 1;
+                offset += 1;
+            }
+            ParcelFrameCandidate::Frame(frame) => {
+                print_frame(frame_count, offset, &frame, endian, schema);
+                frame_count += 1;
+
+                // Like `rx_read_frame`, resynchronize one byte further after a bad checksum.
+                if frame.is_checksum_valid() {
+                    offset += frame.frame_size();
+                } else {
+                    invalid_count += 1;
+                    offset += 1;
+                }
+            }
+        }
+    }
+
+    println!(
+        "{} frame(s), {} with invalid checksum, {} byte(s) skipped, {} trailing byte(s)",
+        frame_count,
+        invalid_count,
+        skipped_count,
+        bytes.len() - offset
+    );
+}
+
+fn print_frame(
+    index: usize,
+    offset: usize,
+    frame: &ParcelFrame,
+    endian: Endian,
+    schema: &HashMap<u16, TopicSchema>,
+) {
+    let topic_schema = schema.get(&frame.topic);
+    let topic_name = match topic_schema {
+        Some(s) => format!(" ({})", s.name),
+        None => String::new(),
+    };
+    let checksum_state = match frame.is_checksum_valid() {
+        true => String::from("ok"),
+        false => format!("INVALID, expected 0x{:02x}", frame.computed_checksum),
+    };
+
+    println!(
+        "frame {} @ offset {}: topic {}{}, payload {} byte(s), checksum 0x{:02x} {}",
+        index,
+        offset,
+        frame.topic,
+        topic_name,
+        frame.payload.len(),
+        frame.received_checksum,
+        checksum_state
+    );
+
+    let mut field_end: usize = 0;
+    if let Some(topic_schema) = topic_schema {
+        for (name, field_type) in topic_schema.fields.iter() {
+            match read_field(&frame.payload, field_end, *field_type, endian) {
+                Some((value, size)) => {
+                    println!("  {:>5}  {}: {}", field_end, name, value);
+                    field_end += size;
+                }
+                None => {
+                    println!("  {:>5}  {}: <out of bounds>", field_end, name);
+                    field_end = frame.payload.len();
+                    break;
+                }
+            }
+        }
+    }
+
+    if field_end < frame.payload.len() {
+        let label = match topic_schema {
+            Some(_) => "unparsed",
+            None => "payload",
+        };
+        println!("  {}:", label);
+        print_hex(&frame.payload[field_end..], field_end);
+    }
+}
+
+fn read_field(
+    payload: &[u8],
+    offset: usize,
+    field_type: FieldType,
+    endian: Endian,
+) -> Option<(String, usize)> {
+    let field = match field_type {
+        FieldType::I8 => {
+            Parcel::rx_read_i8(payload, offset, endian).map(|v| (format!("{} (i8)", v), 1))
+        }
+        FieldType::U8 => {
+            Parcel::rx_read_u8(payload, offset, endian).map(|v| (format!("{} (u8)", v), 1))
+        }
+        FieldType::I16 => {
+            Parcel::rx_read_i16(payload, offset, endian).map(|v| (format!("{} (i16)", v), 2))
+        }
+        FieldType::U16 => {
+            Parcel::rx_read_u16(payload, offset, endian).map(|v| (format!("{} (u16)", v), 2))
+        }
+        FieldType::I32 => {
+            Parcel::rx_read_i32(payload, offset, endian).map(|v| (format!("{} (i32)", v), 4))
+        }
+        FieldType::U32 => {
+            Parcel::rx_read_u32(payload, offset, endian).map(|v| (format!("{} (u32)", v), 4))
+        }
+        FieldType::I64 => {
+            Parcel::rx_read_i64(payload, offset, endian).map(|v| (format!("{} (i64)", v), 8))
+        }
+        FieldType::U64 => {
+            Parcel::rx_read_u64(payload, offset, endian).map(|v| (format!("{} (u64)", v), 8))
+        }
+        FieldType::F32 => {
+            Parcel::rx_read_f32(payload, offset, endian).map(|v| (format!("{} (f32)", v), 4))
+        }
+        FieldType::F64 => {
+            Parcel::rx_read_f64(payload, offset, endian).map(|v| (format!("{} (f64)", v), 8))
+        }
+        FieldType::String(length) => match Parcel::rx_read_string(payload, offset, length) {
+            Ok(v) => Ok((format!("{:?} (string)", v), length)),
+            Err(e) => match offset
+                .checked_add(length)
+                .filter(|end| *end <= payload.len())
+            {
+                // Keep going with the raw bytes if the string is not valid UTF-8.
+                Some(end) => Ok((
+                    format!("{:?} (string, invalid UTF-8)", &payload[offset..end]),
+                    length,
+                )),
+                None => Err(e),
+            },
+        },
+    };
+
+    return field.ok();
+}
+
+fn print_hex(bytes: &[u8], start_offset: usize) {
+    for (row, chunk) in bytes.chunks(16).enumerate() {
+        let hex: Vec<String> = chunk.iter().map(|b| format!("{:02x}", b)).collect();
+        println!("  {:>5}  {}", start_offset + row * 16, hex.join(" "));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build pcap capture with given magic number, byte order and packets.
+    fn build_pcap(is_big_endian: bool, packets: &[&[u8]]) -> Vec<u8> {
+        let u32_bytes = |value: u32| match is_big_endian {
+            true => value.to_be_bytes(),
+            false => value.to_le_bytes(),
+        };
+
+        let mut capture: Vec<u8> = Vec::new();
+        capture.extend_from_slice(&u32_bytes(0xA1B2_C3D4));
+        capture.extend_from_slice(&[0; 20]);
+        for packet in packets {
+            capture.extend_from_slice(&[0; 8]);
+            capture.extend_from_slice(&u32_bytes(packet.len() as u32));
+            capture.extend_from_slice(&u32_bytes(packet.len() as u32));
+            capture.extend_from_slice(packet);
+        }
+        return capture;
+    }
+
+    #[test]
+    fn decode_hex_accepts_common_notations() {
+        let expected: Vec<u8> = vec![0x55, 0x55, 0x10, 0xAB];
+        assert_eq!(decode_hex(b"55 55 10 ab").unwrap(), expected);
+        assert_eq!(decode_hex(b"0x55,0x55,0x10,0XAB\n").unwrap(), expected);
+        assert_eq!(decode_hex(b"555510AB").unwrap(), expected);
+        assert_eq!(decode_hex(b"55:55-10\tab").unwrap(), expected);
+        assert_eq!(decode_hex(b"").unwrap(), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn decode_hex_rejects_malformed_input() {
+        assert!(decode_hex(b"555").is_err());
+        assert!(decode_hex(b"5g").is_err());
+        assert!(decode_hex(&[0xFF, 0xFE]).is_err());
+
+        // Even byte count, but slicing by byte would split the two-byte character.
+        assert!(decode_hex("aéb".as_bytes()).is_err());
+        assert!(decode_hex("éé".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn is_hex_text_detects_hex_dumps() {
+        assert!(is_hex_text(b"55 55 0x10,\n"));
+        assert!(!is_hex_text(b" \n"));
+        assert!(!is_hex_text(&[0x55, 0x55, 0x10, 0x00]));
+        assert!(!is_hex_text("aé".as_bytes()));
+    }
+
+    #[test]
+    fn decode_pcap_concatenates_packet_data() {
+        for is_big_endian in [false, true] {
+            let capture = build_pcap(is_big_endian, &[&[1, 2, 3], &[], &[4, 5]]);
+            assert!(is_pcap(&capture));
+            assert_eq!(decode_pcap(&capture, 0).unwrap(), vec![1, 2, 3, 4, 5]);
+
+            // Packets no longer than the skipped header contribute nothing.
+            assert_eq!(decode_pcap(&capture, 2).unwrap(), vec![3]);
+        }
+    }
+
+    #[test]
+    fn decode_pcap_rejects_bad_captures() {
+        assert!(!is_pcap(&[0x55; 32]));
+        assert!(decode_pcap(&[0x55; 32], 0).is_err());
+
+        let mut capture = build_pcap(false, &[&[1, 2, 3, 4]]);
+        capture.pop();
+        assert!(decode_pcap(&capture, 0).is_err());
+
+        let mut capture = build_pcap(false, &[&[1, 2, 3, 4]]);
+        capture[32..36].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(decode_pcap(&capture, 0).is_err());
+    }
+
+    #[test]
+    fn parse_schema_reads_topics_and_fields() {
+        let schema = parse_schema(
+            "# pose messages\n\
+             [topic 16 robot pose]\n\
+             x f64\n\
+             y f64  # meters\n\
+             \n\
+             [topic 0x20 status]\n\
+             code u8\n\
+             label string 8\n",
+        )
+        .unwrap();
+
+        assert_eq!(schema.len(), 2);
+        let pose = &schema[&16];
+        assert_eq!(pose.name, "robot pose");
+        assert_eq!(pose.fields.len(), 2);
+        assert_eq!(pose.fields[1].0, "y");
+        assert!(matches!(pose.fields[1].1, FieldType::F64));
+
+        let status = &schema[&0x20];
+        assert_eq!(status.name, "status");
+        assert!(matches!(status.fields[0].1, FieldType::U8));
+        assert!(matches!(status.fields[1].1, FieldType::String(8)));
+    }
+
+    #[test]
+    fn parse_schema_reports_line_of_error() {
+        let error = parse_schema("x f64").err().unwrap();
+        assert!(error.starts_with("line 1:"));
+
+        let error = parse_schema("[topic 1 a]\n[topic 1 b]").err().unwrap();
+        assert!(error.starts_with("line 2:"));
+
+        let error = parse_schema("[topic 1 a]\nx f128").err().unwrap();
+        assert!(error.starts_with("line 2:"));
+
+        assert!(parse_schema("[topic 70000 a]").is_err());
+        assert!(parse_schema("[topic 1 a]\nlabel string").is_err());
+    }
+
+    #[test]
+    fn read_field_reads_from_slices() {
+        let mut payload: Vec<u8> = 1.5_f64.to_le_bytes().to_vec();
+        payload.extend_from_slice(b"ab");
+        payload.push(0xFF);
+
+        let (value, size) = read_field(&payload, 0, FieldType::F64, Endian::LittleEndian).unwrap();
+        assert_eq!((value.as_str(), size), ("1.5 (f64)", 8));
+
+        let (value, size) =
+            read_field(&payload[8..], 0, FieldType::String(2), Endian::LittleEndian).unwrap();
+        assert_eq!((value.as_str(), size), ("\"ab\" (string)", 2));
+
+        let (value, _) =
+            read_field(&payload, 9, FieldType::String(2), Endian::LittleEndian).unwrap();
+        assert!(value.contains("invalid UTF-8"));
+
+        assert!(read_field(&payload, 4, FieldType::U64, Endian::LittleEndian).is_none());
+        assert!(read_field(&payload, 10, FieldType::String(2), Endian::LittleEndian).is_none());
+        assert!(read_field(
+            &payload,
+            9,
+            FieldType::String(usize::MAX),
+            Endian::LittleEndian
+        )
+        .is_none());
+    }
+}
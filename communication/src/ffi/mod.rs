@@ -0,0 +1 @@
+pub mod parcel_ffi;
@@ -0,0 +1,492 @@
+//! C interface to `Parcel`.
+//!
+//! Handle, data and output pointers passed to these functions must be NULL or valid for
+//! the given size; NULL is reported with `PARCEL_STATUS_INVALID_ARGUMENT` where noted.
+#![allow(clippy::missing_safety_doc)]
+
+use std::{ffi::c_void, ptr, slice};
+
+use core::enums::endian::Endian;
+
+use crate::parcel::{
+    parcel::Parcel, parcel_error_type::ParcelErrorType, parcel_primitive::ParcelPrimitive,
+};
+
+/// Called with the complete frame when `parcel_tx_send` is invoked.
+pub type ParcelTxCallback =
+    Option<extern "C" fn(data: *const u8, size: usize, user_data: *mut c_void)>;
+
+/// Opaque Parcel instance handed out to C.
+pub struct ParcelHandle {
+    parcel: Parcel<'static>,
+    rx_frame: Option<(u16, Vec<u8>)>,
+}
+
+/// Byte order of a Parcel instance. Functions take it as `uint32_t`, so that values
+/// outside of the enum are rejected instead of being undefined behavior.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ParcelEndian {
+    Little = 0,
+    Big = 1,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ParcelStatus {
+    Ok = 0,
+    /// No complete frame is available in RX buffer.
+    NoFrame = 1,
+    /// NULL pointer or unknown endian value.
+    InvalidArgument = -1,
+    /// TX function called in the wrong order, for example writing a value before the topic.
+    InvalidPhase = -2,
+    OutOfBounds = -3,
+    InvalidData = -4,
+}
+
+struct TxCallbackContext {
+    callback: ParcelTxCallback,
+    user_data: *mut c_void,
+}
+
+impl ParcelEndian {
+    /// Convert endian value received from C, which may be out of range.
+    fn to_endian(endian: u32) -> Option<Endian> {
+        if endian == ParcelEndian::Little as u32 {
+            return Some(Endian::LittleEndian);
+        }
+        if endian == ParcelEndian::Big as u32 {
+            return Some(Endian::BigEndian);
+        }
+        return None;
+    }
+}
+
+impl From<ParcelErrorType> for ParcelStatus {
+    fn from(error: ParcelErrorType) -> Self {
+        return match error {
+            ParcelErrorType::OutOfPhase => ParcelStatus::InvalidPhase,
+            ParcelErrorType::OutOfBounds => ParcelStatus::OutOfBounds,
+            ParcelErrorType::InvalidData => ParcelStatus::InvalidData,
+        };
+    }
+}
+
+impl From<Result<(), ParcelErrorType>> for ParcelStatus {
+    fn from(result: Result<(), ParcelErrorType>) -> Self {
+        return match result {
+            Ok(()) => ParcelStatus::Ok,
+            Err(e) => e.into(),
+        };
+    }
+}
+
+/// Run a Parcel TX call on the instance behind given handle.
+unsafe fn tx_call<F>(handle: *mut ParcelHandle, f: F) -> ParcelStatus
+where
+    F: FnOnce(&mut Parcel<'static>) -> Result<(), ParcelErrorType>,
+{
+    return match handle.as_mut() {
+        Some(h) => f(&mut h.parcel).into(),
+        None => ParcelStatus::InvalidArgument,
+    };
+}
+
+unsafe fn rx_read_value<T: ParcelPrimitive>(
+    payload: *const u8,
+    payload_size: usize,
+    offset: usize,
+    endian: u32,
+    value: *mut T,
+) -> ParcelStatus {
+    if payload.is_null() || value.is_null() {
+        return ParcelStatus::InvalidArgument;
+    }
+    let endian = match ParcelEndian::to_endian(endian) {
+        Some(e) => e,
+        None => return ParcelStatus::InvalidArgument,
+    };
+
+    let payload = slice::from_raw_parts(payload, payload_size);
+    return match Parcel::rx_read::<T>(payload, offset, endian) {
+        Ok(v) => {
+            *value = v;
+            ParcelStatus::Ok
+        }
+        Err(e) => e.into(),
+    };
+}
+
+/// Create new Parcel instance. `tx_callback` may be NULL if frames are taken
+/// with `parcel_tx_buffer` instead. Release the instance with `parcel_destroy`.
+/// Returns NULL if `endian` is not a `ParcelEndian` value.
+#[no_mangle]
+pub extern "C" fn parcel_create(
+    endian: u32,
+    tx_callback: ParcelTxCallback,
+    user_data: *mut c_void,
+) -> *mut ParcelHandle {
+    let endian = match ParcelEndian::to_endian(endian) {
+        Some(e) => e,
+        None => return ptr::null_mut(),
+    };
+
+    let context = TxCallbackContext {
+        callback: tx_callback,
+        user_data: user_data,
+    };
+    let tx_func = move |bytes: &Vec<u8>| {
+        if let Some(callback) = context.callback {
+            callback(bytes.as_ptr(), bytes.len(), context.user_data);
+        }
+    };
+
+    let handle = ParcelHandle {
+        parcel: Parcel::new(endian, tx_func, Vec::new),
+        rx_frame: None,
+    };
+    return Box::into_raw(Box::new(handle));
+}
+
+/// Destroy Parcel instance created by `parcel_create`. NULL is ignored.
+#[no_mangle]
+pub unsafe extern "C" fn parcel_destroy(handle: *mut ParcelHandle) {
+    if !handle.is_null() {
+        drop(Box::from_raw(handle));
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn parcel_tx_clear(handle: *mut ParcelHandle) -> ParcelStatus {
+    return tx_call(handle, |p| {
+        p.tx_clear();
+        return Ok(());
+    });
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn parcel_tx_write_header(handle: *mut ParcelHandle) -> ParcelStatus {
+    return tx_call(handle, |p| p.try_tx_write_header());
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn parcel_tx_write_topic(
+    handle: *mut ParcelHandle,
+    topic: u16,
+) -> ParcelStatus {
+    return tx_call(handle, |p| p.try_tx_write_topic(topic));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn parcel_tx_write_i8(handle: *mut ParcelHandle, value: i8) -> ParcelStatus {
+    return tx_call(handle, |p| p.try_tx_write(value));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn parcel_tx_write_u8(handle: *mut ParcelHandle, value: u8) -> ParcelStatus {
+    return tx_call(handle, |p| p.try_tx_write(value));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn parcel_tx_write_i16(
+    handle: *mut ParcelHandle,
+    value: i16,
+) -> ParcelStatus {
+    return tx_call(handle, |p| p.try_tx_write(value));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn parcel_tx_write_u16(
+    handle: *mut ParcelHandle,
+    value: u16,
+) -> ParcelStatus {
+    return tx_call(handle, |p| p.try_tx_write(value));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn parcel_tx_write_i32(
+    handle: *mut ParcelHandle,
+    value: i32,
+) -> ParcelStatus {
+    return tx_call(handle, |p| p.try_tx_write(value));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn parcel_tx_write_u32(
+    handle: *mut ParcelHandle,
+    value: u32,
+) -> ParcelStatus {
+    return tx_call(handle, |p| p.try_tx_write(value));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn parcel_tx_write_i64(
+    handle: *mut ParcelHandle,
+    value: i64,
+) -> ParcelStatus {
+    return tx_call(handle, |p| p.try_tx_write(value));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn parcel_tx_write_u64(
+    handle: *mut ParcelHandle,
+    value: u64,
+) -> ParcelStatus {
+    return tx_call(handle, |p| p.try_tx_write(value));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn parcel_tx_write_f32(
+    handle: *mut ParcelHandle,
+    value: f32,
+) -> ParcelStatus {
+    return tx_call(handle, |p| p.try_tx_write(value));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn parcel_tx_write_f64(
+    handle: *mut ParcelHandle,
+    value: f64,
+) -> ParcelStatus {
+    return tx_call(handle, |p| p.try_tx_write(value));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn parcel_tx_write_bool(
+    handle: *mut ParcelHandle,
+    value: bool,
+) -> ParcelStatus {
+    return tx_call(handle, |p| p.try_tx_write(value));
+}
+
+/// Write raw bytes, such as a string without terminator, to TX buffer.
+#[no_mangle]
+pub unsafe extern "C" fn parcel_tx_write_bytes(
+    handle: *mut ParcelHandle,
+    data: *const u8,
+    size: usize,
+) -> ParcelStatus {
+    if data.is_null() && size > 0 {
+        return ParcelStatus::InvalidArgument;
+    }
+
+    let bytes = match size {
+        0 => &[],
+        _ => slice::from_raw_parts(data, size),
+    };
+    return tx_call(handle, |p| p.try_tx_write_slice(bytes));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn parcel_tx_finalize(handle: *mut ParcelHandle) -> ParcelStatus {
+    return tx_call(handle, |p| p.try_tx_finalize());
+}
+
+/// Pass the finalized frame to the TX callback.
+#[no_mangle]
+pub unsafe extern "C" fn parcel_tx_send(handle: *mut ParcelHandle) -> ParcelStatus {
+    return tx_call(handle, |p| p.try_tx_send());
+}
+
+/// Get TX buffer. The pointer stays valid until the next TX call on the instance.
+#[no_mangle]
+pub unsafe extern "C" fn parcel_tx_buffer(
+    handle: *const ParcelHandle,
+    size: *mut usize,
+) -> *const u8 {
+    let handle = match handle.as_ref() {
+        Some(h) => h,
+        None => return ptr::null(),
+    };
+
+    let buffer = handle.parcel.tx_buffer();
+    if !size.is_null() {
+        *size = buffer.len();
+    }
+    return buffer.as_ptr();
+}
+
+/// Append received bytes to RX buffer.
+#[no_mangle]
+pub unsafe extern "C" fn parcel_rx_feed(
+    handle: *mut ParcelHandle,
+    data: *const u8,
+    size: usize,
+) -> ParcelStatus {
+    let handle = match handle.as_mut() {
+        Some(h) => h,
+        None => return ParcelStatus::InvalidArgument,
+    };
+    if size == 0 {
+        return ParcelStatus::Ok;
+    }
+    if data.is_null() {
+        return ParcelStatus::InvalidArgument;
+    }
+
+    handle.parcel.rx_feed(slice::from_raw_parts(data, size));
+    return ParcelStatus::Ok;
+}
+
+/// Extract the next frame from RX buffer.
+/// Returns `PARCEL_STATUS_NO_FRAME` if no complete frame is available. Otherwise the payload
+/// pointer stays valid until the next `parcel_rx_read_frame` call on the instance.
+#[no_mangle]
+pub unsafe extern "C" fn parcel_rx_read_frame(
+    handle: *mut ParcelHandle,
+    topic: *mut u16,
+    payload: *mut *const u8,
+    payload_size: *mut usize,
+) -> ParcelStatus {
+    let handle = match handle.as_mut() {
+        Some(h) => h,
+        None => return ParcelStatus::InvalidArgument,
+    };
+    if topic.is_null() || payload.is_null() || payload_size.is_null() {
+        return ParcelStatus::InvalidArgument;
+    }
+
+    handle.rx_frame = handle.parcel.rx_read_frame();
+    let (frame_topic, frame_payload) = match &handle.rx_frame {
+        Some(f) => f,
+        None => return ParcelStatus::NoFrame,
+    };
+
+    *topic = *frame_topic;
+    *payload = frame_payload.as_ptr();
+    *payload_size = frame_payload.len();
+    return ParcelStatus::Ok;
+}
+
+/// Read i8 value from payload at given offset.
+#[no_mangle]
+pub unsafe extern "C" fn parcel_rx_read_i8(
+    payload: *const u8,
+    payload_size: usize,
+    offset: usize,
+    endian: u32,
+    value: *mut i8,
+) -> ParcelStatus {
+    return rx_read_value(payload, payload_size, offset, endian, value);
+}
+
+/// Read u8 value from payload at given offset.
+#[no_mangle]
+pub unsafe extern "C" fn parcel_rx_read_u8(
+    payload: *const u8,
+    payload_size: usize,
+    offset: usize,
+    endian: u32,
+    value: *mut u8,
+) -> ParcelStatus {
+    return rx_read_value(payload, payload_size, offset, endian, value);
+}
+
+/// Read i16 value from payload at given offset.
+#[no_mangle]
+pub unsafe extern "C" fn parcel_rx_read_i16(
+    payload: *const u8,
+    payload_size: usize,
+    offset: usize,
+    endian: u32,
+    value: *mut i16,
+) -> ParcelStatus {
+    return rx_read_value(payload, payload_size, offset, endian, value);
+}
+
+/// Read u16 value from payload at given offset.
+#[no_mangle]
+pub unsafe extern "C" fn parcel_rx_read_u16(
+    payload: *const u8,
+    payload_size: usize,
+    offset: usize,
+    endian: u32,
+    value: *mut u16,
+) -> ParcelStatus {
+    return rx_read_value(payload, payload_size, offset, endian, value);
+}
+
+/// Read i32 value from payload at given offset.
+#[no_mangle]
+pub unsafe extern "C" fn parcel_rx_read_i32(
+    payload: *const u8,
+    payload_size: usize,
+    offset: usize,
+    endian: u32,
+    value: *mut i32,
+) -> ParcelStatus {
+    return rx_read_value(payload, payload_size, offset, endian, value);
+}
+
+/// Read u32 value from payload at given offset.
+#[no_mangle]
+pub unsafe extern "C" fn parcel_rx_read_u32(
+    payload: *const u8,
+    payload_size: usize,
+    offset: usize,
+    endian: u32,
+    value: *mut u32,
+) -> ParcelStatus {
+    return rx_read_value(payload, payload_size, offset, endian, value);
+}
+
+/// Read i64 value from payload at given offset.
+#[no_mangle]
+pub unsafe extern "C" fn parcel_rx_read_i64(
+    payload: *const u8,
+    payload_size: usize,
+    offset: usize,
+    endian: u32,
+    value: *mut i64,
+) -> ParcelStatus {
+    return rx_read_value(payload, payload_size, offset, endian, value);
+}
+
+/// Read u64 value from payload at given offset.
+#[no_mangle]
+pub unsafe extern "C" fn parcel_rx_read_u64(
+    payload: *const u8,
+    payload_size: usize,
+    offset: usize,
+    endian: u32,
+    value: *mut u64,
+) -> ParcelStatus {
+    return rx_read_value(payload, payload_size, offset, endian, value);
+}
+
+/// Read f32 value from payload at given offset.
+#[no_mangle]
+pub unsafe extern "C" fn parcel_rx_read_f32(
+    payload: *const u8,
+    payload_size: usize,
+    offset: usize,
+    endian: u32,
+    value: *mut f32,
+) -> ParcelStatus {
+    return rx_read_value(payload, payload_size, offset, endian, value);
+}
+
+/// Read f64 value from payload at given offset.
+#[no_mangle]
+pub unsafe extern "C" fn parcel_rx_read_f64(
+    payload: *const u8,
+    payload_size: usize,
+    offset: usize,
+    endian: u32,
+    value: *mut f64,
+) -> ParcelStatus {
+    return rx_read_value(payload, payload_size, offset, endian, value);
+}
+
+/// Read bool value from payload at given offset.
+#[no_mangle]
+pub unsafe extern "C" fn parcel_rx_read_bool(
+    payload: *const u8,
+    payload_size: usize,
+    offset: usize,
+    endian: u32,
+    value: *mut bool,
+) -> ParcelStatus {
+    return rx_read_value(payload, payload_size, offset, endian, value);
+}
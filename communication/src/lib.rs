@@ -1,3 +1,7 @@
+pub mod ffi;
+pub mod parcel;
+pub mod shared_memory;
+
 pub fn add(left: u64, right: u64) -> u64 {
     left + right
 }
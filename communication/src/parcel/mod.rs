@@ -1,2 +1,9 @@
+#[allow(clippy::module_inception)]
 pub mod parcel;
 pub mod parcel_error_type;
+pub mod parcel_frame;
+pub mod parcel_message;
+pub mod parcel_primitive;
+pub mod topic;
+pub mod topic_registry;
+pub mod topic_registry_error_type;
@@ -1,26 +1,51 @@
-use core::panic;
-use std::{collections::LinkedList, iter::zip, mem::size_of, num::Wrapping};
-
-use crate::enums::endian::Endian;
-
-use super::parcel_error_type::ParcelErrorType;
+use std::{
+    any::TypeId,
+    collections::{HashMap, LinkedList},
+    num::Wrapping,
+};
+
+use core::enums::endian::Endian;
+
+use super::{
+    parcel_error_type::ParcelErrorType,
+    parcel_frame::{ParcelFrame, ParcelFrameCandidate},
+    parcel_message::ParcelMessage,
+    parcel_primitive::ParcelPrimitive,
+    topic::Topic,
+    topic_registry_error_type::TopicRegistryErrorType,
+};
+
+/// Largest payload the two-byte size field of a frame can describe.
+const MAX_PAYLOAD_SIZE: usize = u16::MAX as usize;
+
+type ParcelTxFunc<'a> = Box<dyn FnMut(&Vec<u8>) + 'a>;
+type ParcelRxFunc<'a> = Box<dyn FnMut() -> Vec<u8> + 'a>;
+/// Decodes a payload and passes it to the subscriber. Returns whether decoding succeeded.
+type ParcelHandler<'a> = Box<dyn FnMut(&[u8], Endian) -> bool + 'a>;
 
 pub struct Parcel<'a> {
     tx_buffer: Vec<u8>,
     rx_buffer: LinkedList<u8>,
     parcel_endian: Endian,
 
-    tx_func: Box<dyn FnMut(&Vec<u8>) -> () + 'a>,
-    rx_func: Box<dyn FnMut() -> Vec<u8> + 'a>,
+    tx_func: ParcelTxFunc<'a>,
+    rx_func: ParcelRxFunc<'a>,
 
     tx_buffer_phase: ParcelTxPhase,
+
+    subscriptions: HashMap<u16, ParcelSubscription<'a>>,
+}
+
+struct ParcelSubscription<'a> {
+    message_type_id: TypeId,
+    handler: ParcelHandler<'a>,
 }
 
 impl<'a> Parcel<'a> {
     /// Create new Parcel instance.
     pub fn new<TxFuncT, RxFuncT>(parcel_endian: Endian, tx_func: TxFuncT, rx_func: RxFuncT) -> Self
     where
-        TxFuncT: FnMut(&Vec<u8>) -> () + 'a,
+        TxFuncT: FnMut(&Vec<u8>) + 'a,
         RxFuncT: FnMut() -> Vec<u8> + 'a,
     {
         return Self {
@@ -30,6 +55,7 @@ impl<'a> Parcel<'a> {
             tx_buffer_phase: ParcelTxPhase::Init,
             tx_func: Box::new(tx_func),
             rx_func: Box::new(rx_func),
+            subscriptions: HashMap::new(),
         };
     }
 
@@ -40,20 +66,35 @@ impl<'a> Parcel<'a> {
 
     /// Write header to TX buffer.
     pub fn tx_write_header(&mut self) {
-        if self.tx_buffer_phase != ParcelTxPhase::Init {
+        if self.try_tx_write_header().is_err() {
             panic!("TX buffer is not initialized.");
         }
+    }
+
+    /// Write header to TX buffer. Fails with `OutOfPhase` unless the buffer is cleared.
+    pub fn try_tx_write_header(&mut self) -> Result<(), ParcelErrorType> {
+        if self.tx_buffer_phase != ParcelTxPhase::Init {
+            return Err(ParcelErrorType::OutOfPhase);
+        }
 
         self.tx_buffer.push(0x55_u8);
         self.tx_buffer.push(0x55_u8);
         self.tx_buffer_phase = ParcelTxPhase::HeaderWritten;
+        return Ok(());
     }
 
     /// Write topic to TX buffer.
     pub fn tx_write_topic(&mut self, topic: u16) {
-        if self.tx_buffer_phase != ParcelTxPhase::HeaderWritten {
+        if self.try_tx_write_topic(topic).is_err() {
             panic!("Header is not written to TX buffer yet.");
         }
+    }
+
+    /// Write topic to TX buffer. Fails with `OutOfPhase` unless only the header is written.
+    pub fn try_tx_write_topic(&mut self, topic: u16) -> Result<(), ParcelErrorType> {
+        if self.tx_buffer_phase != ParcelTxPhase::HeaderWritten {
+            return Err(ParcelErrorType::OutOfPhase);
+        }
 
         let bytes = match self.parcel_endian {
             Endian::BigEndian => topic.to_be_bytes(),
@@ -65,155 +106,151 @@ impl<'a> Parcel<'a> {
         self.tx_write_bytes(&[0x00_u8, 0x00_u8]);
 
         self.tx_buffer_phase = ParcelTxPhase::TopicWritten;
+        return Ok(());
     }
 
-    /// Write i8 value to TX buffer.
-    pub fn tx_write_i8(&mut self, value: i8) {
-        if self.tx_buffer_phase != ParcelTxPhase::TopicWritten {
-            panic!("Topic is not written to TX buffer yet.");
-        }
+    /// Write primitive value to TX buffer.
+    pub fn tx_write<T: ParcelPrimitive>(&mut self, value: T) {
+        Parcel::expect_payload_written(self.try_tx_write(value));
+    }
 
-        let bytes = match self.parcel_endian {
-            Endian::BigEndian => value.to_be_bytes(),
-            Endian::LittleEndian => value.to_le_bytes(),
+    /// Write primitive value to TX buffer.
+    /// Fails with `OutOfPhase` before the topic is written, and with `OutOfBounds` if the
+    /// payload would no longer fit in a frame.
+    pub fn try_tx_write<T: ParcelPrimitive>(&mut self, value: T) -> Result<(), ParcelErrorType> {
+        let start = self.tx_reserve_payload(T::SIZE)?;
+        value.to_parcel_bytes(self.parcel_endian, &mut self.tx_buffer[start..]);
+        return Ok(());
+    }
+
+    /// Write slice of primitive values to TX buffer.
+    /// The buffer grows once for the whole slice, so large arrays are written without reallocation.
+    pub fn tx_write_slice<T: ParcelPrimitive>(&mut self, values: &[T]) {
+        Parcel::expect_payload_written(self.try_tx_write_slice(values));
+    }
+
+    /// Write slice of primitive values to TX buffer, failing like `try_tx_write`.
+    pub fn try_tx_write_slice<T: ParcelPrimitive>(
+        &mut self,
+        values: &[T],
+    ) -> Result<(), ParcelErrorType> {
+        let size = match T::SIZE.checked_mul(values.len()) {
+            Some(s) => s,
+            None => return Err(ParcelErrorType::OutOfBounds),
         };
-        self.tx_write_bytes(&bytes);
+
+        let start = self.tx_reserve_payload(size)?;
+        for (value, bytes) in values
+            .iter()
+            .zip(self.tx_buffer[start..].chunks_exact_mut(T::SIZE))
+        {
+            value.to_parcel_bytes(self.parcel_endian, bytes);
+        }
+        return Ok(());
     }
 
-    /// Write u8 value to TX buffer.
-    pub fn tx_write_u8(&mut self, value: u8) {
+    /// Grow TX buffer by given number of payload bytes and return where they start.
+    fn tx_reserve_payload(&mut self, size: usize) -> Result<usize, ParcelErrorType> {
         if self.tx_buffer_phase != ParcelTxPhase::TopicWritten {
-            panic!("Topic is not written to TX buffer yet.");
+            return Err(ParcelErrorType::OutOfPhase);
         }
 
-        let bytes = match self.parcel_endian {
-            Endian::BigEndian => value.to_be_bytes(),
-            Endian::LittleEndian => value.to_le_bytes(),
-        };
-        self.tx_write_bytes(&bytes);
+        let start = self.tx_buffer.len();
+        match (start - 6).checked_add(size) {
+            Some(payload_size) if payload_size <= MAX_PAYLOAD_SIZE => {}
+            _ => return Err(ParcelErrorType::OutOfBounds),
+        }
+
+        self.tx_buffer.resize(start + size, 0);
+        return Ok(start);
     }
 
-    /// Write i16 value to TX buffer.
-    pub fn tx_write_i16(&mut self, value: i16) {
-        if self.tx_buffer_phase != ParcelTxPhase::TopicWritten {
-            panic!("Topic is not written to TX buffer yet.");
+    fn expect_payload_written(result: Result<(), ParcelErrorType>) {
+        match result {
+            Ok(()) => {}
+            Err(ParcelErrorType::OutOfPhase) => panic!("Topic is not written to TX buffer yet."),
+            Err(_) => panic!("Payload does not fit in a Parcel frame."),
         }
+    }
 
-        let bytes = match self.parcel_endian {
-            Endian::BigEndian => value.to_be_bytes(),
-            Endian::LittleEndian => value.to_le_bytes(),
-        };
-        self.tx_write_bytes(&bytes);
+    /// Write i8 value to TX buffer.
+    pub fn tx_write_i8(&mut self, value: i8) {
+        self.tx_write(value);
+    }
+
+    /// Write u8 value to TX buffer.
+    pub fn tx_write_u8(&mut self, value: u8) {
+        self.tx_write(value);
+    }
+
+    /// Write i16 value to TX buffer.
+    pub fn tx_write_i16(&mut self, value: i16) {
+        self.tx_write(value);
     }
 
     /// Write u16 value to TX buffer.
     pub fn tx_write_u16(&mut self, value: u16) {
-        if self.tx_buffer_phase != ParcelTxPhase::TopicWritten {
-            panic!("Topic is not written to TX buffer yet.");
-        }
-
-        let bytes = match self.parcel_endian {
-            Endian::BigEndian => value.to_be_bytes(),
-            Endian::LittleEndian => value.to_le_bytes(),
-        };
-        self.tx_write_bytes(&bytes);
+        self.tx_write(value);
     }
 
     /// Write i32 value to TX buffer.
     pub fn tx_write_i32(&mut self, value: i32) {
-        if self.tx_buffer_phase != ParcelTxPhase::TopicWritten {
-            panic!("Topic is not written to TX buffer yet.");
-        }
-
-        let bytes = match self.parcel_endian {
-            Endian::BigEndian => value.to_be_bytes(),
-            Endian::LittleEndian => value.to_le_bytes(),
-        };
-        self.tx_write_bytes(&bytes);
+        self.tx_write(value);
     }
 
     /// Write u32 value to TX buffer.
     pub fn tx_write_u32(&mut self, value: u32) {
-        if self.tx_buffer_phase != ParcelTxPhase::TopicWritten {
-            panic!("Topic is not written to TX buffer yet.");
-        }
-
-        let bytes = match self.parcel_endian {
-            Endian::BigEndian => value.to_be_bytes(),
-            Endian::LittleEndian => value.to_le_bytes(),
-        };
-        self.tx_write_bytes(&bytes);
+        self.tx_write(value);
     }
 
     /// Write i64 value to TX buffer.
     pub fn tx_write_i64(&mut self, value: i64) {
-        if self.tx_buffer_phase != ParcelTxPhase::TopicWritten {
-            panic!("Topic is not written to TX buffer yet.");
-        }
-
-        let bytes = match self.parcel_endian {
-            Endian::BigEndian => value.to_be_bytes(),
-            Endian::LittleEndian => value.to_le_bytes(),
-        };
-        self.tx_write_bytes(&bytes);
+        self.tx_write(value);
     }
 
     /// Write u64 value to TX buffer.
     pub fn tx_write_u64(&mut self, value: u64) {
-        if self.tx_buffer_phase != ParcelTxPhase::TopicWritten {
-            panic!("Topic is not written to TX buffer yet.");
-        }
-
-        let bytes = match self.parcel_endian {
-            Endian::BigEndian => value.to_be_bytes(),
-            Endian::LittleEndian => value.to_le_bytes(),
-        };
-        self.tx_write_bytes(&bytes);
+        self.tx_write(value);
     }
 
     /// Write f32 value to TX buffer.
     pub fn tx_write_f32(&mut self, value: f32) {
-        if self.tx_buffer_phase != ParcelTxPhase::TopicWritten {
-            panic!("Topic is not written to TX buffer yet.");
-        }
-
-        let bytes = match self.parcel_endian {
-            Endian::BigEndian => value.to_be_bytes(),
-            Endian::LittleEndian => value.to_le_bytes(),
-        };
-        self.tx_write_bytes(&bytes);
+        self.tx_write(value);
     }
 
     /// Write f64 value to TX buffer.
     pub fn tx_write_f64(&mut self, value: f64) {
-        if self.tx_buffer_phase != ParcelTxPhase::TopicWritten {
-            panic!("Topic is not written to TX buffer yet.");
-        }
-
-        let bytes = match self.parcel_endian {
-            Endian::BigEndian => value.to_be_bytes(),
-            Endian::LittleEndian => value.to_le_bytes(),
-        };
-        self.tx_write_bytes(&bytes);
+        self.tx_write(value);
     }
 
     /// Write string value to TX buffer.
     pub fn tx_write_string(&mut self, value: String) {
-        if self.tx_buffer_phase != ParcelTxPhase::TopicWritten {
-            panic!("Topic is not written to TX buffer yet.");
-        }
+        Parcel::expect_payload_written(self.try_tx_write_string(&value));
+    }
 
+    /// Write string value to TX buffer, failing like `try_tx_write`.
+    pub fn try_tx_write_string(&mut self, value: &str) -> Result<(), ParcelErrorType> {
         let bytes = value.as_bytes();
-        self.tx_write_bytes(bytes);
+        let start = self.tx_reserve_payload(bytes.len())?;
+        self.tx_buffer[start..].copy_from_slice(bytes);
+        return Ok(());
     }
 
     /// Finalize the TX buffer.
     /// Writes the payload size at byte 4 and 5, and the checksum at the end.
     pub fn tx_finalize(&mut self) {
-        if self.tx_buffer_phase != ParcelTxPhase::TopicWritten {
+        if self.try_tx_finalize().is_err() {
             panic!("Topic is not written to TX buffer yet.");
         }
+    }
+
+    /// Finalize the TX buffer. Fails with `OutOfPhase` unless the topic is written.
+    pub fn try_tx_finalize(&mut self) -> Result<(), ParcelErrorType> {
+        if self.tx_buffer_phase != ParcelTxPhase::TopicWritten {
+            return Err(ParcelErrorType::OutOfPhase);
+        }
 
+        // Payload writes are limited to `MAX_PAYLOAD_SIZE`, so the size always fits.
         let payload_size = (self.tx_buffer.len() - 6) as u16;
         let checksum = Parcel::compute_checksum(&self.tx_buffer[6..]);
         let payload_size_bytes = match self.parcel_endian {
@@ -225,14 +262,99 @@ impl<'a> Parcel<'a> {
         self.tx_buffer[5] = payload_size_bytes[1];
         self.tx_buffer.push(checksum);
         self.tx_buffer_phase = ParcelTxPhase::Finalized;
+        return Ok(());
     }
 
     pub fn tx_send(&mut self) {
-        if self.tx_buffer_phase != ParcelTxPhase::Finalized {
+        if self.try_tx_send().is_err() {
             panic!("TX buffer is not finalized.");
         }
+    }
+
+    /// Pass the TX buffer to the TX function. Fails with `OutOfPhase` unless it is finalized.
+    pub fn try_tx_send(&mut self) -> Result<(), ParcelErrorType> {
+        if self.tx_buffer_phase != ParcelTxPhase::Finalized {
+            return Err(ParcelErrorType::OutOfPhase);
+        }
 
         (self.tx_func)(&self.tx_buffer);
+        return Ok(());
+    }
+
+    /// Build a frame for given topic and message, and send it.
+    pub fn publish<TMessage: ParcelMessage>(
+        &mut self,
+        topic: &Topic<TMessage>,
+        message: &TMessage,
+    ) {
+        self.tx_clear();
+        self.tx_write_header();
+        self.tx_write_topic(topic.get_id());
+        message.tx_write_to(self);
+        self.tx_finalize();
+        self.tx_send();
+    }
+
+    /// Register handler called by `rx_dispatch` for every message received on given topic.
+    /// Replaces the previous handler of the topic. Fails if the topic ID is already
+    /// subscribed with a different message type.
+    pub fn subscribe<TMessage, HandlerT>(
+        &mut self,
+        topic: &Topic<TMessage>,
+        handler: HandlerT,
+    ) -> Result<(), TopicRegistryErrorType>
+    where
+        TMessage: ParcelMessage + 'static,
+        HandlerT: FnMut(TMessage) + 'a,
+    {
+        let message_type_id = TypeId::of::<TMessage>();
+        if let Some(subscription) = self.subscriptions.get(&topic.get_id()) {
+            if subscription.message_type_id != message_type_id {
+                return Err(TopicRegistryErrorType::DuplicateTopicId(topic.get_id()));
+            }
+        }
+
+        let mut handler = handler;
+        self.subscriptions.insert(
+            topic.get_id(),
+            ParcelSubscription {
+                message_type_id: message_type_id,
+                handler: Box::new(move |payload: &[u8], parcel_endian: Endian| {
+                    return match TMessage::rx_read_from(payload, parcel_endian) {
+                        Ok(message) => {
+                            handler(message);
+                            true
+                        }
+                        Err(_) => false,
+                    };
+                }),
+            },
+        );
+        return Ok(());
+    }
+
+    /// Remove handler of given topic.
+    pub fn unsubscribe<TMessage>(&mut self, topic: &Topic<TMessage>) {
+        self.subscriptions.remove(&topic.get_id());
+    }
+
+    /// Read every complete frame from RX buffer and pass it to the handler of its topic.
+    /// Frames without a subscriber or with a payload that fails to decode are dropped.
+    /// Returns number of messages delivered to handlers.
+    pub fn rx_dispatch(&mut self) -> usize {
+        let mut delivered_count: usize = 0;
+        while let Some((topic, payload)) = self.rx_read_frame() {
+            let subscription = match self.subscriptions.get_mut(&topic) {
+                Some(s) => s,
+                None => continue,
+            };
+
+            if (subscription.handler)(&payload, self.parcel_endian) {
+                delivered_count += 1;
+            }
+        }
+
+        return delivered_count;
     }
 
     /// Write bytes to TX buffer.
@@ -245,232 +367,260 @@ impl<'a> Parcel<'a> {
     /// Receive data via RX function.
     pub fn rx_receive(&mut self) {
         let rx_data = (self.rx_func)();
-        for byte in rx_data {
-            self.rx_buffer.push_back(byte);
+        self.rx_feed(&rx_data);
+    }
+
+    /// Append bytes received outside of the RX function to RX buffer.
+    pub fn rx_feed(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.rx_buffer.push_back(*byte);
         }
     }
 
-    pub fn rx_read_frame(&mut self) -> Option<(u16, Vec<u8>)> {
-        let mut frame_size: usize = 0;
-        let mut frame_bytes: Vec<u8> = Vec::new();
-        let mut payload_size_bytes: [u8; 2] = [0; 2];
+    /// Get TX buffer. Holds the complete frame once finalized.
+    pub fn tx_buffer(&self) -> &Vec<u8> {
+        return &self.tx_buffer;
+    }
 
+    pub fn rx_read_frame(&mut self) -> Option<(u16, Vec<u8>)> {
         while self.rx_buffer.len() >= 7 {
-            let mut index: usize = 0;
-            let mut out_of_order: bool = false;
-
-            // Pop bytes until correctly-formed metadata is found.
-            for byte in self.rx_buffer.iter() {
-                if index == 0 || index == 1 {
-                    if *byte != 0x55_u8 {
-                        out_of_order = true;
-                        break;
-                    }
-                } else if index == 4 || index == 5 {
-                    payload_size_bytes[index - 4] = *byte;
-                } else if index == 6 {
-                    break;
+            let frame = match Parcel::rx_inspect_frame(self.rx_buffer.iter(), self.parcel_endian) {
+                ParcelFrameCandidate::Incomplete => return None,
+                ParcelFrameCandidate::OutOfOrder => {
+                    self.rx_buffer.pop_front();
+                    continue;
                 }
+                ParcelFrameCandidate::Frame(frame) => frame,
+            };
 
-                index += 1;
+            if !frame.is_checksum_valid() {
+                self.rx_buffer.pop_front();
+                continue;
             }
 
-            if out_of_order {
+            for _ in 0..frame.frame_size() {
                 self.rx_buffer.pop_front();
-                continue;
             }
+            return Some((frame.topic, frame.payload));
+        }
 
-            let payload_size: u16 = match self.parcel_endian {
-                Endian::BigEndian => u16::from_be_bytes(payload_size_bytes),
-                Endian::LittleEndian => u16::from_le_bytes(payload_size_bytes),
-            };
+        return None;
+    }
 
-            frame_size = 6 + payload_size as usize + 1;
+    /// Inspect bytes at the start of given RX stream without consuming them.
+    /// This is the frame detection used by `rx_read_frame`, but frames with an invalid
+    /// checksum are returned as well so that tools can report them.
+    pub fn rx_inspect_frame<'b, I>(bytes: I, parcel_endian: Endian) -> ParcelFrameCandidate
+    where
+        I: ExactSizeIterator<Item = &'b u8>,
+    {
+        let available = bytes.len();
+        if available < 7 {
+            return ParcelFrameCandidate::Incomplete;
+        }
 
-            // If there are less bytes than frame size, terminate the loop.
-            if self.rx_buffer.len() < frame_size as usize {
-                return None;
-            }
+        let mut frame_size: usize = 7;
+        let mut frame_bytes: Vec<u8> = Vec::with_capacity(frame_size);
+        let mut bytes = bytes;
 
-            frame_bytes.resize(frame_size, 0);
-            for (index, byte) in zip(0..frame_size, self.rx_buffer.iter()) {
-                *frame_bytes.get_mut(index).unwrap() = *byte;
-            }
+        // Read metadata and check that the header is correctly formed.
+        for byte in bytes.by_ref().take(6) {
+            frame_bytes.push(*byte);
+        }
+        if frame_bytes[0] != 0x55_u8 || frame_bytes[1] != 0x55_u8 {
+            return ParcelFrameCandidate::OutOfOrder;
+        }
 
-            let received_checksum = *frame_bytes.last().unwrap();
-            let computed_checksum =
-                Parcel::compute_checksum(&frame_bytes[6..frame_bytes.len() - 1]);
-            if received_checksum != computed_checksum {
-                self.rx_buffer.pop_front();
-                continue;
-            }
+        let topic_bytes: [u8; 2] = [frame_bytes[2], frame_bytes[3]];
+        let payload_size_bytes: [u8; 2] = [frame_bytes[4], frame_bytes[5]];
+        let (topic, payload_size) = match parcel_endian {
+            Endian::BigEndian => (
+                u16::from_be_bytes(topic_bytes),
+                u16::from_be_bytes(payload_size_bytes),
+            ),
+            Endian::LittleEndian => (
+                u16::from_le_bytes(topic_bytes),
+                u16::from_le_bytes(payload_size_bytes),
+            ),
+        };
 
-            let topic_bytes: [u8; 2] = [*frame_bytes.get(2).unwrap(), *frame_bytes.get(3).unwrap()];
+        frame_size += payload_size as usize;
 
-            let topic = match self.parcel_endian {
-                Endian::BigEndian => u16::from_be_bytes(topic_bytes),
-                Endian::LittleEndian => u16::from_le_bytes(topic_bytes),
-            };
+        // If there are less bytes than frame size, wait for more bytes.
+        if available < frame_size {
+            return ParcelFrameCandidate::Incomplete;
+        }
 
-            let payload = Vec::from(&frame_bytes[6..frame_bytes.len() - 1]);
-            for _ in 0..frame_size {
-                self.rx_buffer.pop_front();
-            }
-            return Some((topic, payload));
+        let payload: Vec<u8> = bytes
+            .by_ref()
+            .take(payload_size as usize)
+            .copied()
+            .collect();
+        let received_checksum = *bytes.next().unwrap();
+        let computed_checksum = Parcel::compute_checksum(&payload);
+
+        return ParcelFrameCandidate::Frame(ParcelFrame {
+            topic: topic,
+            payload: payload,
+            received_checksum: received_checksum,
+            computed_checksum: computed_checksum,
+        });
+    }
+
+    /// Read primitive value from payload at given offset.
+    pub fn rx_read<T: ParcelPrimitive>(
+        payload: &[u8],
+        offset: usize,
+        as_endian: Endian,
+    ) -> Result<T, ParcelErrorType> {
+        let bytes = Parcel::rx_payload_range(payload, offset, T::SIZE)?;
+        return T::from_parcel_bytes(bytes, as_endian);
+    }
+
+    /// Read given number of consecutive primitive values from payload at given offset.
+    pub fn rx_read_slice<T: ParcelPrimitive>(
+        payload: &[u8],
+        offset: usize,
+        count: usize,
+        as_endian: Endian,
+    ) -> Result<Vec<T>, ParcelErrorType> {
+        // The count usually comes off the wire, so check it against the payload before
+        // allocating anything.
+        let size = match T::SIZE.checked_mul(count) {
+            Some(s) => s,
+            None => return Err(ParcelErrorType::OutOfBounds),
+        };
+        let bytes = Parcel::rx_payload_range(payload, offset, size)?;
+
+        let mut values: Vec<T> = Vec::with_capacity(count);
+        for bytes in bytes.chunks_exact(T::SIZE) {
+            values.push(T::from_parcel_bytes(bytes, as_endian)?);
         }
+        return Ok(values);
+    }
 
-        return None;
+    /// Fill given slice with consecutive primitive values read from payload at given offset.
+    pub fn rx_read_into<T: ParcelPrimitive>(
+        payload: &[u8],
+        offset: usize,
+        values: &mut [T],
+        as_endian: Endian,
+    ) -> Result<(), ParcelErrorType> {
+        let size = match T::SIZE.checked_mul(values.len()) {
+            Some(s) => s,
+            None => return Err(ParcelErrorType::OutOfBounds),
+        };
+        let bytes = Parcel::rx_payload_range(payload, offset, size)?;
+
+        for (value, bytes) in values.iter_mut().zip(bytes.chunks_exact(T::SIZE)) {
+            *value = T::from_parcel_bytes(bytes, as_endian)?;
+        }
+        return Ok(());
     }
 
     pub fn rx_read_i8(
-        payload: &Vec<u8>,
+        payload: &[u8],
         offset: usize,
         as_endian: Endian,
     ) -> Result<i8, ParcelErrorType> {
-        let mut bytes = Parcel::rx_copy_bytes::<1>(payload, offset)?;
-        return Ok(match as_endian {
-            Endian::BigEndian => i8::from_be_bytes(bytes),
-            Endian::LittleEndian => i8::from_le_bytes(bytes),
-        });
+        return Parcel::rx_read(payload, offset, as_endian);
     }
 
     pub fn rx_read_u8(
-        payload: &Vec<u8>,
+        payload: &[u8],
         offset: usize,
         as_endian: Endian,
     ) -> Result<u8, ParcelErrorType> {
-        let mut bytes = Parcel::rx_copy_bytes::<1>(payload, offset)?;
-        return Ok(match as_endian {
-            Endian::BigEndian => u8::from_be_bytes(bytes),
-            Endian::LittleEndian => u8::from_le_bytes(bytes),
-        });
+        return Parcel::rx_read(payload, offset, as_endian);
     }
 
     pub fn rx_read_i16(
-        payload: &Vec<u8>,
+        payload: &[u8],
         offset: usize,
         as_endian: Endian,
     ) -> Result<i16, ParcelErrorType> {
-        let mut bytes = Parcel::rx_copy_bytes::<2>(payload, offset)?;
-        return Ok(match as_endian {
-            Endian::BigEndian => i16::from_be_bytes(bytes),
-            Endian::LittleEndian => i16::from_le_bytes(bytes),
-        });
+        return Parcel::rx_read(payload, offset, as_endian);
     }
 
     pub fn rx_read_u16(
-        payload: &Vec<u8>,
+        payload: &[u8],
         offset: usize,
         as_endian: Endian,
     ) -> Result<u16, ParcelErrorType> {
-        let mut bytes = Parcel::rx_copy_bytes::<2>(payload, offset)?;
-        return Ok(match as_endian {
-            Endian::BigEndian => u16::from_be_bytes(bytes),
-            Endian::LittleEndian => u16::from_le_bytes(bytes),
-        });
+        return Parcel::rx_read(payload, offset, as_endian);
     }
 
     pub fn rx_read_i32(
-        payload: &Vec<u8>,
+        payload: &[u8],
         offset: usize,
         as_endian: Endian,
     ) -> Result<i32, ParcelErrorType> {
-        let mut bytes = Parcel::rx_copy_bytes::<4>(payload, offset)?;
-        return Ok(match as_endian {
-            Endian::BigEndian => i32::from_be_bytes(bytes),
-            Endian::LittleEndian => i32::from_le_bytes(bytes),
-        });
+        return Parcel::rx_read(payload, offset, as_endian);
     }
 
     pub fn rx_read_u32(
-        payload: &Vec<u8>,
+        payload: &[u8],
         offset: usize,
         as_endian: Endian,
     ) -> Result<u32, ParcelErrorType> {
-        let mut bytes = Parcel::rx_copy_bytes::<4>(payload, offset)?;
-        return Ok(match as_endian {
-            Endian::BigEndian => u32::from_be_bytes(bytes),
-            Endian::LittleEndian => u32::from_le_bytes(bytes),
-        });
+        return Parcel::rx_read(payload, offset, as_endian);
     }
 
     pub fn rx_read_i64(
-        payload: &Vec<u8>,
+        payload: &[u8],
         offset: usize,
         as_endian: Endian,
     ) -> Result<i64, ParcelErrorType> {
-        let mut bytes = Parcel::rx_copy_bytes::<8>(payload, offset)?;
-        return Ok(match as_endian {
-            Endian::BigEndian => i64::from_be_bytes(bytes),
-            Endian::LittleEndian => i64::from_le_bytes(bytes),
-        });
+        return Parcel::rx_read(payload, offset, as_endian);
     }
 
     pub fn rx_read_u64(
-        payload: &Vec<u8>,
+        payload: &[u8],
         offset: usize,
         as_endian: Endian,
     ) -> Result<u64, ParcelErrorType> {
-        let mut bytes = Parcel::rx_copy_bytes::<8>(payload, offset)?;
-        return Ok(match as_endian {
-            Endian::BigEndian => u64::from_be_bytes(bytes),
-            Endian::LittleEndian => u64::from_le_bytes(bytes),
-        });
+        return Parcel::rx_read(payload, offset, as_endian);
     }
 
     pub fn rx_read_f32(
-        payload: &Vec<u8>,
+        payload: &[u8],
         offset: usize,
         as_endian: Endian,
     ) -> Result<f32, ParcelErrorType> {
-        let mut bytes = Parcel::rx_copy_bytes::<4>(payload, offset)?;
-        return Ok(match as_endian {
-            Endian::BigEndian => f32::from_be_bytes(bytes),
-            Endian::LittleEndian => f32::from_le_bytes(bytes),
-        });
+        return Parcel::rx_read(payload, offset, as_endian);
     }
 
     pub fn rx_read_f64(
-        payload: &Vec<u8>,
+        payload: &[u8],
         offset: usize,
         as_endian: Endian,
     ) -> Result<f64, ParcelErrorType> {
-        let mut bytes = Parcel::rx_copy_bytes::<8>(payload, offset)?;
-        return Ok(match as_endian {
-            Endian::BigEndian => f64::from_be_bytes(bytes),
-            Endian::LittleEndian => f64::from_le_bytes(bytes),
-        });
+        return Parcel::rx_read(payload, offset, as_endian);
     }
 
     pub fn rx_read_string(
-        payload: &Vec<u8>,
+        payload: &[u8],
         offset: usize,
         length: usize,
     ) -> Result<String, ParcelErrorType> {
-        if offset + length > payload.len() {
-            return Err(ParcelErrorType::OutOfBounds);
-        }
-
-        let string_bytes = Vec::from(&payload[offset..(offset + length)]);
+        let string_bytes = Vec::from(Parcel::rx_payload_range(payload, offset, length)?);
         match String::from_utf8(string_bytes) {
             Ok(string) => return Ok(string),
             Err(_) => return Err(ParcelErrorType::InvalidData),
         };
     }
 
-    fn rx_copy_bytes<const SIZE: usize>(
-        payload: &Vec<u8>,
+    /// Bytes of payload in given range, failing with `OutOfBounds` for ranges past its end,
+    /// including ranges whose end does not fit in `usize`.
+    fn rx_payload_range(
+        payload: &[u8],
         offset: usize,
-    ) -> Result<[u8; SIZE], ParcelErrorType> {
-        if offset + SIZE > payload.len() {
-            return Err(ParcelErrorType::OutOfBounds);
-        }
-
-        let mut bytes = [0_u8; SIZE];
-        for i in 0..SIZE {
-            bytes[i] = payload[offset + i];
-        }
-        return Ok(bytes);
+        size: usize,
+    ) -> Result<&[u8], ParcelErrorType> {
+        return match offset.checked_add(size) {
+            Some(end) if end <= payload.len() => Ok(&payload[offset..end]),
+            _ => Err(ParcelErrorType::OutOfBounds),
+        };
     }
 
     /// Compute checksum of given bytes.
@@ -478,7 +628,7 @@ impl<'a> Parcel<'a> {
         let mut checksum = Wrapping(0xFF_u8);
         for byte in bytes {
             let b = Wrapping(*byte);
-            checksum = checksum ^ b;
+            checksum ^= b;
         }
 
         return checksum.0;
@@ -492,3 +642,393 @@ enum ParcelTxPhase {
     TopicWritten,
     Finalized,
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Pose {
+        x: f64,
+        y: f64,
+        id: u16,
+    }
+
+    impl ParcelMessage for Pose {
+        fn tx_write_to(&self, parcel: &mut Parcel) {
+            parcel.tx_write(self.x);
+            parcel.tx_write(self.y);
+            parcel.tx_write(self.id);
+        }
+
+        fn rx_read_from(payload: &[u8], parcel_endian: Endian) -> Result<Self, ParcelErrorType> {
+            return Ok(Pose {
+                x: Parcel::rx_read(payload, 0, parcel_endian)?,
+                y: Parcel::rx_read(payload, 8, parcel_endian)?,
+                id: Parcel::rx_read(payload, 16, parcel_endian)?,
+            });
+        }
+    }
+
+    struct Status {
+        code: u8,
+    }
+
+    impl ParcelMessage for Status {
+        fn tx_write_to(&self, parcel: &mut Parcel) {
+            parcel.tx_write(self.code);
+        }
+
+        fn rx_read_from(payload: &[u8], parcel_endian: Endian) -> Result<Self, ParcelErrorType> {
+            return Ok(Status {
+                code: Parcel::rx_read(payload, 0, parcel_endian)?,
+            });
+        }
+    }
+
+    const POSE_TOPIC: Topic<Pose> = Topic::new(16, "pose");
+    const STATUS_TOPIC: Topic<Status> = Topic::new(17, "status");
+
+    /// Parcel whose sent frames are collected in the returned list.
+    fn recording_parcel<'a>(parcel_endian: Endian) -> (Parcel<'a>, Rc<RefCell<Vec<Vec<u8>>>>) {
+        let sent: Rc<RefCell<Vec<Vec<u8>>>> = Rc::new(RefCell::new(Vec::new()));
+        let sent_by_tx = sent.clone();
+        let parcel = Parcel::new(
+            parcel_endian,
+            move |bytes: &Vec<u8>| sent_by_tx.borrow_mut().push(bytes.clone()),
+            Vec::new,
+        );
+        return (parcel, sent);
+    }
+
+    /// Parcel whose handler for `POSE_TOPIC` collects messages in the returned list.
+    fn pose_receiver<'a>(parcel_endian: Endian) -> (Parcel<'a>, Rc<RefCell<Vec<Pose>>>) {
+        let received: Rc<RefCell<Vec<Pose>>> = Rc::new(RefCell::new(Vec::new()));
+        let received_by_handler = received.clone();
+        let mut parcel = Parcel::new(parcel_endian, |_: &Vec<u8>| {}, Vec::new);
+        parcel
+            .subscribe(&POSE_TOPIC, move |pose: Pose| {
+                received_by_handler.borrow_mut().push(pose)
+            })
+            .unwrap();
+        return (parcel, received);
+    }
+
+    #[test]
+    fn published_messages_are_dispatched_to_subscriber() {
+        for parcel_endian in [Endian::LittleEndian, Endian::BigEndian] {
+            let (mut sender, sent) = recording_parcel(parcel_endian);
+            let (mut receiver, received) = pose_receiver(parcel_endian);
+
+            let poses = vec![
+                Pose {
+                    x: 1.5,
+                    y: -2.0,
+                    id: 7,
+                },
+                Pose {
+                    x: 0.0,
+                    y: 1e9,
+                    id: 65535,
+                },
+            ];
+            for pose in poses.iter() {
+                sender.publish(&POSE_TOPIC, pose);
+            }
+            assert_eq!(sent.borrow().len(), 2);
+            assert_eq!(sent.borrow()[0].len(), 6 + 18 + 1);
+
+            for frame in sent.borrow().iter() {
+                receiver.rx_feed(frame);
+            }
+            assert_eq!(receiver.rx_dispatch(), 2);
+            assert_eq!(*received.borrow(), poses);
+            assert_eq!(receiver.rx_dispatch(), 0);
+        }
+    }
+
+    #[test]
+    fn dispatch_drops_unsubscribed_corrupt_and_undecodable_frames() {
+        let (mut sender, sent) = recording_parcel(Endian::LittleEndian);
+        let (mut receiver, received) = pose_receiver(Endian::LittleEndian);
+        let pose = Pose {
+            x: 3.0,
+            y: 4.0,
+            id: 1,
+        };
+
+        // No subscriber for the status topic.
+        sender.publish(&STATUS_TOPIC, &Status { code: 2 });
+        // Checksum mismatch.
+        sender.publish(&POSE_TOPIC, &pose);
+        sent.borrow_mut()[1][8] ^= 0x01;
+        // Payload too short for a pose.
+        sender.publish(
+            &Topic::<Status>::new(POSE_TOPIC.get_id(), "short"),
+            &Status { code: 3 },
+        );
+        // Garbage followed by a valid frame.
+        sender.publish(&POSE_TOPIC, &pose);
+
+        receiver.rx_feed(&sent.borrow()[0]);
+        receiver.rx_feed(&sent.borrow()[1]);
+        receiver.rx_feed(&sent.borrow()[2]);
+        receiver.rx_feed(&[0x00, 0x55, 0x12]);
+        receiver.rx_feed(&sent.borrow()[3]);
+
+        assert_eq!(receiver.rx_dispatch(), 1);
+        assert_eq!(*received.borrow(), vec![pose]);
+    }
+
+    #[test]
+    fn subscribe_replaces_handler_and_rejects_other_message_type() {
+        let (mut sender, sent) = recording_parcel(Endian::LittleEndian);
+        let (mut receiver, first_received) = pose_receiver(Endian::LittleEndian);
+
+        let second_count = Rc::new(RefCell::new(0));
+        let second_count_by_handler = second_count.clone();
+        receiver
+            .subscribe(&POSE_TOPIC, move |_: Pose| {
+                *second_count_by_handler.borrow_mut() += 1
+            })
+            .unwrap();
+
+        let conflicting_topic: Topic<Status> = Topic::new(POSE_TOPIC.get_id(), "conflict");
+        assert_eq!(
+            receiver.subscribe(&conflicting_topic, |_: Status| {}).err(),
+            Some(TopicRegistryErrorType::DuplicateTopicId(
+                POSE_TOPIC.get_id()
+            ))
+        );
+
+        let pose = Pose {
+            x: 1.0,
+            y: 1.0,
+            id: 1,
+        };
+        sender.publish(&POSE_TOPIC, &pose);
+        receiver.rx_feed(&sent.borrow()[0]);
+        assert_eq!(receiver.rx_dispatch(), 1);
+        assert!(first_received.borrow().is_empty());
+        assert_eq!(*second_count.borrow(), 1);
+
+        // Once unsubscribed, the topic ID may be used with another message type.
+        receiver.unsubscribe(&POSE_TOPIC);
+        receiver.rx_feed(&sent.borrow()[0]);
+        assert_eq!(receiver.rx_dispatch(), 0);
+        assert!(receiver
+            .subscribe(&conflicting_topic, |_: Status| {})
+            .is_ok());
+    }
+
+    /// Payload of a frame holding the values written by given function.
+    fn write_payload<WriteFuncT>(parcel_endian: Endian, write_func: WriteFuncT) -> Vec<u8>
+    where
+        WriteFuncT: FnOnce(&mut Parcel),
+    {
+        let (mut parcel, _) = recording_parcel(parcel_endian);
+        parcel.tx_write_header();
+        parcel.tx_write_topic(1);
+        write_func(&mut parcel);
+        parcel.tx_finalize();
+
+        let frame = parcel.tx_buffer();
+        return frame[6..frame.len() - 1].to_vec();
+    }
+
+    fn assert_round_trip<T>(values: &[T])
+    where
+        T: ParcelPrimitive + PartialEq + std::fmt::Debug,
+    {
+        for parcel_endian in [
+            Endian::LittleEndian,
+            Endian::BigEndian,
+            Endian::Native,
+            Endian::Network,
+        ] {
+            let payload = write_payload(parcel_endian, |parcel| {
+                for value in values {
+                    parcel.tx_write(*value);
+                }
+                parcel.tx_write_slice(values);
+            });
+            assert_eq!(payload.len(), 2 * T::SIZE * values.len());
+
+            for (i, value) in values.iter().enumerate() {
+                let read: T = Parcel::rx_read(&payload, i * T::SIZE, parcel_endian).unwrap();
+                assert_eq!(read, *value);
+            }
+            let offset = T::SIZE * values.len();
+            let read: Vec<T> =
+                Parcel::rx_read_slice(&payload, offset, values.len(), parcel_endian).unwrap();
+            assert_eq!(read, values);
+
+            let mut read_into: Vec<T> = vec![values[0]; values.len()];
+            Parcel::rx_read_into(&payload, offset, &mut read_into, parcel_endian).unwrap();
+            assert_eq!(read_into, values);
+        }
+    }
+
+    #[test]
+    fn primitives_round_trip_in_every_byte_order() {
+        assert_round_trip(&[i8::MIN, -1, 0, i8::MAX]);
+        assert_round_trip(&[0_u8, 0x7F, u8::MAX]);
+        assert_round_trip(&[i16::MIN, -2, i16::MAX]);
+        assert_round_trip(&[0_u16, 0x0102, u16::MAX]);
+        assert_round_trip(&[i32::MIN, -3, i32::MAX]);
+        assert_round_trip(&[0_u32, 0x0102_0304, u32::MAX]);
+        assert_round_trip(&[i64::MIN, -4, i64::MAX]);
+        assert_round_trip(&[0_u64, 0x0102_0304_0506_0708, u64::MAX]);
+        assert_round_trip(&[i128::MIN, -5, i128::MAX]);
+        assert_round_trip(&[0_u128, 0x0102_0304_0506_0708_090A_0B0C_0D0E_0F10, u128::MAX]);
+        assert_round_trip(&[f32::MIN, -0.5, 0.0, f32::INFINITY, f32::MAX]);
+        assert_round_trip(&[f64::MIN, -0.25, f64::MIN_POSITIVE, f64::NEG_INFINITY]);
+        assert_round_trip(&[true, false, true]);
+    }
+
+    #[test]
+    fn primitives_are_written_in_requested_byte_order() {
+        let little = write_payload(Endian::LittleEndian, |p| p.tx_write(0x0102_0304_u32));
+        assert_eq!(little, vec![0x04, 0x03, 0x02, 0x01]);
+        let big = write_payload(Endian::BigEndian, |p| p.tx_write(0x0102_0304_u32));
+        assert_eq!(big, vec![0x01, 0x02, 0x03, 0x04]);
+
+        assert_eq!(
+            write_payload(Endian::Network, |p| p.tx_write(0x0102_u16)),
+            vec![0x01, 0x02]
+        );
+        assert_eq!(
+            write_payload(Endian::Native, |p| p.tx_write(0x0102_u16)),
+            0x0102_u16.to_ne_bytes().to_vec()
+        );
+
+        let wide = write_payload(Endian::BigEndian, |p| p.tx_write(1_u128));
+        assert_eq!(wide.len(), 16);
+        assert_eq!(wide[15], 1);
+        assert!(wide[..15].iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn invalid_bool_byte_is_invalid_data() {
+        assert_eq!(
+            Parcel::rx_read::<bool>(&[0x02], 0, Endian::LittleEndian),
+            Err(ParcelErrorType::InvalidData)
+        );
+        assert_eq!(
+            Parcel::rx_read_slice::<bool>(&[0x01, 0xFF], 0, 2, Endian::LittleEndian),
+            Err(ParcelErrorType::InvalidData)
+        );
+    }
+
+    #[test]
+    fn reads_past_payload_are_out_of_bounds() {
+        let payload: Vec<u8> = vec![0; 8];
+        let endian = Endian::LittleEndian;
+        assert_eq!(
+            Parcel::rx_read::<u32>(&payload, 5, endian),
+            Err(ParcelErrorType::OutOfBounds)
+        );
+        assert_eq!(
+            Parcel::rx_read::<u8>(&payload, usize::MAX, endian),
+            Err(ParcelErrorType::OutOfBounds)
+        );
+        assert_eq!(
+            Parcel::rx_read_slice::<u16>(&payload, 2, 4, endian),
+            Err(ParcelErrorType::OutOfBounds)
+        );
+        assert_eq!(
+            Parcel::rx_read_string(&payload, usize::MAX, 2),
+            Err(ParcelErrorType::OutOfBounds)
+        );
+        assert_eq!(
+            Parcel::rx_read_slice::<u16>(&payload, 8, 0, endian),
+            Ok(Vec::new())
+        );
+    }
+
+    #[test]
+    fn hostile_counts_do_not_overflow() {
+        let payload: Vec<u8> = vec![0; 8];
+        let endian = Endian::LittleEndian;
+
+        // Wraps around to a small size if multiplied unchecked.
+        let wrapping_count = usize::MAX / 4 + 2;
+        assert_eq!(
+            Parcel::rx_read_slice::<u32>(&payload, 0, wrapping_count, endian),
+            Err(ParcelErrorType::OutOfBounds)
+        );
+        assert_eq!(
+            Parcel::rx_read_slice::<u128>(&payload, 0, usize::MAX, endian),
+            Err(ParcelErrorType::OutOfBounds)
+        );
+        assert_eq!(
+            Parcel::rx_read_slice::<u8>(&payload, 4, usize::MAX - 2, endian),
+            Err(ParcelErrorType::OutOfBounds)
+        );
+
+        let mut values: Vec<u32> = vec![0; 3];
+        assert_eq!(
+            Parcel::rx_read_into(&payload, usize::MAX - 4, &mut values, endian),
+            Err(ParcelErrorType::OutOfBounds)
+        );
+    }
+
+    #[test]
+    fn tx_writes_check_phase_and_frame_size() {
+        let (mut parcel, _) = recording_parcel(Endian::LittleEndian);
+        assert_eq!(parcel.try_tx_write(1_u8), Err(ParcelErrorType::OutOfPhase));
+        assert_eq!(
+            parcel.try_tx_write_slice(&[1_u8]),
+            Err(ParcelErrorType::OutOfPhase)
+        );
+
+        parcel.tx_write_header();
+        parcel.tx_write_topic(1);
+        let values: Vec<u16> = vec![0; MAX_PAYLOAD_SIZE / 2];
+        assert_eq!(parcel.try_tx_write_slice(&values), Ok(()));
+        assert_eq!(
+            parcel.try_tx_write(1_u16),
+            Err(ParcelErrorType::OutOfBounds)
+        );
+        assert_eq!(parcel.try_tx_write(1_u8), Ok(()));
+        assert_eq!(parcel.try_tx_write(1_u8), Err(ParcelErrorType::OutOfBounds));
+        assert_eq!(parcel.tx_buffer().len(), 6 + MAX_PAYLOAD_SIZE);
+    }
+
+    #[test]
+    fn try_tx_calls_report_wrong_phase() {
+        let (mut parcel, frames) = recording_parcel(Endian::BigEndian);
+        assert_eq!(
+            parcel.try_tx_write_topic(1),
+            Err(ParcelErrorType::OutOfPhase)
+        );
+        assert_eq!(
+            parcel.try_tx_write_string("a"),
+            Err(ParcelErrorType::OutOfPhase)
+        );
+        assert_eq!(parcel.try_tx_finalize(), Err(ParcelErrorType::OutOfPhase));
+        assert_eq!(parcel.try_tx_send(), Err(ParcelErrorType::OutOfPhase));
+
+        assert_eq!(parcel.try_tx_write_header(), Ok(()));
+        assert_eq!(
+            parcel.try_tx_write_header(),
+            Err(ParcelErrorType::OutOfPhase)
+        );
+        assert_eq!(parcel.try_tx_write_topic(0x0102), Ok(()));
+        assert_eq!(parcel.try_tx_write_string("ab"), Ok(()));
+        assert_eq!(parcel.try_tx_send(), Err(ParcelErrorType::OutOfPhase));
+        assert_eq!(parcel.try_tx_finalize(), Ok(()));
+        assert_eq!(parcel.try_tx_write(1_u8), Err(ParcelErrorType::OutOfPhase));
+        assert_eq!(parcel.try_tx_send(), Ok(()));
+
+        let checksum = Parcel::compute_checksum(b"ab");
+        assert_eq!(
+            *frames.borrow(),
+            vec![vec![
+                0x55, 0x55, 0x01, 0x02, 0x00, 0x02, b'a', b'b', checksum
+            ]]
+        );
+    }
+}
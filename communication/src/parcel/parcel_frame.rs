@@ -0,0 +1,29 @@
+/// Frame found in an RX stream, with both the received and the computed checksum.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParcelFrame {
+    pub topic: u16,
+    pub payload: Vec<u8>,
+    pub received_checksum: u8,
+    pub computed_checksum: u8,
+}
+
+impl ParcelFrame {
+    pub fn is_checksum_valid(&self) -> bool {
+        return self.received_checksum == self.computed_checksum;
+    }
+
+    /// Size of the whole frame including header, topic, payload size and checksum.
+    pub fn frame_size(&self) -> usize {
+        return 6 + self.payload.len() + 1;
+    }
+}
+
+/// Result of inspecting the start of an RX stream.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParcelFrameCandidate {
+    /// Not enough bytes to decide yet.
+    Incomplete,
+    /// The stream does not start with a frame header; skip one byte.
+    OutOfOrder,
+    Frame(ParcelFrame),
+}
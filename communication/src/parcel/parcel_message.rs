@@ -0,0 +1,12 @@
+use core::enums::endian::Endian;
+
+use super::{parcel::Parcel, parcel_error_type::ParcelErrorType};
+
+/// Message type that can be sent as the payload of a Parcel frame.
+pub trait ParcelMessage: Sized {
+    /// Write the message to TX buffer. Called after the topic is written.
+    fn tx_write_to(&self, parcel: &mut Parcel);
+
+    /// Read the message from a received payload.
+    fn rx_read_from(payload: &[u8], parcel_endian: Endian) -> Result<Self, ParcelErrorType>;
+}
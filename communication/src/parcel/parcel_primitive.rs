@@ -0,0 +1,64 @@
+use core::enums::endian::Endian;
+
+use super::parcel_error_type::ParcelErrorType;
+
+/// Fixed-size value that can be written to and read from a Parcel payload.
+pub trait ParcelPrimitive: Sized + Copy {
+    /// Number of bytes the value occupies in the payload.
+    const SIZE: usize;
+
+    /// Write the value to `bytes`, which is exactly `SIZE` bytes long.
+    fn to_parcel_bytes(self, as_endian: Endian, bytes: &mut [u8]);
+
+    /// Read the value from `bytes`, which is exactly `SIZE` bytes long.
+    fn from_parcel_bytes(bytes: &[u8], as_endian: Endian) -> Result<Self, ParcelErrorType>;
+}
+
+macro_rules! impl_parcel_primitive {
+    ($($t:ty),*) => {
+        $(
+            impl ParcelPrimitive for $t {
+                const SIZE: usize = std::mem::size_of::<$t>();
+
+                fn to_parcel_bytes(self, as_endian: Endian, bytes: &mut [u8]) {
+                    let value_bytes = match as_endian {
+                        Endian::BigEndian => self.to_be_bytes(),
+                        Endian::LittleEndian => self.to_le_bytes(),
+                    };
+                    bytes.copy_from_slice(&value_bytes);
+                }
+
+                fn from_parcel_bytes(bytes: &[u8], as_endian: Endian) -> Result<Self, ParcelErrorType> {
+                    let value_bytes: [u8; std::mem::size_of::<$t>()] = match bytes.try_into() {
+                        Ok(b) => b,
+                        Err(_) => return Err(ParcelErrorType::OutOfBounds),
+                    };
+                    return Ok(match as_endian {
+                        Endian::BigEndian => <$t>::from_be_bytes(value_bytes),
+                        Endian::LittleEndian => <$t>::from_le_bytes(value_bytes),
+                    });
+                }
+            }
+        )*
+    };
+}
+
+impl_parcel_primitive!(i8, u8, i16, u16, i32, u32, i64, u64, i128, u128, f32, f64);
+
+/// Booleans are written as one byte, 0x00 or 0x01. Any other byte is invalid data.
+impl ParcelPrimitive for bool {
+    const SIZE: usize = 1;
+
+    fn to_parcel_bytes(self, _as_endian: Endian, bytes: &mut [u8]) {
+        bytes[0] = self as u8;
+    }
+
+    fn from_parcel_bytes(bytes: &[u8], _as_endian: Endian) -> Result<Self, ParcelErrorType> {
+        return match bytes {
+            [0x00_u8] => Ok(false),
+            [0x01_u8] => Ok(true),
+            [_] => Err(ParcelErrorType::InvalidData),
+            _ => Err(ParcelErrorType::OutOfBounds),
+        };
+    }
+}
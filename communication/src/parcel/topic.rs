@@ -0,0 +1,35 @@
+use std::marker::PhantomData;
+
+/// Topic ID bound to the message type sent on it.
+/// Topics are meant to be declared once as constants and shared by publishers and subscribers.
+pub struct Topic<TMessage> {
+    id: u16,
+    name: &'static str,
+    message: PhantomData<fn() -> TMessage>,
+}
+
+impl<TMessage> Topic<TMessage> {
+    pub const fn new(id: u16, name: &'static str) -> Self {
+        return Self {
+            id: id,
+            name: name,
+            message: PhantomData,
+        };
+    }
+
+    pub fn get_id(&self) -> u16 {
+        return self.id;
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        return self.name;
+    }
+}
+
+impl<TMessage> Clone for Topic<TMessage> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<TMessage> Copy for Topic<TMessage> {}
@@ -0,0 +1,125 @@
+use std::{
+    any::{type_name, TypeId},
+    collections::HashMap,
+};
+
+use super::{topic::Topic, topic_registry_error_type::TopicRegistryErrorType};
+
+/// Registry of every topic used by an application.
+/// Registering all topics at startup catches two message types sharing one topic ID.
+pub struct TopicRegistry {
+    topics: HashMap<u16, TopicEntry>,
+}
+
+struct TopicEntry {
+    name: &'static str,
+    message_type_id: TypeId,
+    message_type_name: &'static str,
+}
+
+impl TopicRegistry {
+    pub fn new() -> Self {
+        return Self {
+            topics: HashMap::new(),
+        };
+    }
+
+    /// Register topic. Registering the same topic twice is allowed,
+    /// but reusing its ID for another name or message type is an error.
+    pub fn register<TMessage: 'static>(
+        &mut self,
+        topic: &Topic<TMessage>,
+    ) -> Result<(), TopicRegistryErrorType> {
+        let message_type_id = TypeId::of::<TMessage>();
+        if let Some(entry) = self.topics.get(&topic.get_id()) {
+            if entry.name == topic.get_name() && entry.message_type_id == message_type_id {
+                return Ok(());
+            }
+            return Err(TopicRegistryErrorType::DuplicateTopicId(topic.get_id()));
+        }
+
+        self.topics.insert(
+            topic.get_id(),
+            TopicEntry {
+                name: topic.get_name(),
+                message_type_id: message_type_id,
+                message_type_name: type_name::<TMessage>(),
+            },
+        );
+        return Ok(());
+    }
+
+    pub fn contains(&self, topic_id: u16) -> bool {
+        return self.topics.contains_key(&topic_id);
+    }
+
+    pub fn get_topic_count(&self) -> usize {
+        return self.topics.len();
+    }
+
+    pub fn get_topic_name(&self, topic_id: u16) -> Option<&'static str> {
+        return self.topics.get(&topic_id).map(|e| e.name);
+    }
+
+    pub fn get_message_type_name(&self, topic_id: u16) -> Option<&'static str> {
+        return self.topics.get(&topic_id).map(|e| e.message_type_name);
+    }
+}
+
+impl Default for TopicRegistry {
+    fn default() -> Self {
+        return TopicRegistry::new();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Pose;
+    struct Status;
+
+    const POSE_TOPIC: Topic<Pose> = Topic::new(16, "pose");
+
+    #[test]
+    fn registering_same_topic_twice_is_allowed() {
+        let mut registry = TopicRegistry::new();
+        assert_eq!(registry.register(&POSE_TOPIC), Ok(()));
+        assert_eq!(registry.register(&POSE_TOPIC), Ok(()));
+        assert_eq!(registry.get_topic_count(), 1);
+
+        assert!(registry.contains(16));
+        assert!(!registry.contains(17));
+        assert_eq!(registry.get_topic_name(16), Some("pose"));
+        assert_eq!(registry.get_topic_name(17), None);
+        assert_eq!(
+            registry.get_message_type_name(16),
+            Some(type_name::<Pose>())
+        );
+    }
+
+    #[test]
+    fn reusing_topic_id_is_rejected() {
+        let mut registry = TopicRegistry::new();
+        registry.register(&POSE_TOPIC).unwrap();
+
+        let renamed_topic: Topic<Pose> = Topic::new(16, "pose_estimate");
+        assert_eq!(
+            registry.register(&renamed_topic),
+            Err(TopicRegistryErrorType::DuplicateTopicId(16))
+        );
+        let retyped_topic: Topic<Status> = Topic::new(16, "pose");
+        assert_eq!(
+            registry.register(&retyped_topic),
+            Err(TopicRegistryErrorType::DuplicateTopicId(16))
+        );
+
+        // The first registration is kept.
+        assert_eq!(registry.get_topic_count(), 1);
+        assert_eq!(registry.get_topic_name(16), Some("pose"));
+        assert_eq!(
+            registry.get_message_type_name(16),
+            Some(type_name::<Pose>())
+        );
+    }
+}
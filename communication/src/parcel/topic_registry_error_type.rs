@@ -0,0 +1,5 @@
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TopicRegistryErrorType {
+    /// Topic ID is already registered with a different name or message type.
+    DuplicateTopicId(u16),
+}
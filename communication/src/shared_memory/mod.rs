@@ -0,0 +1,3 @@
+pub mod shared_memory_error_type;
+pub mod shared_memory_ring;
+pub mod shared_memory_transport;
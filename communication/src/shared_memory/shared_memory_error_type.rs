@@ -0,0 +1,8 @@
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SharedMemoryErrorType {
+    InvalidName,
+    InvalidLayout,
+    MessageTooLarge,
+    Timeout,
+    OsError(i32),
+}
@@ -0,0 +1,485 @@
+use std::{
+    ffi::CString,
+    mem::size_of,
+    ptr,
+    sync::atomic::{AtomicU32, AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+use super::shared_memory_error_type::SharedMemoryErrorType;
+
+const RING_MAGIC: u32 = 0x5043_5252;
+const RING_DATA_OFFSET: usize = 64;
+const _: () = assert!(size_of::<RingHeader>() <= RING_DATA_OFFSET);
+
+/// Layout of the control block placed at the start of the shared memory region.
+/// `head` and `tail` count bytes written and read since creation, so the fill level
+/// is always `head - tail`. `data_seq` and `space_seq` are futex words bumped after
+/// every write and read respectively.
+#[repr(C)]
+struct RingHeader {
+    magic: AtomicU32,
+    capacity: u32,
+    data_seq: AtomicU32,
+    space_seq: AtomicU32,
+    head: AtomicU64,
+    tail: AtomicU64,
+}
+
+/// Single-producer single-consumer byte ring living in POSIX shared memory.
+///
+/// Every `write` publishes its bytes with a single update of the head counter,
+/// so a reader never observes half of a Parcel frame.
+pub struct SharedMemoryRing {
+    name: CString,
+    mapping: *mut u8,
+    mapping_size: usize,
+    capacity: usize,
+    is_owner: bool,
+}
+
+unsafe impl Send for SharedMemoryRing {}
+
+impl SharedMemoryRing {
+    /// Create new shared memory ring with given name and data capacity in bytes.
+    /// The ring is unlinked from `/dev/shm` when the creating instance is dropped.
+    pub fn create(name: &str, capacity: usize) -> Result<Self, SharedMemoryErrorType> {
+        if capacity == 0 || capacity > u32::MAX as usize {
+            return Err(SharedMemoryErrorType::InvalidLayout);
+        }
+
+        let c_name = SharedMemoryRing::to_shm_name(name)?;
+        let mapping_size = RING_DATA_OFFSET + capacity;
+
+        let fd = unsafe {
+            libc::shm_open(
+                c_name.as_ptr(),
+                libc::O_CREAT | libc::O_EXCL | libc::O_RDWR,
+                0o600,
+            )
+        };
+        if fd < 0 {
+            return Err(SharedMemoryRing::last_os_error());
+        }
+
+        if unsafe { libc::ftruncate(fd, mapping_size as libc::off_t) } != 0 {
+            let error = SharedMemoryRing::last_os_error();
+            unsafe {
+                libc::close(fd);
+                libc::shm_unlink(c_name.as_ptr());
+            }
+            return Err(error);
+        }
+
+        let mapping = match SharedMemoryRing::map(fd, mapping_size) {
+            Ok(m) => m,
+            Err(e) => {
+                unsafe { libc::shm_unlink(c_name.as_ptr()) };
+                return Err(e);
+            }
+        };
+
+        let ring = Self {
+            name: c_name,
+            mapping: mapping,
+            mapping_size: mapping_size,
+            capacity: capacity,
+            is_owner: true,
+        };
+
+        // Publish the magic number last so that openers never see a half-initialized header.
+        let header = ring.header_ptr();
+        unsafe {
+            ptr::addr_of_mut!((*header).capacity).write(capacity as u32);
+        }
+        let header = ring.header();
+        header.data_seq.store(0, Ordering::Relaxed);
+        header.space_seq.store(0, Ordering::Relaxed);
+        header.head.store(0, Ordering::Relaxed);
+        header.tail.store(0, Ordering::Relaxed);
+        header.magic.store(RING_MAGIC, Ordering::Release);
+
+        return Ok(ring);
+    }
+
+    /// Open existing shared memory ring created by another instance.
+    pub fn open(name: &str) -> Result<Self, SharedMemoryErrorType> {
+        let c_name = SharedMemoryRing::to_shm_name(name)?;
+
+        let fd = unsafe { libc::shm_open(c_name.as_ptr(), libc::O_RDWR, 0) };
+        if fd < 0 {
+            return Err(SharedMemoryRing::last_os_error());
+        }
+
+        let mut stat: libc::stat = unsafe { std::mem::zeroed() };
+        if unsafe { libc::fstat(fd, &mut stat) } != 0 {
+            let error = SharedMemoryRing::last_os_error();
+            unsafe { libc::close(fd) };
+            return Err(error);
+        }
+
+        let mapping_size = stat.st_size as usize;
+        if mapping_size <= RING_DATA_OFFSET {
+            unsafe { libc::close(fd) };
+            return Err(SharedMemoryErrorType::InvalidLayout);
+        }
+
+        let mapping = SharedMemoryRing::map(fd, mapping_size)?;
+        let ring = Self {
+            name: c_name,
+            mapping: mapping,
+            mapping_size: mapping_size,
+            capacity: mapping_size - RING_DATA_OFFSET,
+            is_owner: false,
+        };
+
+        let header = ring.header();
+        if header.magic.load(Ordering::Acquire) != RING_MAGIC
+            || header.capacity as usize != ring.capacity
+        {
+            return Err(SharedMemoryErrorType::InvalidLayout);
+        }
+
+        return Ok(ring);
+    }
+
+    /// Data capacity of the ring in bytes.
+    pub fn capacity(&self) -> usize {
+        return self.capacity;
+    }
+
+    /// Number of bytes written but not read yet.
+    pub fn len(&self) -> usize {
+        let header = self.header();
+        let head = header.head.load(Ordering::Acquire);
+        let tail = header.tail.load(Ordering::Acquire);
+        return (head - tail) as usize;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.len() == 0;
+    }
+
+    /// Write bytes to the ring as one unit.
+    /// Waits for free space up to `timeout`; `None` waits indefinitely.
+    pub fn write(
+        &self,
+        bytes: &[u8],
+        timeout: Option<Duration>,
+    ) -> Result<(), SharedMemoryErrorType> {
+        if bytes.len() > self.capacity {
+            return Err(SharedMemoryErrorType::MessageTooLarge);
+        }
+
+        let header = self.header();
+        let deadline = timeout.map(|t| Instant::now() + t);
+
+        loop {
+            // Load the sequence before checking the fill level so that a read in between
+            // changes the futex word and the wait below returns immediately.
+            let seq = header.space_seq.load(Ordering::Acquire);
+            let head = header.head.load(Ordering::Relaxed);
+            let tail = header.tail.load(Ordering::Acquire);
+            let free = self.capacity - (head - tail) as usize;
+
+            if free >= bytes.len() {
+                self.copy_in(head, bytes);
+                header
+                    .head
+                    .store(head + bytes.len() as u64, Ordering::Release);
+                header.data_seq.fetch_add(1, Ordering::Release);
+                futex_wake(&header.data_seq);
+                return Ok(());
+            }
+
+            let remaining = match deadline {
+                Some(d) => {
+                    let now = Instant::now();
+                    if now >= d {
+                        return Err(SharedMemoryErrorType::Timeout);
+                    }
+                    Some(d - now)
+                }
+                None => None,
+            };
+            futex_wait(&header.space_seq, seq, remaining);
+        }
+    }
+
+    /// Read all bytes currently available in the ring. Never blocks.
+    pub fn read_available(&self) -> Vec<u8> {
+        let header = self.header();
+        let head = header.head.load(Ordering::Acquire);
+        let tail = header.tail.load(Ordering::Relaxed);
+        let size = (head - tail) as usize;
+        if size == 0 {
+            return Vec::new();
+        }
+
+        let bytes = self.copy_out(tail, size);
+        header.tail.store(head, Ordering::Release);
+        header.space_seq.fetch_add(1, Ordering::Release);
+        futex_wake(&header.space_seq);
+        return bytes;
+    }
+
+    /// Block until the ring holds unread bytes or the timeout expires.
+    /// Returns whether data is available.
+    pub fn wait_for_data(&self, timeout: Option<Duration>) -> bool {
+        let header = self.header();
+        let deadline = timeout.map(|t| Instant::now() + t);
+
+        loop {
+            let seq = header.data_seq.load(Ordering::Acquire);
+            if !self.is_empty() {
+                return true;
+            }
+
+            let remaining = match deadline {
+                Some(d) => {
+                    let now = Instant::now();
+                    if now >= d {
+                        return false;
+                    }
+                    Some(d - now)
+                }
+                None => None,
+            };
+            futex_wait(&header.data_seq, seq, remaining);
+        }
+    }
+
+    fn copy_in(&self, position: u64, bytes: &[u8]) {
+        let start = (position % self.capacity as u64) as usize;
+        let first = usize::min(bytes.len(), self.capacity - start);
+        unsafe {
+            let data = self.mapping.add(RING_DATA_OFFSET);
+            ptr::copy_nonoverlapping(bytes.as_ptr(), data.add(start), first);
+            ptr::copy_nonoverlapping(bytes.as_ptr().add(first), data, bytes.len() - first);
+        }
+    }
+
+    fn copy_out(&self, position: u64, size: usize) -> Vec<u8> {
+        let start = (position % self.capacity as u64) as usize;
+        let first = usize::min(size, self.capacity - start);
+        let mut bytes: Vec<u8> = Vec::with_capacity(size);
+        unsafe {
+            let data = self.mapping.add(RING_DATA_OFFSET);
+            ptr::copy_nonoverlapping(data.add(start), bytes.as_mut_ptr(), first);
+            ptr::copy_nonoverlapping(data, bytes.as_mut_ptr().add(first), size - first);
+            bytes.set_len(size);
+        }
+        return bytes;
+    }
+
+    fn header_ptr(&self) -> *mut RingHeader {
+        return self.mapping as *mut RingHeader;
+    }
+
+    fn header(&self) -> &RingHeader {
+        return unsafe { &*self.header_ptr() };
+    }
+
+    fn map(fd: libc::c_int, size: usize) -> Result<*mut u8, SharedMemoryErrorType> {
+        let mapping = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                size,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_SHARED,
+                fd,
+                0,
+            )
+        };
+        let error = SharedMemoryRing::last_os_error();
+        unsafe { libc::close(fd) };
+
+        if mapping == libc::MAP_FAILED {
+            return Err(error);
+        }
+        return Ok(mapping as *mut u8);
+    }
+
+    fn to_shm_name(name: &str) -> Result<CString, SharedMemoryErrorType> {
+        if name.is_empty() || name.contains('/') {
+            return Err(SharedMemoryErrorType::InvalidName);
+        }
+
+        return match CString::new(format!("/{}", name)) {
+            Ok(n) => Ok(n),
+            Err(_) => Err(SharedMemoryErrorType::InvalidName),
+        };
+    }
+
+    fn last_os_error() -> SharedMemoryErrorType {
+        let code = std::io::Error::last_os_error().raw_os_error().unwrap_or(0);
+        return SharedMemoryErrorType::OsError(code);
+    }
+}
+
+impl Drop for SharedMemoryRing {
+    fn drop(&mut self) {
+        unsafe {
+            libc::munmap(self.mapping as *mut libc::c_void, self.mapping_size);
+            if self.is_owner {
+                libc::shm_unlink(self.name.as_ptr());
+            }
+        }
+    }
+}
+
+/// Sleep while the futex word still holds `expected`.
+/// The futex is not process-private since the word lives in shared memory.
+fn futex_wait(word: &AtomicU32, expected: u32, timeout: Option<Duration>) {
+    let timespec = timeout.map(|t| libc::timespec {
+        tv_sec: t.as_secs() as libc::time_t,
+        tv_nsec: t.subsec_nanos() as libc::c_long,
+    });
+    let timespec_ptr = match timespec.as_ref() {
+        Some(t) => t as *const libc::timespec,
+        None => ptr::null(),
+    };
+
+    unsafe {
+        libc::syscall(
+            libc::SYS_futex,
+            word.as_ptr(),
+            libc::FUTEX_WAIT,
+            expected,
+            timespec_ptr,
+        );
+    }
+}
+
+/// Wake every waiter sleeping on the futex word.
+fn futex_wake(word: &AtomicU32) {
+    unsafe {
+        libc::syscall(libc::SYS_futex, word.as_ptr(), libc::FUTEX_WAKE, i32::MAX);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+
+    /// Name unique to this test process, so that parallel test runs do not collide.
+    fn ring_name(test_name: &str) -> String {
+        return format!("ring_test_{}_{}", std::process::id(), test_name);
+    }
+
+    #[test]
+    fn open_sees_bytes_written_by_creator() {
+        let name = ring_name("round_trip");
+        let creator = SharedMemoryRing::create(&name, 64).unwrap();
+        let opener = SharedMemoryRing::open(&name).unwrap();
+        assert_eq!(opener.capacity(), 64);
+
+        creator.write(&[1, 2, 3], None).unwrap();
+        creator.write(&[4, 5], None).unwrap();
+        assert_eq!(opener.len(), 5);
+        assert_eq!(opener.read_available(), vec![1, 2, 3, 4, 5]);
+        assert!(opener.is_empty());
+        assert!(creator.is_empty());
+        assert_eq!(opener.read_available(), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn create_and_open_reject_invalid_arguments() {
+        assert_eq!(
+            SharedMemoryRing::create("", 16).err(),
+            Some(SharedMemoryErrorType::InvalidName)
+        );
+        assert_eq!(
+            SharedMemoryRing::create("a/b", 16).err(),
+            Some(SharedMemoryErrorType::InvalidName)
+        );
+        assert_eq!(
+            SharedMemoryRing::create(&ring_name("zero"), 0).err(),
+            Some(SharedMemoryErrorType::InvalidLayout)
+        );
+        assert!(matches!(
+            SharedMemoryRing::open(&ring_name("missing")),
+            Err(SharedMemoryErrorType::OsError(_))
+        ));
+
+        // The name is taken until the creator is dropped.
+        let name = ring_name("exclusive");
+        let creator = SharedMemoryRing::create(&name, 16).unwrap();
+        assert!(matches!(
+            SharedMemoryRing::create(&name, 16),
+            Err(SharedMemoryErrorType::OsError(_))
+        ));
+        drop(creator);
+        assert!(SharedMemoryRing::open(&name).is_err());
+    }
+
+    #[test]
+    fn writes_wrap_around_end_of_buffer() {
+        let ring = SharedMemoryRing::create(&ring_name("wraparound"), 16).unwrap();
+        for round in 0..10_u8 {
+            let bytes: Vec<u8> = (0..11).map(|i| round * 16 + i).collect();
+            ring.write(&bytes, None).unwrap();
+            assert_eq!(ring.read_available(), bytes);
+        }
+    }
+
+    #[test]
+    fn full_ring_times_out_until_space_is_freed() {
+        let ring = SharedMemoryRing::create(&ring_name("full"), 8).unwrap();
+        assert_eq!(
+            ring.write(&[0; 9], None),
+            Err(SharedMemoryErrorType::MessageTooLarge)
+        );
+
+        ring.write(&[7; 8], None).unwrap();
+        let start = Instant::now();
+        assert_eq!(
+            ring.write(&[1], Some(Duration::from_millis(20))),
+            Err(SharedMemoryErrorType::Timeout)
+        );
+        assert!(start.elapsed() >= Duration::from_millis(20));
+        assert_eq!(ring.len(), 8);
+
+        assert_eq!(ring.read_available(), vec![7; 8]);
+        ring.write(&[1], Some(Duration::ZERO)).unwrap();
+        assert_eq!(ring.read_available(), vec![1]);
+    }
+
+    #[test]
+    fn blocked_writer_resumes_when_reader_frees_space() {
+        let name = ring_name("blocked_writer");
+        let ring = SharedMemoryRing::create(&name, 4).unwrap();
+        ring.write(&[1, 2, 3, 4], None).unwrap();
+
+        let writer = thread::spawn(move || {
+            let ring = SharedMemoryRing::open(&name).unwrap();
+            return ring.write(&[5, 6], Some(Duration::from_secs(5)));
+        });
+        thread::sleep(Duration::from_millis(20));
+        assert_eq!(ring.read_available(), vec![1, 2, 3, 4]);
+
+        assert_eq!(writer.join().unwrap(), Ok(()));
+        assert_eq!(ring.read_available(), vec![5, 6]);
+    }
+
+    #[test]
+    fn wait_for_data_times_out_or_wakes_on_write() {
+        let name = ring_name("wait");
+        let ring = SharedMemoryRing::create(&name, 16).unwrap();
+
+        let start = Instant::now();
+        assert!(!ring.wait_for_data(Some(Duration::from_millis(20))));
+        assert!(start.elapsed() >= Duration::from_millis(20));
+
+        let writer = thread::spawn(move || {
+            let ring = SharedMemoryRing::open(&name).unwrap();
+            thread::sleep(Duration::from_millis(20));
+            ring.write(&[9], None).unwrap();
+        });
+        assert!(ring.wait_for_data(Some(Duration::from_secs(5))));
+        writer.join().unwrap();
+        assert_eq!(ring.read_available(), vec![9]);
+    }
+}
@@ -0,0 +1,173 @@
+use std::{cell::Cell, time::Duration};
+
+use super::{
+    shared_memory_error_type::SharedMemoryErrorType, shared_memory_ring::SharedMemoryRing,
+};
+
+/// Bidirectional intra-host transport for Parcel built from two shared memory rings.
+///
+/// One side calls `create` and the peer calls `connect` with the same name.
+/// The TX and RX functions returned by `tx_func` and `rx_func` plug directly into
+/// `Parcel::new`, so framing, topics and checksums are unchanged.
+pub struct SharedMemoryTransport {
+    tx_ring: SharedMemoryRing,
+    rx_ring: SharedMemoryRing,
+    tx_timeout: Option<Duration>,
+    dropped_frame_count: Cell<u64>,
+}
+
+impl SharedMemoryTransport {
+    /// Create new transport with given name and per-direction capacity in bytes.
+    pub fn create(name: &str, capacity: usize) -> Result<Self, SharedMemoryErrorType> {
+        let tx_ring = SharedMemoryRing::create(&format!("{}.a2b", name), capacity)?;
+        let rx_ring = SharedMemoryRing::create(&format!("{}.b2a", name), capacity)?;
+        return Ok(SharedMemoryTransport::from_rings(tx_ring, rx_ring));
+    }
+
+    /// Connect to a transport created by another process.
+    pub fn connect(name: &str) -> Result<Self, SharedMemoryErrorType> {
+        let tx_ring = SharedMemoryRing::open(&format!("{}.b2a", name))?;
+        let rx_ring = SharedMemoryRing::open(&format!("{}.a2b", name))?;
+        return Ok(SharedMemoryTransport::from_rings(tx_ring, rx_ring));
+    }
+
+    fn from_rings(tx_ring: SharedMemoryRing, rx_ring: SharedMemoryRing) -> Self {
+        return Self {
+            tx_ring: tx_ring,
+            rx_ring: rx_ring,
+            tx_timeout: None,
+            dropped_frame_count: Cell::new(0),
+        };
+    }
+
+    /// Set how long TX waits for the peer to free space before dropping a frame.
+    /// `None` waits indefinitely.
+    pub fn set_tx_timeout(&mut self, tx_timeout: Option<Duration>) {
+        self.tx_timeout = tx_timeout;
+    }
+
+    /// Number of frames dropped by the TX function because they did not fit in time.
+    pub fn dropped_frame_count(&self) -> u64 {
+        return self.dropped_frame_count.get();
+    }
+
+    /// Write bytes to the peer.
+    pub fn send(&self, bytes: &[u8]) -> Result<(), SharedMemoryErrorType> {
+        return self.tx_ring.write(bytes, self.tx_timeout);
+    }
+
+    /// Read all bytes the peer has sent so far. Never blocks.
+    pub fn receive(&self) -> Vec<u8> {
+        return self.rx_ring.read_available();
+    }
+
+    /// Block until the peer has sent data or the timeout expires.
+    pub fn wait_for_data(&self, timeout: Option<Duration>) -> bool {
+        return self.rx_ring.wait_for_data(timeout);
+    }
+
+    /// TX function to be passed to `Parcel::new`.
+    pub fn tx_func(&self) -> impl FnMut(&Vec<u8>) + '_ {
+        return move |bytes: &Vec<u8>| {
+            if self.send(bytes).is_err() {
+                self.dropped_frame_count
+                    .set(self.dropped_frame_count.get() + 1);
+            }
+        };
+    }
+
+    /// RX function to be passed to `Parcel::new`.
+    pub fn rx_func(&self) -> impl FnMut() -> Vec<u8> + '_ {
+        return move || self.receive();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::enums::endian::Endian;
+
+    use super::*;
+    use crate::parcel::parcel::Parcel;
+
+    /// Name unique to this test process, so that parallel test runs do not collide.
+    fn transport_name(test_name: &str) -> String {
+        return format!("transport_test_{}_{}", std::process::id(), test_name);
+    }
+
+    fn send_frame(parcel: &mut Parcel, topic: u16, value: u64) {
+        parcel.tx_clear();
+        parcel.tx_write_header();
+        parcel.tx_write_topic(topic);
+        parcel.tx_write(value);
+        parcel.tx_finalize();
+        parcel.tx_send();
+    }
+
+    #[test]
+    fn parcels_exchange_frames_in_both_directions() {
+        let name = transport_name("round_trip");
+        let creator = SharedMemoryTransport::create(&name, 256).unwrap();
+        let peer = SharedMemoryTransport::connect(&name).unwrap();
+        let mut creator_parcel =
+            Parcel::new(Endian::LittleEndian, creator.tx_func(), creator.rx_func());
+        let mut peer_parcel = Parcel::new(Endian::LittleEndian, peer.tx_func(), peer.rx_func());
+
+        send_frame(&mut creator_parcel, 16, 1);
+        send_frame(&mut creator_parcel, 17, u64::MAX);
+        peer_parcel.rx_receive();
+        assert_eq!(
+            peer_parcel.rx_read_frame(),
+            Some((16, 1_u64.to_le_bytes().to_vec()))
+        );
+        assert_eq!(
+            peer_parcel.rx_read_frame(),
+            Some((17, u64::MAX.to_le_bytes().to_vec()))
+        );
+        assert_eq!(peer_parcel.rx_read_frame(), None);
+
+        send_frame(&mut peer_parcel, 18, 42);
+        creator_parcel.rx_receive();
+        assert_eq!(
+            creator_parcel.rx_read_frame(),
+            Some((18, 42_u64.to_le_bytes().to_vec()))
+        );
+
+        // Frames only travel to the peer, never back to their sender.
+        peer_parcel.rx_receive();
+        assert_eq!(peer_parcel.rx_read_frame(), None);
+        assert_eq!(creator.dropped_frame_count(), 0);
+        assert_eq!(peer.dropped_frame_count(), 0);
+    }
+
+    #[test]
+    fn full_ring_drops_frames_after_timeout() {
+        let name = transport_name("full");
+        // A frame with an 8-byte payload takes 15 bytes, so only one fits.
+        let mut creator = SharedMemoryTransport::create(&name, 16).unwrap();
+        creator.set_tx_timeout(Some(Duration::from_millis(10)));
+        let peer = SharedMemoryTransport::connect(&name).unwrap();
+        let mut creator_parcel =
+            Parcel::new(Endian::LittleEndian, creator.tx_func(), creator.rx_func());
+        let mut peer_parcel = Parcel::new(Endian::LittleEndian, peer.tx_func(), peer.rx_func());
+
+        send_frame(&mut creator_parcel, 16, 1);
+        assert_eq!(creator.dropped_frame_count(), 0);
+        send_frame(&mut creator_parcel, 16, 2);
+        assert_eq!(creator.dropped_frame_count(), 1);
+
+        // Once the peer reads, there is room again.
+        peer_parcel.rx_receive();
+        assert_eq!(
+            peer_parcel.rx_read_frame(),
+            Some((16, 1_u64.to_le_bytes().to_vec()))
+        );
+        send_frame(&mut creator_parcel, 16, 3);
+        assert_eq!(creator.dropped_frame_count(), 1);
+        peer_parcel.rx_receive();
+        assert_eq!(
+            peer_parcel.rx_read_frame(),
+            Some((16, 3_u64.to_le_bytes().to_vec()))
+        );
+        assert_eq!(peer_parcel.rx_read_frame(), None);
+    }
+}
@@ -0,0 +1,112 @@
+/*
+ * Reads Parcel frames produced by the Rust implementation and writes them back.
+ *
+ * Usage: parcel_roundtrip <little|big> <input file> <output file>
+ *
+ * Frames on topic 1 carry (u32 counter, f64 value, i16 offset, bool flag) and are
+ * decoded and re-encoded field by field. Frames on any other topic are echoed as raw
+ * payload bytes. The output must be byte-identical to the input.
+ */
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "parcel.h"
+
+#define CHECK(expr)                                                   \
+    do {                                                              \
+        if (!(expr)) {                                                \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__,    \
+                    __LINE__, #expr);                                 \
+            exit(1);                                                  \
+        }                                                             \
+    } while (0)
+
+static void write_frame(const uint8_t *data, size_t size, void *user_data)
+{
+    CHECK(fwrite(data, 1, size, (FILE *)user_data) == size);
+}
+
+static void echo_typed_frame(ParcelHandle *tx, ParcelEndian endian, const uint8_t *payload,
+                             size_t payload_size)
+{
+    uint32_t counter;
+    double value;
+    int16_t offset;
+    bool flag;
+
+    CHECK(parcel_rx_read_u32(payload, payload_size, 0, endian, &counter) == PARCEL_STATUS_OK);
+    CHECK(parcel_rx_read_f64(payload, payload_size, 4, endian, &value) == PARCEL_STATUS_OK);
+    CHECK(parcel_rx_read_i16(payload, payload_size, 12, endian, &offset) == PARCEL_STATUS_OK);
+    CHECK(parcel_rx_read_bool(payload, payload_size, 14, endian, &flag) == PARCEL_STATUS_OK);
+    CHECK(parcel_rx_read_u8(payload, payload_size, 15, endian, (uint8_t *)&flag) ==
+          PARCEL_STATUS_OUT_OF_BOUNDS);
+    CHECK(parcel_rx_read_u32(payload, payload_size, 0, 7, &counter) ==
+          PARCEL_STATUS_INVALID_ARGUMENT);
+
+    CHECK(parcel_tx_write_u32(tx, counter) == PARCEL_STATUS_OK);
+    CHECK(parcel_tx_write_f64(tx, value) == PARCEL_STATUS_OK);
+    CHECK(parcel_tx_write_i16(tx, offset) == PARCEL_STATUS_OK);
+    CHECK(parcel_tx_write_bool(tx, flag) == PARCEL_STATUS_OK);
+}
+
+int main(int argc, char **argv)
+{
+    CHECK(argc == 4);
+    ParcelEndian endian = strcmp(argv[1], "big") == 0 ? PARCEL_ENDIAN_BIG : PARCEL_ENDIAN_LITTLE;
+
+    FILE *input = fopen(argv[2], "rb");
+    FILE *output = fopen(argv[3], "wb");
+    CHECK(input != NULL && output != NULL);
+
+    ParcelHandle *rx = parcel_create(endian, NULL, NULL);
+    ParcelHandle *tx = parcel_create(endian, write_frame, output);
+    CHECK(rx != NULL && tx != NULL);
+    CHECK(parcel_create(2, NULL, NULL) == NULL);
+
+    /* Calls in the wrong order must be rejected, not crash. */
+    CHECK(parcel_tx_write_u8(tx, 0) == PARCEL_STATUS_INVALID_PHASE);
+    CHECK(parcel_tx_write_topic(tx, 1) == PARCEL_STATUS_INVALID_PHASE);
+    CHECK(parcel_tx_finalize(tx) == PARCEL_STATUS_INVALID_PHASE);
+    CHECK(parcel_tx_send(tx) == PARCEL_STATUS_INVALID_PHASE);
+    CHECK(parcel_tx_write_header(tx) == PARCEL_STATUS_OK);
+    CHECK(parcel_tx_write_header(tx) == PARCEL_STATUS_INVALID_PHASE);
+    CHECK(parcel_tx_clear(tx) == PARCEL_STATUS_OK);
+
+    /* Feed the input in small chunks so that frames are split across feeds. */
+    uint8_t chunk[5];
+    size_t chunk_size;
+    size_t frame_count = 0;
+    while ((chunk_size = fread(chunk, 1, sizeof(chunk), input)) > 0) {
+        CHECK(parcel_rx_feed(rx, chunk, chunk_size) == PARCEL_STATUS_OK);
+
+        uint16_t topic;
+        const uint8_t *payload;
+        size_t payload_size;
+        while (parcel_rx_read_frame(rx, &topic, &payload, &payload_size) == PARCEL_STATUS_OK) {
+            CHECK(parcel_tx_clear(tx) == PARCEL_STATUS_OK);
+            CHECK(parcel_tx_write_header(tx) == PARCEL_STATUS_OK);
+            CHECK(parcel_tx_write_topic(tx, topic) == PARCEL_STATUS_OK);
+            if (topic == 1) {
+                echo_typed_frame(tx, endian, payload, payload_size);
+            } else {
+                CHECK(parcel_tx_write_bytes(tx, payload, payload_size) == PARCEL_STATUS_OK);
+            }
+            CHECK(parcel_tx_finalize(tx) == PARCEL_STATUS_OK);
+
+            size_t frame_size = 0;
+            CHECK(parcel_tx_buffer(tx, &frame_size) != NULL);
+            CHECK(frame_size == payload_size + 7);
+            CHECK(parcel_tx_send(tx) == PARCEL_STATUS_OK);
+            frame_count++;
+        }
+    }
+
+    parcel_destroy(rx);
+    parcel_destroy(tx);
+    fclose(input);
+    fclose(output);
+
+    printf("%zu\n", frame_count);
+    return 0;
+}
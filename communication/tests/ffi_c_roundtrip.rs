@@ -0,0 +1,102 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use communication::parcel::parcel::Parcel;
+use core::enums::endian::Endian;
+
+/// Directory holding the library artifacts, as reported by the build script.
+fn artifact_dir() -> PathBuf {
+    return PathBuf::from(env!("COMMUNICATION_ARTIFACT_DIR"));
+}
+
+fn build_c_program(output: &Path) {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let compiler = env::var("CC").unwrap_or(String::from("cc"));
+
+    let status = Command::new(compiler)
+        .arg("-std=c99")
+        .arg("-Wall")
+        .arg("-Werror")
+        .arg("-I")
+        .arg(manifest_dir.join("include"))
+        .arg(manifest_dir.join("tests/c/parcel_roundtrip.c"))
+        .arg(artifact_dir().join("libcommunication.a"))
+        .args(["-lpthread", "-ldl", "-lm", "-lrt"])
+        .arg("-o")
+        .arg(output)
+        .status()
+        .expect("failed to run C compiler");
+    assert!(status.success(), "C test program failed to build");
+}
+
+fn build_frames(endian: Endian) -> Vec<u8> {
+    let mut frames: Vec<u8> = Vec::new();
+    let mut parcel = Parcel::new(endian, |bytes: &Vec<u8>| frames.extend(bytes), Vec::new);
+
+    for i in 0..20_u32 {
+        parcel.tx_clear();
+        parcel.tx_write_header();
+        if i % 2 == 0 {
+            parcel.tx_write_topic(1);
+            parcel.tx_write_u32(i);
+            parcel.tx_write_f64(i as f64 * -0.25);
+            parcel.tx_write_i16(-(i as i16));
+            parcel.tx_write(i % 4 == 0);
+        } else {
+            parcel.tx_write_topic(0x0102 + i as u16);
+            parcel.tx_write_string(format!("frame {}", i));
+            parcel.tx_write_slice(&vec![0x55_u8; i as usize]);
+        }
+        parcel.tx_finalize();
+        parcel.tx_send();
+    }
+
+    drop(parcel);
+    return frames;
+}
+
+#[test]
+fn c_program_round_trips_rust_frames() {
+    let work_dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("ffi_c_roundtrip");
+    fs::create_dir_all(&work_dir).unwrap();
+
+    let program = work_dir.join("parcel_roundtrip");
+    build_c_program(&program);
+
+    for (endian, endian_name) in [(Endian::LittleEndian, "little"), (Endian::BigEndian, "big")] {
+        let input = work_dir.join(format!("input_{}.bin", endian_name));
+        let output = work_dir.join(format!("output_{}.bin", endian_name));
+        let frames = build_frames(endian);
+        fs::write(&input, &frames).unwrap();
+
+        let result = Command::new(&program)
+            .arg(endian_name)
+            .arg(&input)
+            .arg(&output)
+            .output()
+            .expect("failed to run C test program");
+        assert!(
+            result.status.success(),
+            "C test program failed: {}",
+            String::from_utf8_lossy(&result.stderr)
+        );
+        assert_eq!(String::from_utf8_lossy(&result.stdout).trim(), "20");
+
+        let echoed = fs::read(&output).unwrap();
+        assert_eq!(echoed, frames);
+
+        // The echoed stream must also decode with the Rust implementation.
+        let mut parcel = Parcel::new(endian, |_: &Vec<u8>| {}, Vec::new);
+        parcel.rx_feed(&echoed);
+        let (topic, payload) = parcel.rx_read_frame().unwrap();
+        assert_eq!(topic, 1);
+        assert_eq!(Parcel::rx_read_f64(&payload, 4, endian).ok(), Some(-0.0));
+        assert_eq!(
+            Parcel::rx_read::<bool>(&payload, 14, endian).ok(),
+            Some(true)
+        );
+    }
+}
@@ -0,0 +1,222 @@
+//! Path query benchmark of `Graph` against its `FrozenGraph` snapshot and a
+//! `ContractionHierarchy` built from it.
+//!
+//! The map is synthetic, generated below rather than extracted from a real floor plan:
+//! `TopologyExtractor` lives in the map crate, which is a workspace member but does not
+//! wire its topology modules into its library, so the extractor cannot be called here.
+//! The generated map imitates its output for a large floor plan: a lattice of corridors
+//! whose intersections are joined by chains of waypoints, with rooms hanging off the
+//! corridors and some corridor sections walled off.
+//!
+//! Run with `cargo bench -p core --bench frozen_graph_search`.
+
+use std::{hint::black_box, time::Instant};
+
+use core::{
+    algorithm::{
+        a_star::a_star_algorithm::AStarAlgorithm, dijkstra::dijkstra_algorithm::DijkstraAlgorithm,
+        frozen_search::frozen_search_algorithm::FrozenSearchAlgorithm,
+        hierarchy::contraction_hierarchy::ContractionHierarchy,
+    },
+    graph::graph::Graph,
+    numerics::{vector::Vector, vector2d::Vector2D},
+};
+
+/// Corridor intersections per side of the floor plan.
+const LATTICE_SIZE: u32 = 120;
+/// Waypoints between neighboring intersections.
+const WAYPOINT_COUNT: u32 = 3;
+/// Distance between neighboring intersections in meters.
+const CORRIDOR_LENGTH: f64 = 8_f64;
+const QUERY_COUNT: usize = 200;
+
+type TopologyMap = Graph<Vector2D, f64>;
+
+/// Linear congruential generator, so that every run measures the same map and queries.
+struct Random(u64);
+
+impl Random {
+    fn next(&mut self) -> u32 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        return (self.0 >> 33) as u32;
+    }
+}
+
+fn add_chain(map: &mut TopologyMap, node1_id: u32, node2_id: u32) {
+    let p1 = *map.get_node_by_id(&node1_id).unwrap().node_info();
+    let p2 = *map.get_node_by_id(&node2_id).unwrap().node_info();
+
+    let mut previous_id = node1_id;
+    let mut previous_position = p1;
+    for i in 1..=(WAYPOINT_COUNT + 1) {
+        let t = i as f64 / (WAYPOINT_COUNT + 1) as f64;
+        let (node_id, position) = if i == WAYPOINT_COUNT + 1 {
+            (node2_id, p2)
+        } else {
+            let position = Vector2D::from_xy(p1.x + (p2.x - p1.x) * t, p1.y + (p2.y - p1.y) * t);
+            (map.add_node(position), position)
+        };
+
+        let length = (position - previous_position).magnitude();
+        map.add_edge(previous_id, node_id, length).unwrap();
+        previous_id = node_id;
+        previous_position = position;
+    }
+}
+
+fn build_floor_plan_map(random: &mut Random) -> TopologyMap {
+    let mut map: TopologyMap = Graph::new(false, false);
+    let mut intersections: Vec<u32> = Vec::new();
+    for y in 0..LATTICE_SIZE {
+        for x in 0..LATTICE_SIZE {
+            let position =
+                Vector2D::from_xy(x as f64 * CORRIDOR_LENGTH, y as f64 * CORRIDOR_LENGTH);
+            intersections.push(map.add_node(position));
+        }
+    }
+
+    for y in 0..LATTICE_SIZE {
+        for x in 0..LATTICE_SIZE {
+            let node_id = intersections[(y * LATTICE_SIZE + x) as usize];
+            // About one corridor section in eight is walled off.
+            if x + 1 < LATTICE_SIZE && !random.next().is_multiple_of(8) {
+                add_chain(&mut map, node_id, node_id + 1);
+            }
+            if y + 1 < LATTICE_SIZE && !random.next().is_multiple_of(8) {
+                add_chain(&mut map, node_id, node_id + LATTICE_SIZE);
+            }
+
+            // Room entered through a door next to the intersection.
+            if random.next().is_multiple_of(2) {
+                let corridor = *map.get_node_by_id(&node_id).unwrap().node_info();
+                let room = map.add_node(Vector2D::from_xy(
+                    corridor.x + CORRIDOR_LENGTH / 2_f64,
+                    corridor.y + CORRIDOR_LENGTH / 2_f64,
+                ));
+                map.add_edge(node_id, room, CORRIDOR_LENGTH / 2_f64 * 2_f64.sqrt())
+                    .unwrap();
+            }
+        }
+    }
+
+    return map;
+}
+
+fn report(name: &str, graph_seconds: f64, optimized_seconds: f64) {
+    println!(
+        "{:<24} Graph {:>9.2} ms/query   optimized {:>9.2} ms/query   speedup {:>5.1}x",
+        name,
+        graph_seconds * 1000_f64 / QUERY_COUNT as f64,
+        optimized_seconds * 1000_f64 / QUERY_COUNT as f64,
+        graph_seconds / optimized_seconds
+    );
+}
+
+fn main() {
+    let mut random = Random(42);
+    let map = build_floor_plan_map(&mut random);
+
+    let timer = Instant::now();
+    let frozen = map.freeze();
+    println!(
+        "floor plan map: {} nodes, {} edges, frozen in {:.1} ms",
+        map.get_node_count(),
+        map.get_edge_count(),
+        timer.elapsed().as_secs_f64() * 1000_f64
+    );
+
+    let node_count = map.get_node_count() as u32;
+    let queries: Vec<(u32, u32)> = (0..QUERY_COUNT)
+        .map(|_| {
+            (
+                1 + random.next() % node_count,
+                1 + random.next() % node_count,
+            )
+        })
+        .collect();
+    let cost_func = |length: &f64| *length;
+    let heuristic_func = |p1: &Vector2D, p2: &Vector2D| (*p2 - *p1).magnitude();
+
+    let timer = Instant::now();
+    let graph_costs: Vec<Option<f64>> = queries
+        .iter()
+        .map(|(start, goal)| {
+            return DijkstraAlgorithm::shortest_path(&map, *start, *goal, cost_func)
+                .unwrap()
+                .map(|p| p.cost);
+        })
+        .collect();
+    let dijkstra_seconds = timer.elapsed().as_secs_f64();
+
+    let timer = Instant::now();
+    let frozen_costs: Vec<Option<f64>> = queries
+        .iter()
+        .map(|(start, goal)| {
+            return FrozenSearchAlgorithm::shortest_path(&frozen, *start, *goal, cost_func)
+                .unwrap()
+                .map(|p| p.cost);
+        })
+        .collect();
+    let frozen_seconds = timer.elapsed().as_secs_f64();
+    assert_eq!(graph_costs, frozen_costs);
+    report("Dijkstra", dijkstra_seconds, frozen_seconds);
+
+    let a_star = AStarAlgorithm::new();
+    let timer = Instant::now();
+    for (start, goal) in queries.iter() {
+        black_box(
+            a_star
+                .search(&map, *start, *goal, cost_func, heuristic_func)
+                .unwrap(),
+        );
+    }
+    let graph_seconds = timer.elapsed().as_secs_f64();
+
+    let timer = Instant::now();
+    for (start, goal) in queries.iter() {
+        black_box(
+            FrozenSearchAlgorithm::a_star(&frozen, *start, *goal, cost_func, heuristic_func)
+                .unwrap(),
+        );
+    }
+    let frozen_seconds = timer.elapsed().as_secs_f64();
+    report("A*", graph_seconds, frozen_seconds);
+
+    // Generic algorithms also run on the snapshot through `GraphView`.
+    let timer = Instant::now();
+    for (start, goal) in queries.iter() {
+        black_box(DijkstraAlgorithm::shortest_path(&frozen, *start, *goal, cost_func).unwrap());
+    }
+    let generic_seconds = timer.elapsed().as_secs_f64();
+    report("Dijkstra via GraphView", dijkstra_seconds, generic_seconds);
+
+    let timer = Instant::now();
+    let hierarchy = ContractionHierarchy::build(&frozen, cost_func).unwrap();
+    println!(
+        "contraction hierarchy: {} arcs, built in {:.1} s",
+        hierarchy.get_arc_count(),
+        timer.elapsed().as_secs_f64()
+    );
+
+    let timer = Instant::now();
+    let hierarchy_costs: Vec<Option<f64>> = queries
+        .iter()
+        .map(|(start, goal)| {
+            return hierarchy
+                .shortest_path(*start, *goal)
+                .unwrap()
+                .map(|p| p.cost);
+        })
+        .collect();
+    let hierarchy_seconds = timer.elapsed().as_secs_f64();
+    for (graph_cost, hierarchy_cost) in graph_costs.iter().zip(hierarchy_costs.iter()) {
+        assert_eq!(graph_cost.is_some(), hierarchy_cost.is_some());
+        if let (Some(g), Some(h)) = (graph_cost, hierarchy_cost) {
+            assert!((g - h).abs() < 1e-6);
+        }
+    }
+    report("Contraction hierarchy", dijkstra_seconds, hierarchy_seconds);
+}
@@ -0,0 +1,352 @@
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, HashSet},
+};
+
+use crate::graph::{graph_error::GraphError, graph_path::GraphPath, graph_view::GraphView};
+
+use super::a_star_result::{AStarResult, AStarStatistics};
+
+/// Order in which open set entries with equal estimated total cost are expanded.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AStarTieBreaking {
+    /// No preference between equal entries.
+    None,
+    /// Prefer entries closer to the goal. Usually expands far fewer nodes on grids and
+    /// topology maps where many paths share the same cost.
+    PreferLowHeuristic,
+    /// Prefer entries closer to the start.
+    PreferHighHeuristic,
+}
+
+/// A* shortest path search.
+///
+/// Edge costs are computed from edge info, like in `DijkstraAlgorithm`, and the heuristic
+/// estimates the remaining cost from a node to the goal node, for example the Euclidean
+/// distance between `TopologyNode::position`s. With an admissible heuristic the returned
+/// path is optimal.
+pub struct AStarAlgorithm {
+    tie_breaking: AStarTieBreaking,
+    expansion_limit: Option<usize>,
+}
+
+struct OpenSetEntry {
+    estimated_cost: f64,
+    heuristic: f64,
+    cost: f64,
+    node_id: u32,
+    tie_breaking: AStarTieBreaking,
+}
+
+impl AStarAlgorithm {
+    pub fn new() -> Self {
+        return Self {
+            tie_breaking: AStarTieBreaking::PreferLowHeuristic,
+            expansion_limit: None,
+        };
+    }
+
+    pub fn set_tie_breaking(&mut self, tie_breaking: AStarTieBreaking) {
+        self.tie_breaking = tie_breaking;
+    }
+
+    /// Give up after expanding given number of nodes. `None` removes the limit.
+    pub fn set_expansion_limit(&mut self, expansion_limit: Option<usize>) {
+        self.expansion_limit = expansion_limit;
+    }
+
+    /// Find shortest path from start node to goal node.
+    pub fn search<GraphT, CostFuncT, HeuristicFuncT>(
+        &self,
+        graph: &GraphT,
+        start_node: u32,
+        goal_node: u32,
+        cost_func: CostFuncT,
+        heuristic_func: HeuristicFuncT,
+    ) -> Result<AStarResult, GraphError>
+    where
+        GraphT: GraphView,
+        CostFuncT: Fn(&GraphT::EdgeInfo) -> f64,
+        HeuristicFuncT: Fn(&GraphT::NodeInfo, &GraphT::NodeInfo) -> f64,
+    {
+        let goal_info = match graph.get_node_by_id(&goal_node) {
+            Some(n) => n.node_info(),
+            None => return Err(GraphError::NoSuchNode),
+        };
+        let start_info = match graph.get_node_by_id(&start_node) {
+            Some(n) => n.node_info(),
+            None => return Err(GraphError::NoSuchNode),
+        };
+
+        let mut statistics = AStarStatistics::default();
+        let mut costs: HashMap<u32, f64> = HashMap::new();
+        let mut predecessors: HashMap<u32, (u32, u32)> = HashMap::new();
+        let mut expanded: HashSet<u32> = HashSet::new();
+        let mut open_set: BinaryHeap<OpenSetEntry> = BinaryHeap::new();
+
+        let start_heuristic = heuristic_func(start_info, goal_info);
+        costs.insert(start_node, 0_f64);
+        open_set.push(OpenSetEntry {
+            estimated_cost: start_heuristic,
+            heuristic: start_heuristic,
+            cost: 0_f64,
+            node_id: start_node,
+            tie_breaking: self.tie_breaking,
+        });
+        statistics.generated_node_count += 1;
+        statistics.max_open_set_size = 1;
+
+        while let Some(entry) = open_set.pop() {
+            // Skip stale entries superseded by a cheaper path to the same node.
+            if entry.cost > *costs.get(&entry.node_id).unwrap() {
+                continue;
+            }
+
+            let node_id = entry.node_id;
+            if node_id == goal_node {
+                let path = AStarAlgorithm::build_path(&predecessors, goal_node, entry.cost);
+                return Ok(AStarResult {
+                    path: Some(path),
+                    statistics: statistics,
+                });
+            }
+
+            if let Some(limit) = self.expansion_limit {
+                if statistics.expanded_node_count >= limit {
+                    statistics.is_expansion_limit_reached = true;
+                    break;
+                }
+            }
+
+            statistics.expanded_node_count += 1;
+            if !expanded.insert(node_id) {
+                statistics.reopened_node_count += 1;
+            }
+
+            for (edge_id, next_node_id) in graph.connected_edges(node_id) {
+                let edge = graph.get_edge_by_id(&edge_id).unwrap();
+                if next_node_id == node_id || !edge.can_move_from(node_id) {
+                    continue;
+                }
+
+                let edge_cost = cost_func(edge.edge_info());
+                if edge_cost.is_nan() || edge_cost < 0_f64 {
+                    return Err(GraphError::InvalidEdgeCost);
+                }
+
+                let next_cost = entry.cost + edge_cost;
+                let is_improved = match costs.get(&next_node_id) {
+                    Some(c) => next_cost < *c,
+                    None => true,
+                };
+                if !is_improved {
+                    continue;
+                }
+
+                let next_info = graph.get_node_by_id(&next_node_id).unwrap().node_info();
+                let heuristic = heuristic_func(next_info, goal_info);
+                costs.insert(next_node_id, next_cost);
+                predecessors.insert(next_node_id, (node_id, edge_id));
+                open_set.push(OpenSetEntry {
+                    estimated_cost: next_cost + heuristic,
+                    heuristic: heuristic,
+                    cost: next_cost,
+                    node_id: next_node_id,
+                    tie_breaking: self.tie_breaking,
+                });
+                statistics.generated_node_count += 1;
+            }
+
+            statistics.max_open_set_size = usize::max(statistics.max_open_set_size, open_set.len());
+        }
+
+        return Ok(AStarResult {
+            path: None,
+            statistics: statistics,
+        });
+    }
+
+    fn build_path(predecessors: &HashMap<u32, (u32, u32)>, goal_node: u32, cost: f64) -> GraphPath {
+        let mut nodes: Vec<u32> = vec![goal_node];
+        let mut edges: Vec<u32> = Vec::new();
+
+        let mut current = goal_node;
+        while let Some((previous_node, edge_id)) = predecessors.get(&current) {
+            nodes.push(*previous_node);
+            edges.push(*edge_id);
+            current = *previous_node;
+        }
+
+        nodes.reverse();
+        edges.reverse();
+        return GraphPath {
+            nodes: nodes,
+            edges: edges,
+            cost: cost,
+        };
+    }
+}
+
+impl Default for AStarAlgorithm {
+    fn default() -> Self {
+        return AStarAlgorithm::new();
+    }
+}
+
+impl PartialEq for OpenSetEntry {
+    fn eq(&self, other: &Self) -> bool {
+        return self.cmp(other) == Ordering::Equal;
+    }
+}
+
+impl Eq for OpenSetEntry {}
+
+impl PartialOrd for OpenSetEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        return Some(self.cmp(other));
+    }
+}
+
+impl Ord for OpenSetEntry {
+    /// Reversed so that `BinaryHeap` pops the lowest estimated cost first.
+    fn cmp(&self, other: &Self) -> Ordering {
+        let ordering = other.estimated_cost.total_cmp(&self.estimated_cost);
+        return match self.tie_breaking {
+            AStarTieBreaking::None => ordering,
+            AStarTieBreaking::PreferLowHeuristic => {
+                ordering.then_with(|| other.heuristic.total_cmp(&self.heuristic))
+            }
+            AStarTieBreaking::PreferHighHeuristic => {
+                ordering.then_with(|| self.heuristic.total_cmp(&other.heuristic))
+            }
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::graph::Graph;
+
+    /// Grid of `size` x `size` nodes at integer positions with unit-cost edges.
+    /// Node IDs are row-major from 1.
+    fn grid_graph(size: u32) -> Graph<(f64, f64), f64> {
+        let mut graph: Graph<(f64, f64), f64> = Graph::new(false, false);
+        for y in 0..size {
+            for x in 0..size {
+                graph.add_node((x as f64, y as f64));
+            }
+        }
+        for y in 0..size {
+            for x in 0..size {
+                let node_id = y * size + x + 1;
+                if x + 1 < size {
+                    graph.add_edge(node_id, node_id + 1, 1_f64).unwrap();
+                }
+                if y + 1 < size {
+                    graph.add_edge(node_id, node_id + size, 1_f64).unwrap();
+                }
+            }
+        }
+        return graph;
+    }
+
+    fn manhattan(a: &(f64, f64), b: &(f64, f64)) -> f64 {
+        return (a.0 - b.0).abs() + (a.1 - b.1).abs();
+    }
+
+    fn search_grid(tie_breaking: AStarTieBreaking) -> AStarResult {
+        let graph = grid_graph(5);
+        let mut algorithm = AStarAlgorithm::new();
+        algorithm.set_tie_breaking(tie_breaking);
+        return algorithm.search(&graph, 1, 25, |e| *e, manhattan).unwrap();
+    }
+
+    #[test]
+    fn finds_shortest_path_on_small_graph() {
+        // a -1- b -1- d is cheaper than the direct a -5- d and than a -1- c -3- d.
+        let mut graph: Graph<(f64, f64), f64> = Graph::new(false, false);
+        let a = graph.add_node((0_f64, 0_f64));
+        let b = graph.add_node((1_f64, 1_f64));
+        let c = graph.add_node((1_f64, -1_f64));
+        let d = graph.add_node((2_f64, 0_f64));
+        graph.add_edge(a, d, 5_f64).unwrap();
+        let edge_ab = graph.add_edge(a, b, 1_f64).unwrap();
+        let edge_bd = graph.add_edge(b, d, 1_f64).unwrap();
+        graph.add_edge(a, c, 1_f64).unwrap();
+        graph.add_edge(c, d, 3_f64).unwrap();
+
+        let result = AStarAlgorithm::new()
+            .search(&graph, a, d, |e| *e, |_, _| 0_f64)
+            .unwrap();
+        let path = result.path.unwrap();
+        assert_eq!(path.nodes, vec![a, b, d]);
+        assert_eq!(path.edges, vec![edge_ab, edge_bd]);
+        assert_eq!(path.cost, 2_f64);
+        assert!(!result.statistics.is_expansion_limit_reached);
+    }
+
+    #[test]
+    fn tie_breaking_toward_goal_expands_only_the_path() {
+        let low = search_grid(AStarTieBreaking::PreferLowHeuristic);
+        let high = search_grid(AStarTieBreaking::PreferHighHeuristic);
+
+        // Every monotone path across the grid costs 8; preferring low heuristics walks
+        // straight along one of them, expanding every path node except the goal.
+        assert_eq!(low.path.as_ref().unwrap().cost, 8_f64);
+        assert_eq!(high.path.as_ref().unwrap().cost, 8_f64);
+        assert_eq!(low.statistics.expanded_node_count, 8);
+        assert_eq!(low.statistics.reopened_node_count, 0);
+        assert!(high.statistics.expanded_node_count > low.statistics.expanded_node_count);
+    }
+
+    #[test]
+    fn expansion_limit_stops_search() {
+        let graph = grid_graph(5);
+        let mut algorithm = AStarAlgorithm::new();
+        algorithm.set_expansion_limit(Some(3));
+
+        let result = algorithm.search(&graph, 1, 25, |e| *e, manhattan).unwrap();
+        assert_eq!(result.path, None);
+        assert!(result.statistics.is_expansion_limit_reached);
+        assert_eq!(result.statistics.expanded_node_count, 3);
+
+        // A limit that is large enough does not change the result.
+        algorithm.set_expansion_limit(Some(8));
+        let result = algorithm.search(&graph, 1, 25, |e| *e, manhattan).unwrap();
+        assert_eq!(result.path.unwrap().cost, 8_f64);
+        assert!(!result.statistics.is_expansion_limit_reached);
+    }
+
+    #[test]
+    fn unreachable_goal_and_invalid_costs() {
+        let mut graph: Graph<(f64, f64), f64> = Graph::new(false, false);
+        let node0 = graph.add_node((0_f64, 0_f64));
+        let node1 = graph.add_node((1_f64, 0_f64));
+        graph
+            .add_directed_edge(node1, node0, true, false, 1_f64)
+            .unwrap();
+
+        // The only edge leads from the goal back to the start.
+        let result = AStarAlgorithm::new()
+            .search(&graph, node0, node1, |e| *e, manhattan)
+            .unwrap();
+        assert_eq!(result.path, None);
+        assert!(!result.statistics.is_expansion_limit_reached);
+
+        for cost in [-1_f64, f64::NAN] {
+            assert_eq!(
+                AStarAlgorithm::new()
+                    .search(&graph, node1, node0, |_| cost, manhattan)
+                    .err(),
+                Some(GraphError::InvalidEdgeCost)
+            );
+        }
+        assert_eq!(
+            AStarAlgorithm::new()
+                .search(&graph, node0, 7, |e| *e, manhattan)
+                .err(),
+            Some(GraphError::NoSuchNode)
+        );
+    }
+}
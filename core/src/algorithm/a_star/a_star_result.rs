@@ -0,0 +1,22 @@
+use crate::graph::graph_path::GraphPath;
+
+/// Outcome of an A* search.
+#[derive(Clone, Debug)]
+pub struct AStarResult {
+    /// Path to the goal node, or None if the goal is unreachable or the expansion limit was hit.
+    pub path: Option<GraphPath>,
+    pub statistics: AStarStatistics,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AStarStatistics {
+    /// Number of nodes taken from the open set and expanded.
+    pub expanded_node_count: usize,
+    /// Number of entries pushed to the open set.
+    pub generated_node_count: usize,
+    /// Number of already expanded nodes expanded again because a cheaper path was found.
+    /// Stays zero for consistent heuristics.
+    pub reopened_node_count: usize,
+    pub max_open_set_size: usize,
+    pub is_expansion_limit_reached: bool,
+}
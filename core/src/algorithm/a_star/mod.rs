@@ -0,0 +1,2 @@
+pub mod a_star_algorithm;
+pub mod a_star_result;
@@ -0,0 +1,39 @@
+use std::collections::HashMap;
+
+/// Betweenness centrality of nodes and edges.
+///
+/// The centrality of a node or edge is the sum, over ordered pairs of distinct start and
+/// goal nodes, of the fraction of shortest paths between them that pass through it.
+/// Start and goal nodes of a path do not count as passed through. High values mark the
+/// junctions and corridors most traffic depends on.
+#[derive(Clone, Debug, Default)]
+pub struct BetweennessCentrality {
+    pub node_centralities: HashMap<u32, f64>,
+    pub edge_centralities: HashMap<u32, f64>,
+}
+
+impl BetweennessCentrality {
+    pub fn node_centrality(&self, node_id: u32) -> Option<f64> {
+        return self.node_centralities.get(&node_id).copied();
+    }
+
+    pub fn edge_centrality(&self, edge_id: u32) -> Option<f64> {
+        return self.edge_centralities.get(&edge_id).copied();
+    }
+
+    /// Nodes sorted by descending centrality, ties in ascending ID order.
+    pub fn ranked_nodes(&self) -> Vec<(u32, f64)> {
+        return BetweennessCentrality::ranked(&self.node_centralities);
+    }
+
+    /// Edges sorted by descending centrality, ties in ascending ID order.
+    pub fn ranked_edges(&self) -> Vec<(u32, f64)> {
+        return BetweennessCentrality::ranked(&self.edge_centralities);
+    }
+
+    fn ranked(centralities: &HashMap<u32, f64>) -> Vec<(u32, f64)> {
+        let mut ranked: Vec<(u32, f64)> = centralities.iter().map(|(k, v)| (*k, *v)).collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        return ranked;
+    }
+}
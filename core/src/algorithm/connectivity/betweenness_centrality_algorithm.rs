@@ -0,0 +1,276 @@
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use crate::{
+    algorithm::dijkstra::cost_queue_entry::CostQueueEntry,
+    graph::{graph_error::GraphError, graph_view::GraphView},
+};
+
+use super::betweenness_centrality::BetweennessCentrality;
+
+/// Betweenness centrality of `Graph` with Brandes' algorithm.
+///
+/// Shortest paths follow edges only in the directions they allow, with costs computed
+/// from edge info by a caller-supplied function, like in `DijkstraAlgorithm`. Paths of
+/// equal cost share the credit. The exact computation runs one search from every node;
+/// the sampled computation runs searches from a random subset of nodes and scales the
+/// result, trading accuracy for speed on large maps.
+pub struct BetweennessCentralityAlgorithm;
+
+impl BetweennessCentralityAlgorithm {
+    pub fn exact<GraphT, CostFuncT>(
+        graph: &GraphT,
+        cost_func: CostFuncT,
+    ) -> Result<BetweennessCentrality, GraphError>
+    where
+        GraphT: GraphView,
+        CostFuncT: Fn(&GraphT::EdgeInfo) -> f64,
+    {
+        let mut source_nodes: Vec<u32> = graph.node_ids().collect();
+        source_nodes.sort();
+        return BetweennessCentralityAlgorithm::accumulate(graph, &source_nodes, 1_f64, &cost_func);
+    }
+
+    /// Estimate centrality from searches started at `sample_count` distinct nodes, chosen
+    /// pseudo-randomly from given seed so that results are reproducible.
+    pub fn sampled<GraphT, CostFuncT>(
+        graph: &GraphT,
+        sample_count: usize,
+        seed: u64,
+        cost_func: CostFuncT,
+    ) -> Result<BetweennessCentrality, GraphError>
+    where
+        GraphT: GraphView,
+        CostFuncT: Fn(&GraphT::EdgeInfo) -> f64,
+    {
+        let mut source_nodes: Vec<u32> = graph.node_ids().collect();
+        source_nodes.sort();
+        let node_count = source_nodes.len();
+        if sample_count >= node_count {
+            return BetweennessCentralityAlgorithm::accumulate(
+                graph,
+                &source_nodes,
+                1_f64,
+                &cost_func,
+            );
+        }
+
+        // Partial Fisher-Yates shuffle driven by SplitMix64.
+        let mut state = seed;
+        for i in 0..sample_count {
+            state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^= z >> 31;
+
+            let j = i + (z % (node_count - i) as u64) as usize;
+            source_nodes.swap(i, j);
+        }
+        source_nodes.truncate(sample_count);
+
+        let scale = node_count as f64 / sample_count as f64;
+        return BetweennessCentralityAlgorithm::accumulate(graph, &source_nodes, scale, &cost_func);
+    }
+
+    fn accumulate<GraphT, CostFuncT>(
+        graph: &GraphT,
+        source_nodes: &[u32],
+        scale: f64,
+        cost_func: &CostFuncT,
+    ) -> Result<BetweennessCentrality, GraphError>
+    where
+        GraphT: GraphView,
+        CostFuncT: Fn(&GraphT::EdgeInfo) -> f64,
+    {
+        let mut result = BetweennessCentrality {
+            node_centralities: graph.node_ids().map(|n| (n, 0_f64)).collect(),
+            edge_centralities: graph.edge_ids().map(|e| (e, 0_f64)).collect(),
+        };
+
+        for source_node in source_nodes {
+            let source_node = *source_node;
+            let mut distances: HashMap<u32, f64> = HashMap::new();
+            let mut path_counts: HashMap<u32, f64> = HashMap::new();
+            let mut predecessors: HashMap<u32, Vec<(u32, u32)>> = HashMap::new();
+            let mut settled: HashSet<u32> = HashSet::new();
+            let mut settled_order: Vec<u32> = Vec::new();
+            let mut queue: BinaryHeap<CostQueueEntry> = BinaryHeap::new();
+
+            distances.insert(source_node, 0_f64);
+            path_counts.insert(source_node, 1_f64);
+            queue.push(CostQueueEntry {
+                cost: 0_f64,
+                node_id: source_node,
+            });
+
+            while let Some(CostQueueEntry { cost, node_id }) = queue.pop() {
+                if cost > distances[&node_id] || !settled.insert(node_id) {
+                    continue;
+                }
+                settled_order.push(node_id);
+
+                for (edge_id, next_node_id) in graph.connected_edges(node_id) {
+                    let edge = graph.get_edge_by_id(&edge_id).unwrap();
+                    if settled.contains(&next_node_id) || !edge.can_move_from(node_id) {
+                        continue;
+                    }
+
+                    let edge_cost = cost_func(edge.edge_info());
+                    if edge_cost.is_nan() || edge_cost < 0_f64 {
+                        return Err(GraphError::InvalidEdgeCost);
+                    }
+
+                    let next_cost = cost + edge_cost;
+                    let node_path_count = path_counts[&node_id];
+                    match distances.get(&next_node_id) {
+                        Some(d) if next_cost > *d => {}
+                        Some(d) if next_cost == *d => {
+                            *path_counts.get_mut(&next_node_id).unwrap() += node_path_count;
+                            predecessors
+                                .get_mut(&next_node_id)
+                                .unwrap()
+                                .push((node_id, edge_id));
+                        }
+                        _ => {
+                            distances.insert(next_node_id, next_cost);
+                            path_counts.insert(next_node_id, node_path_count);
+                            predecessors.insert(next_node_id, vec![(node_id, edge_id)]);
+                            queue.push(CostQueueEntry {
+                                cost: next_cost,
+                                node_id: next_node_id,
+                            });
+                        }
+                    }
+                }
+            }
+
+            // Accumulate dependencies from the farthest node back to the source.
+            let mut dependencies: HashMap<u32, f64> = HashMap::new();
+            for node_id in settled_order.iter().rev() {
+                let dependency = *dependencies.get(node_id).unwrap_or(&0_f64);
+                if let Some(node_predecessors) = predecessors.get(node_id) {
+                    let node_path_count = path_counts[node_id];
+                    for (previous_node, edge_id) in node_predecessors {
+                        let credit =
+                            path_counts[previous_node] / node_path_count * (1_f64 + dependency);
+                        *result.edge_centralities.get_mut(edge_id).unwrap() += credit * scale;
+                        *dependencies.entry(*previous_node).or_insert(0_f64) += credit;
+                    }
+                }
+                if *node_id != source_node {
+                    *result.node_centralities.get_mut(node_id).unwrap() += dependency * scale;
+                }
+            }
+        }
+
+        return Ok(result);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::graph::Graph;
+
+    fn assert_close(actual: Option<f64>, expected: f64) {
+        assert!(
+            (actual.unwrap() - expected).abs() < 1e-9,
+            "{:?} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn path_credits_the_middle_node_and_both_edges() {
+        let mut graph: Graph<(), f64> = Graph::new(false, false);
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        let c = graph.add_node(());
+        let ab = graph.add_edge(a, b, 1_f64).unwrap();
+        let bc = graph.add_edge(b, c, 1_f64).unwrap();
+
+        let centrality = BetweennessCentralityAlgorithm::exact(&graph, |w| *w).unwrap();
+        assert_close(centrality.node_centrality(a), 0_f64);
+        assert_close(centrality.node_centrality(b), 2_f64);
+        assert_close(centrality.node_centrality(c), 0_f64);
+        assert_close(centrality.edge_centrality(ab), 4_f64);
+        assert_close(centrality.edge_centrality(bc), 4_f64);
+        assert_eq!(centrality.ranked_nodes()[0].0, b);
+    }
+
+    #[test]
+    fn equal_cost_paths_share_credit_and_one_way_edges_are_respected() {
+        // Square a-b-d-c-a: every pair of opposite nodes has two shortest paths.
+        let mut graph: Graph<(), f64> = Graph::new(false, false);
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        let c = graph.add_node(());
+        let d = graph.add_node(());
+        graph.add_edge(a, b, 1_f64).unwrap();
+        graph.add_edge(b, d, 1_f64).unwrap();
+        graph.add_edge(d, c, 1_f64).unwrap();
+        graph.add_edge(c, a, 1_f64).unwrap();
+
+        let centrality = BetweennessCentralityAlgorithm::exact(&graph, |w| *w).unwrap();
+        for node_id in [a, b, c, d] {
+            assert_close(centrality.node_centrality(node_id), 1_f64);
+        }
+
+        // One-way chain: only the a to c path passes b.
+        let mut graph: Graph<(), f64> = Graph::new(false, false);
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        let c = graph.add_node(());
+        graph.add_directed_edge(a, b, true, false, 1_f64).unwrap();
+        graph.add_directed_edge(c, b, false, true, 1_f64).unwrap();
+
+        let centrality = BetweennessCentralityAlgorithm::exact(&graph, |w| *w).unwrap();
+        assert_close(centrality.node_centrality(b), 1_f64);
+    }
+
+    #[test]
+    fn sampling_all_nodes_matches_exact_and_is_reproducible() {
+        let mut graph: Graph<(), f64> = Graph::new(false, false);
+        let nodes: Vec<u32> = (0..12).map(|_| graph.add_node(())).collect();
+        for i in 0..nodes.len() {
+            graph
+                .add_edge(
+                    nodes[i],
+                    nodes[(i + 1) % nodes.len()],
+                    1_f64 + (i % 3) as f64,
+                )
+                .unwrap();
+        }
+        graph.add_edge(nodes[0], nodes[6], 2_f64).unwrap();
+
+        let exact = BetweennessCentralityAlgorithm::exact(&graph, |w| *w).unwrap();
+        let full = BetweennessCentralityAlgorithm::sampled(&graph, 100, 7, |w| *w).unwrap();
+        for node_id in &nodes {
+            assert_close(
+                full.node_centrality(*node_id),
+                exact.node_centrality(*node_id).unwrap(),
+            );
+        }
+
+        let first = BetweennessCentralityAlgorithm::sampled(&graph, 4, 7, |w| *w).unwrap();
+        let second = BetweennessCentralityAlgorithm::sampled(&graph, 4, 7, |w| *w).unwrap();
+        assert_eq!(first.ranked_nodes(), second.ranked_nodes());
+        assert_eq!(first.ranked_edges(), second.ranked_edges());
+    }
+
+    #[test]
+    fn invalid_costs_are_rejected() {
+        let mut graph: Graph<(), f64> = Graph::new(false, false);
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        graph.add_edge(a, b, 1_f64).unwrap();
+
+        for cost in [f64::NAN, -1_f64] {
+            assert_eq!(
+                BetweennessCentralityAlgorithm::exact(&graph, |_| cost).err(),
+                Some(GraphError::InvalidEdgeCost)
+            );
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::graph::graph_view::GraphView;
+
+/// Detection of the edges and nodes of `Graph` whose removal disconnects the graph.
+///
+/// Edge directions are ignored, so that a corridor counts as a single point of failure
+/// whenever losing it splits the map, regardless of the direction it may be traversed in.
+/// Parallel edges between the same nodes back each other up and are never bridges.
+pub struct CutElementsAlgorithm;
+
+struct DepthFirstFrame {
+    node_id: u32,
+    parent_edge: Option<u32>,
+    neighbors: Vec<(u32, u32)>,
+    position: usize,
+}
+
+impl CutElementsAlgorithm {
+    /// Find edges whose removal increases the number of connected components.
+    /// Returned IDs are sorted.
+    pub fn bridges<GraphT: GraphView>(graph: &GraphT) -> Vec<u32> {
+        let (mut bridges, _) = CutElementsAlgorithm::search(graph);
+        bridges.sort();
+        return bridges;
+    }
+
+    /// Find nodes whose removal increases the number of connected components.
+    /// Returned IDs are sorted.
+    pub fn articulation_points<GraphT: GraphView>(graph: &GraphT) -> Vec<u32> {
+        let (_, articulation_points) = CutElementsAlgorithm::search(graph);
+        let mut articulation_points: Vec<u32> = articulation_points.into_iter().collect();
+        articulation_points.sort();
+        return articulation_points;
+    }
+
+    /// Depth-first search computing discovery times and low links of every node, with
+    /// an explicit stack so that long corridors do not overflow the thread stack.
+    fn search<GraphT: GraphView>(graph: &GraphT) -> (Vec<u32>, HashSet<u32>) {
+        let mut bridges: Vec<u32> = Vec::new();
+        let mut articulation_points: HashSet<u32> = HashSet::new();
+        let mut discovery: HashMap<u32, usize> = HashMap::new();
+        let mut low_links: HashMap<u32, usize> = HashMap::new();
+        let mut next_time: usize = 0;
+
+        let mut root_nodes: Vec<u32> = graph.node_ids().collect();
+        root_nodes.sort();
+
+        for root_node in root_nodes {
+            if discovery.contains_key(&root_node) {
+                continue;
+            }
+
+            discovery.insert(root_node, next_time);
+            low_links.insert(root_node, next_time);
+            next_time += 1;
+
+            let mut root_children: usize = 0;
+            let mut stack: Vec<DepthFirstFrame> = vec![DepthFirstFrame {
+                node_id: root_node,
+                parent_edge: None,
+                neighbors: CutElementsAlgorithm::neighbors(graph, root_node),
+                position: 0,
+            }];
+
+            while let Some(frame) = stack.last_mut() {
+                let node_id = frame.node_id;
+                if frame.position < frame.neighbors.len() {
+                    let (edge_id, next_node_id) = frame.neighbors[frame.position];
+                    frame.position += 1;
+
+                    if frame.parent_edge == Some(edge_id) {
+                        continue;
+                    }
+
+                    match discovery.get(&next_node_id) {
+                        Some(next_discovery) => {
+                            let low_link = usize::min(low_links[&node_id], *next_discovery);
+                            low_links.insert(node_id, low_link);
+                        }
+                        None => {
+                            if node_id == root_node {
+                                root_children += 1;
+                            }
+                            discovery.insert(next_node_id, next_time);
+                            low_links.insert(next_node_id, next_time);
+                            next_time += 1;
+                            stack.push(DepthFirstFrame {
+                                node_id: next_node_id,
+                                parent_edge: Some(edge_id),
+                                neighbors: CutElementsAlgorithm::neighbors(graph, next_node_id),
+                                position: 0,
+                            });
+                        }
+                    }
+                    continue;
+                }
+
+                let parent_edge = frame.parent_edge;
+                stack.pop();
+
+                if let Some(parent) = stack.last() {
+                    let parent_id = parent.node_id;
+                    let node_low_link = low_links[&node_id];
+                    let low_link = usize::min(low_links[&parent_id], node_low_link);
+                    low_links.insert(parent_id, low_link);
+
+                    if node_low_link > discovery[&parent_id] {
+                        bridges.push(parent_edge.unwrap());
+                    }
+                    if parent_id != root_node && node_low_link >= discovery[&parent_id] {
+                        articulation_points.insert(parent_id);
+                    }
+                }
+            }
+
+            if root_children > 1 {
+                articulation_points.insert(root_node);
+            }
+        }
+
+        return (bridges, articulation_points);
+    }
+
+    /// Connected edges of given node as (edge, neighbor) pairs, without self-loops.
+    fn neighbors<GraphT: GraphView>(graph: &GraphT, node_id: u32) -> Vec<(u32, u32)> {
+        return graph
+            .connected_edges(node_id)
+            .filter(|(_, next_node_id)| *next_node_id != node_id)
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::graph::Graph;
+
+    #[test]
+    fn finds_bridges_and_articulation_points() {
+        // Two triangles joined by a bridge, then a pendant node reached over parallel edges.
+        let mut graph: Graph<(), ()> = Graph::new(true, true);
+        let n: Vec<u32> = (0..8).map(|_| graph.add_node(())).collect();
+        graph.add_edge(n[0], n[1], ()).unwrap();
+        graph.add_edge(n[1], n[2], ()).unwrap();
+        graph.add_edge(n[2], n[0], ()).unwrap();
+        let bridge = graph
+            .add_directed_edge(n[2], n[3], true, false, ())
+            .unwrap();
+        graph.add_edge(n[3], n[4], ()).unwrap();
+        graph.add_edge(n[4], n[5], ()).unwrap();
+        graph.add_edge(n[5], n[3], ()).unwrap();
+        let pendant = graph.add_edge(n[5], n[6], ()).unwrap();
+        graph.add_edge(n[6], n[7], ()).unwrap();
+        graph.add_edge(n[7], n[6], ()).unwrap();
+        graph.add_edge(n[7], n[7], ()).unwrap();
+
+        assert_eq!(CutElementsAlgorithm::bridges(&graph), vec![bridge, pendant]);
+        assert_eq!(
+            CutElementsAlgorithm::articulation_points(&graph),
+            vec![n[2], n[3], n[5], n[6]]
+        );
+    }
+
+    #[test]
+    fn root_is_articulation_point_only_with_several_children() {
+        let mut graph: Graph<(), ()> = Graph::new(false, false);
+        let center = graph.add_node(());
+        let leaves: Vec<u32> = (0..3).map(|_| graph.add_node(())).collect();
+        for leaf in &leaves {
+            graph.add_edge(center, *leaf, ()).unwrap();
+        }
+        let isolated = graph.add_node(());
+
+        assert_eq!(
+            CutElementsAlgorithm::articulation_points(&graph),
+            vec![center]
+        );
+        assert_eq!(CutElementsAlgorithm::bridges(&graph).len(), 3);
+        assert!(!CutElementsAlgorithm::articulation_points(&graph).contains(&isolated));
+    }
+
+    #[test]
+    fn long_path_does_not_overflow_the_stack() {
+        let mut graph: Graph<(), ()> = Graph::new(false, false);
+        let nodes: Vec<u32> = (0..100_000).map(|_| graph.add_node(())).collect();
+        for pair in nodes.windows(2) {
+            graph.add_edge(pair[0], pair[1], ()).unwrap();
+        }
+
+        assert_eq!(CutElementsAlgorithm::bridges(&graph).len(), nodes.len() - 1);
+        assert_eq!(
+            CutElementsAlgorithm::articulation_points(&graph),
+            nodes[1..nodes.len() - 1].to_vec()
+        );
+    }
+}
@@ -0,0 +1,3 @@
+pub mod betweenness_centrality;
+pub mod betweenness_centrality_algorithm;
+pub mod cut_elements_algorithm;
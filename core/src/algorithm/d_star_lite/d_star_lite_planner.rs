@@ -0,0 +1,635 @@
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, HashSet},
+};
+
+use crate::graph::{
+    graph_change::GraphChange, graph_error::GraphError, graph_path::GraphPath,
+    graph_view::GraphView,
+};
+
+/// Incremental shortest path planner based on D* Lite (Koenig and Likhachev).
+///
+/// The planner searches backward from the goal and keeps its search state between
+/// calls. When edges are removed, added or re-weighted, only the affected part of the
+/// search is repaired, which is much cheaper than planning from scratch when a robot
+/// replans constantly while moving toward a fixed goal.
+///
+/// Changes are read from the change log of the graph, so `Graph::enable_change_log`
+/// must be called before the planner is created. Edges must be modified through
+/// `Graph::update_edge_info` for their new costs to be noticed. If the changes cannot
+/// be read, because the log is disabled or was trimmed past the last plan, the planner
+/// starts over; `is_last_plan_incremental` tells whether this happened.
+///
+/// Changes of the predicates of a `FilteredGraph` are not recorded in the change log,
+/// so `restart` must be called after nodes or edges of a filtered view were hidden or
+/// shown by other means than changes of the underlying graph.
+///
+/// Costs and heuristic are supplied on every call, like in `AStarAlgorithm`, and must
+/// not change between calls except through changes of the graph. The heuristic must be
+/// consistent, such as the Euclidean distance between node positions.
+pub struct DStarLitePlanner {
+    start_node: u32,
+    goal_node: u32,
+    last_start_node: u32,
+    key_modifier: f64,
+    g_values: HashMap<u32, f64>,
+    rhs_values: HashMap<u32, f64>,
+    open_keys: HashMap<u32, (f64, f64)>,
+    open_queue: BinaryHeap<OpenQueueEntry>,
+    revision: Option<u64>,
+    expanded_node_count: usize,
+    last_plan_incremental: bool,
+}
+
+struct OpenQueueEntry {
+    key: (f64, f64),
+    node_id: u32,
+}
+
+impl DStarLitePlanner {
+    pub fn new<GraphT: GraphView>(
+        graph: &GraphT,
+        start_node: u32,
+        goal_node: u32,
+    ) -> Result<Self, GraphError> {
+        if graph.get_node_by_id(&start_node).is_none() || graph.get_node_by_id(&goal_node).is_none()
+        {
+            return Err(GraphError::NoSuchNode);
+        }
+
+        return Ok(Self {
+            start_node: start_node,
+            goal_node: goal_node,
+            last_start_node: start_node,
+            key_modifier: 0_f64,
+            g_values: HashMap::new(),
+            rhs_values: HashMap::new(),
+            open_keys: HashMap::new(),
+            open_queue: BinaryHeap::new(),
+            revision: None,
+            expanded_node_count: 0,
+            last_plan_incremental: false,
+        });
+    }
+
+    pub fn get_start_node(&self) -> u32 {
+        return self.start_node;
+    }
+
+    pub fn get_goal_node(&self) -> u32 {
+        return self.goal_node;
+    }
+
+    /// Number of nodes expanded by the last call to `plan`.
+    pub fn get_expanded_node_count(&self) -> usize {
+        return self.expanded_node_count;
+    }
+
+    /// Revision of the graph the last call to `plan` brought the search up to, that is
+    /// the oldest revision whose later changes the planner still needs.
+    pub fn get_revision(&self) -> Option<u64> {
+        return self.revision;
+    }
+
+    /// Check if the last call to `plan` repaired the previous search from the change log
+    /// of the graph, rather than starting over.
+    pub fn is_last_plan_incremental(&self) -> bool {
+        return self.last_plan_incremental;
+    }
+
+    /// Drop the search state, so that the next call to `plan` starts over.
+    pub fn restart(&mut self) {
+        self.revision = None;
+    }
+
+    /// Move the start to given node, typically the node the robot has just reached.
+    /// The search state is kept, unless the previous start node was removed from the
+    /// graph, in which case the next call to `plan` starts over.
+    pub fn set_start_node<GraphT, HeuristicFuncT>(
+        &mut self,
+        graph: &GraphT,
+        start_node: u32,
+        heuristic_func: HeuristicFuncT,
+    ) -> Result<(), GraphError>
+    where
+        GraphT: GraphView,
+        HeuristicFuncT: Fn(&GraphT::NodeInfo, &GraphT::NodeInfo) -> f64,
+    {
+        let new_start = match graph.get_node_by_id(&start_node) {
+            Some(n) => n,
+            None => return Err(GraphError::NoSuchNode),
+        };
+
+        // Keys computed from the old start are kept valid by raising all new keys instead.
+        if let Some(last_start) = graph.get_node_by_id(&self.last_start_node) {
+            self.key_modifier += heuristic_func(last_start.node_info(), new_start.node_info());
+            self.last_start_node = start_node;
+        } else {
+            self.revision = None;
+        }
+        self.start_node = start_node;
+        return Ok(());
+    }
+
+    /// Bring the search up to date with the graph and return the shortest path from the
+    /// start node to the goal node, or None if the goal cannot be reached.
+    pub fn plan<GraphT, CostFuncT, HeuristicFuncT>(
+        &mut self,
+        graph: &GraphT,
+        cost_func: CostFuncT,
+        heuristic_func: HeuristicFuncT,
+    ) -> Result<Option<GraphPath>, GraphError>
+    where
+        GraphT: GraphView,
+        CostFuncT: Fn(&GraphT::EdgeInfo) -> f64,
+        HeuristicFuncT: Fn(&GraphT::NodeInfo, &GraphT::NodeInfo) -> f64,
+    {
+        if graph.get_node_by_id(&self.start_node).is_none()
+            || graph.get_node_by_id(&self.goal_node).is_none()
+        {
+            return Err(GraphError::NoSuchNode);
+        }
+
+        self.expanded_node_count = 0;
+        let changes = match self.revision {
+            Some(revision) => graph.changes_since(revision),
+            None => None,
+        };
+        self.last_plan_incremental = changes.is_some();
+        match changes {
+            Some(changes) => {
+                let mut changed_nodes: HashSet<u32> = HashSet::new();
+                for change in changes {
+                    match change {
+                        GraphChange::NodeRemoved { node_id } => {
+                            self.forget_node(*node_id);
+                        }
+                        GraphChange::EdgeAdded {
+                            node1_id, node2_id, ..
+                        }
+                        | GraphChange::EdgeRemoved {
+                            node1_id, node2_id, ..
+                        }
+                        | GraphChange::EdgeUpdated {
+                            node1_id, node2_id, ..
+                        } => {
+                            changed_nodes.insert(*node1_id);
+                            changed_nodes.insert(*node2_id);
+                        }
+                        GraphChange::NodeAdded { .. } => {}
+                    }
+                }
+
+                let mut changed_nodes: Vec<u32> = changed_nodes.into_iter().collect();
+                changed_nodes.sort();
+                for node_id in changed_nodes {
+                    if graph.get_node_by_id(&node_id).is_some() {
+                        self.update_node(graph, node_id, &cost_func, &heuristic_func)?;
+                    }
+                }
+            }
+            None => self.reset(graph, &heuristic_func),
+        }
+        self.revision = Some(graph.get_revision());
+
+        self.compute_shortest_path(graph, &cost_func, &heuristic_func)?;
+        return self.extract_path(graph, &cost_func);
+    }
+
+    fn reset<GraphT, HeuristicFuncT>(&mut self, graph: &GraphT, heuristic_func: &HeuristicFuncT)
+    where
+        GraphT: GraphView,
+        HeuristicFuncT: Fn(&GraphT::NodeInfo, &GraphT::NodeInfo) -> f64,
+    {
+        self.last_start_node = self.start_node;
+        self.key_modifier = 0_f64;
+        self.g_values.clear();
+        self.rhs_values.clear();
+        self.open_keys.clear();
+        self.open_queue.clear();
+
+        self.rhs_values.insert(self.goal_node, 0_f64);
+        let key = self.calculate_key(graph, self.goal_node, heuristic_func);
+        self.push_open(self.goal_node, key);
+    }
+
+    fn forget_node(&mut self, node_id: u32) {
+        self.g_values.remove(&node_id);
+        self.rhs_values.remove(&node_id);
+        self.open_keys.remove(&node_id);
+    }
+
+    fn g_value(&self, node_id: u32) -> f64 {
+        return *self.g_values.get(&node_id).unwrap_or(&f64::INFINITY);
+    }
+
+    fn rhs_value(&self, node_id: u32) -> f64 {
+        return *self.rhs_values.get(&node_id).unwrap_or(&f64::INFINITY);
+    }
+
+    fn calculate_key<GraphT, HeuristicFuncT>(
+        &self,
+        graph: &GraphT,
+        node_id: u32,
+        heuristic_func: &HeuristicFuncT,
+    ) -> (f64, f64)
+    where
+        GraphT: GraphView,
+        HeuristicFuncT: Fn(&GraphT::NodeInfo, &GraphT::NodeInfo) -> f64,
+    {
+        let value = f64::min(self.g_value(node_id), self.rhs_value(node_id));
+        let start_info = graph.get_node_by_id(&self.start_node).unwrap().node_info();
+        let node_info = graph.get_node_by_id(&node_id).unwrap().node_info();
+        let heuristic = heuristic_func(start_info, node_info);
+        return (value + heuristic + self.key_modifier, value);
+    }
+
+    fn push_open(&mut self, node_id: u32, key: (f64, f64)) {
+        self.open_keys.insert(node_id, key);
+        self.open_queue.push(OpenQueueEntry {
+            key: key,
+            node_id: node_id,
+        });
+    }
+
+    /// Top entry of the open queue, dropping entries superseded or removed since pushed.
+    fn peek_open(&mut self) -> Option<(u32, (f64, f64))> {
+        while let Some(entry) = self.open_queue.peek() {
+            match self.open_keys.get(&entry.node_id) {
+                Some(key) if *key == entry.key => return Some((entry.node_id, entry.key)),
+                _ => {
+                    self.open_queue.pop();
+                }
+            }
+        }
+
+        return None;
+    }
+
+    /// Cost of given edge when moving from given node, or None if it cannot be traversed.
+    fn edge_cost<GraphT, CostFuncT>(
+        graph: &GraphT,
+        edge_id: u32,
+        from_node: u32,
+        cost_func: &CostFuncT,
+    ) -> Result<Option<f64>, GraphError>
+    where
+        GraphT: GraphView,
+        CostFuncT: Fn(&GraphT::EdgeInfo) -> f64,
+    {
+        let edge = graph.get_edge_by_id(&edge_id).unwrap();
+        if edge.node1() == edge.node2() || !edge.can_move_from(from_node) {
+            return Ok(None);
+        }
+
+        let cost = cost_func(edge.edge_info());
+        if cost.is_nan() || cost < 0_f64 {
+            return Err(GraphError::InvalidEdgeCost);
+        }
+        return Ok(Some(cost));
+    }
+
+    fn update_node<GraphT, CostFuncT, HeuristicFuncT>(
+        &mut self,
+        graph: &GraphT,
+        node_id: u32,
+        cost_func: &CostFuncT,
+        heuristic_func: &HeuristicFuncT,
+    ) -> Result<(), GraphError>
+    where
+        GraphT: GraphView,
+        CostFuncT: Fn(&GraphT::EdgeInfo) -> f64,
+        HeuristicFuncT: Fn(&GraphT::NodeInfo, &GraphT::NodeInfo) -> f64,
+    {
+        if node_id != self.goal_node {
+            let mut rhs = f64::INFINITY;
+            for (edge_id, next_node_id) in graph.connected_edges(node_id) {
+                if let Some(cost) = DStarLitePlanner::edge_cost(graph, edge_id, node_id, cost_func)?
+                {
+                    rhs = f64::min(rhs, cost + self.g_value(next_node_id));
+                }
+            }
+            self.rhs_values.insert(node_id, rhs);
+        }
+
+        self.open_keys.remove(&node_id);
+        if self.g_value(node_id) != self.rhs_value(node_id) {
+            let key = self.calculate_key(graph, node_id, heuristic_func);
+            self.push_open(node_id, key);
+        }
+
+        return Ok(());
+    }
+
+    /// Nodes that can move to given node, over edges of any cost.
+    fn predecessors<GraphT: GraphView>(graph: &GraphT, node_id: u32) -> Vec<u32> {
+        return graph
+            .connected_edges(node_id)
+            .filter(|(edge_id, previous_node_id)| {
+                let edge = graph.get_edge_by_id(edge_id).unwrap();
+                return *previous_node_id != node_id && edge.can_move_from(*previous_node_id);
+            })
+            .map(|(_, previous_node_id)| previous_node_id)
+            .collect();
+    }
+
+    fn compute_shortest_path<GraphT, CostFuncT, HeuristicFuncT>(
+        &mut self,
+        graph: &GraphT,
+        cost_func: &CostFuncT,
+        heuristic_func: &HeuristicFuncT,
+    ) -> Result<(), GraphError>
+    where
+        GraphT: GraphView,
+        CostFuncT: Fn(&GraphT::EdgeInfo) -> f64,
+        HeuristicFuncT: Fn(&GraphT::NodeInfo, &GraphT::NodeInfo) -> f64,
+    {
+        while let Some((node_id, old_key)) = self.peek_open() {
+            let start_key = self.calculate_key(graph, self.start_node, heuristic_func);
+            // Stop once the start node is consistent and nothing cheaper is left to expand.
+            let start_g = self.g_value(self.start_node);
+            if DStarLitePlanner::compare_keys(old_key, start_key) != Ordering::Less
+                && self.rhs_value(self.start_node) == start_g
+            {
+                break;
+            }
+
+            let new_key = self.calculate_key(graph, node_id, heuristic_func);
+            if DStarLitePlanner::compare_keys(old_key, new_key) == Ordering::Less {
+                self.push_open(node_id, new_key);
+                continue;
+            }
+
+            self.open_keys.remove(&node_id);
+            self.expanded_node_count += 1;
+
+            let g = self.g_value(node_id);
+            let rhs = self.rhs_value(node_id);
+            if g > rhs {
+                self.g_values.insert(node_id, rhs);
+            } else {
+                self.g_values.insert(node_id, f64::INFINITY);
+                self.update_node(graph, node_id, cost_func, heuristic_func)?;
+            }
+
+            for previous_node_id in DStarLitePlanner::predecessors(graph, node_id) {
+                self.update_node(graph, previous_node_id, cost_func, heuristic_func)?;
+            }
+        }
+
+        return Ok(());
+    }
+
+    /// Follow the cheapest successors from the start node down to the goal node.
+    fn extract_path<GraphT, CostFuncT>(
+        &self,
+        graph: &GraphT,
+        cost_func: &CostFuncT,
+    ) -> Result<Option<GraphPath>, GraphError>
+    where
+        GraphT: GraphView,
+        CostFuncT: Fn(&GraphT::EdgeInfo) -> f64,
+    {
+        if self.g_value(self.start_node).is_infinite() {
+            return Ok(None);
+        }
+
+        let mut nodes: Vec<u32> = vec![self.start_node];
+        let mut edges: Vec<u32> = Vec::new();
+        let mut visited: HashSet<u32> = HashSet::from([self.start_node]);
+        let mut cost = 0_f64;
+        let mut current = self.start_node;
+        while current != self.goal_node {
+            let mut best: Option<(f64, f64, u32, u32)> = None;
+            let mut connections: Vec<(u32, u32)> = graph.connected_edges(current).collect();
+            connections.sort();
+            for (edge_id, next_node_id) in connections {
+                let edge_cost =
+                    match DStarLitePlanner::edge_cost(graph, edge_id, current, cost_func)? {
+                        Some(c) => c,
+                        None => continue,
+                    };
+                let total = edge_cost + self.g_value(next_node_id);
+                if best.is_none() || total < best.unwrap().0 {
+                    best = Some((total, edge_cost, edge_id, next_node_id));
+                }
+            }
+
+            let (_, edge_cost, edge_id, next_node_id) = match best {
+                Some(b) if b.0.is_finite() => b,
+                _ => return Ok(None),
+            };
+            if !visited.insert(next_node_id) {
+                // Only possible if costs changed without being recorded in the graph.
+                return Ok(None);
+            }
+            cost += edge_cost;
+            nodes.push(next_node_id);
+            edges.push(edge_id);
+            current = next_node_id;
+        }
+
+        return Ok(Some(GraphPath {
+            nodes: nodes,
+            edges: edges,
+            cost: cost,
+        }));
+    }
+
+    fn compare_keys(key1: (f64, f64), key2: (f64, f64)) -> Ordering {
+        return key1.0.total_cmp(&key2.0).then(key1.1.total_cmp(&key2.1));
+    }
+}
+
+impl PartialEq for OpenQueueEntry {
+    fn eq(&self, other: &Self) -> bool {
+        return self.cmp(other) == Ordering::Equal;
+    }
+}
+
+impl Eq for OpenQueueEntry {}
+
+impl PartialOrd for OpenQueueEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        return Some(self.cmp(other));
+    }
+}
+
+impl Ord for OpenQueueEntry {
+    /// Reversed so that `BinaryHeap` pops the lowest key first.
+    fn cmp(&self, other: &Self) -> Ordering {
+        return DStarLitePlanner::compare_keys(other.key, self.key)
+            .then_with(|| other.node_id.cmp(&self.node_id));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::dijkstra::dijkstra_algorithm::DijkstraAlgorithm;
+    use crate::graph::graph::Graph;
+
+    const SIZE: u32 = 8;
+
+    fn distance(a: &(f64, f64), b: &(f64, f64)) -> f64 {
+        return ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt();
+    }
+
+    /// Grid of unit spacing with pseudo-random edge costs of at least the edge length,
+    /// so that the Euclidean distance is consistent. Every fifth edge is one-way.
+    fn grid() -> (Graph<(f64, f64), f64>, Vec<u32>) {
+        let mut graph: Graph<(f64, f64), f64> = Graph::new(false, false);
+        graph.enable_change_log();
+        let mut nodes: Vec<u32> = Vec::new();
+        for y in 0..SIZE {
+            for x in 0..SIZE {
+                nodes.push(graph.add_node((x as f64, y as f64)));
+            }
+        }
+
+        let mut state: u32 = 12345;
+        let mut edge_count = 0;
+        for y in 0..SIZE {
+            for x in 0..SIZE {
+                let node = nodes[(y * SIZE + x) as usize];
+                for (dx, dy) in [(1, 0), (0, 1)] {
+                    if x + dx >= SIZE || y + dy >= SIZE {
+                        continue;
+                    }
+                    state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                    let cost = 1_f64 + ((state >> 16) % 4) as f64 * 0.5;
+                    let next = nodes[((y + dy) * SIZE + x + dx) as usize];
+                    edge_count += 1;
+                    graph
+                        .add_directed_edge(node, next, true, edge_count % 5 != 0, cost)
+                        .unwrap();
+                }
+            }
+        }
+        return (graph, nodes);
+    }
+
+    fn plan_and_compare(
+        planner: &mut DStarLitePlanner,
+        graph: &Graph<(f64, f64), f64>,
+    ) -> Option<GraphPath> {
+        let path = planner.plan(graph, |w| *w, distance).unwrap();
+        let expected = DijkstraAlgorithm::shortest_path(
+            graph,
+            planner.get_start_node(),
+            planner.get_goal_node(),
+            |w| *w,
+        )
+        .unwrap();
+        match (&path, &expected) {
+            (Some(path), Some(expected)) => {
+                assert!((path.cost - expected.cost).abs() < 1e-9);
+                let edge_costs: f64 = path
+                    .edges
+                    .iter()
+                    .map(|e| *graph.get_edge_by_id(e).unwrap().edge_info())
+                    .sum();
+                assert!((path.cost - edge_costs).abs() < 1e-9);
+            }
+            (None, None) => {}
+            _ => panic!("planner found {:?}, Dijkstra found {:?}", path, expected),
+        }
+        return path;
+    }
+
+    #[test]
+    fn replans_match_dijkstra_after_changes() {
+        let (mut graph, nodes) = grid();
+        let mut planner = DStarLitePlanner::new(&graph, nodes[0], nodes[nodes.len() - 1]).unwrap();
+
+        let path = plan_and_compare(&mut planner, &graph).unwrap();
+        assert!(!planner.is_last_plan_incremental());
+        let full_expansions = planner.get_expanded_node_count();
+
+        // Block the middle of the current path.
+        graph
+            .remove_edge(&path.edges[path.edges.len() / 2])
+            .unwrap();
+        let path = plan_and_compare(&mut planner, &graph).unwrap();
+        assert!(planner.is_last_plan_incremental());
+        assert_eq!(planner.get_revision(), Some(graph.get_revision()));
+
+        // Make the current path more expensive and an edge elsewhere cheaper.
+        graph
+            .update_edge_info(&path.edges[1], |w| *w += 5_f64)
+            .unwrap();
+        let other_edge = graph.edge_ids().find(|e| !path.edges.contains(e)).unwrap();
+        graph.update_edge_info(&other_edge, |w| *w = 1_f64).unwrap();
+        let path = plan_and_compare(&mut planner, &graph).unwrap();
+        assert!(planner.is_last_plan_incremental());
+
+        // Move along the path, then re-weight an edge next to the robot.
+        planner
+            .set_start_node(&graph, path.nodes[2], distance)
+            .unwrap();
+        let path = plan_and_compare(&mut planner, &graph).unwrap();
+        assert!(planner.is_last_plan_incremental());
+        assert!(planner.get_expanded_node_count() < full_expansions);
+        graph
+            .update_edge_info(&path.edges[0], |w| *w *= 3_f64)
+            .unwrap();
+        plan_and_compare(&mut planner, &graph).unwrap();
+        assert!(planner.is_last_plan_incremental());
+    }
+
+    #[test]
+    fn starts_over_when_changes_cannot_be_read() {
+        let (mut graph, nodes) = grid();
+        let mut planner = DStarLitePlanner::new(&graph, nodes[0], nodes[nodes.len() - 1]).unwrap();
+        plan_and_compare(&mut planner, &graph);
+
+        // Trimming up to the planner revision keeps what it needs.
+        graph.trim_change_log(planner.get_revision().unwrap());
+        let edge_id = graph.edge_ids().min().unwrap();
+        graph.remove_edge(&edge_id).unwrap();
+        plan_and_compare(&mut planner, &graph);
+        assert!(planner.is_last_plan_incremental());
+
+        let edge_id = graph.edge_ids().min().unwrap();
+        graph.remove_edge(&edge_id).unwrap();
+        graph.trim_change_log(graph.get_revision());
+        plan_and_compare(&mut planner, &graph);
+        assert!(!planner.is_last_plan_incremental());
+
+        graph.disable_change_log();
+        let edge_id = graph.edge_ids().min().unwrap();
+        graph.remove_edge(&edge_id).unwrap();
+        plan_and_compare(&mut planner, &graph);
+        assert!(!planner.is_last_plan_incremental());
+
+        graph.enable_change_log();
+        plan_and_compare(&mut planner, &graph);
+        assert!(planner.is_last_plan_incremental());
+        planner.restart();
+        plan_and_compare(&mut planner, &graph);
+        assert!(!planner.is_last_plan_incremental());
+    }
+
+    #[test]
+    fn reports_unreachable_goal_and_invalid_costs() {
+        let (mut graph, nodes) = grid();
+        let goal = nodes[nodes.len() - 1];
+        let mut planner = DStarLitePlanner::new(&graph, nodes[0], goal).unwrap();
+        assert!(plan_and_compare(&mut planner, &graph).is_some());
+
+        let goal_edges: Vec<u32> = graph.connected_edges(goal).map(|(e, _)| e).collect();
+        for edge_id in goal_edges {
+            graph.remove_edge(&edge_id).unwrap();
+        }
+        assert!(plan_and_compare(&mut planner, &graph).is_none());
+
+        let (graph, nodes) = grid();
+        let mut planner = DStarLitePlanner::new(&graph, nodes[0], goal).unwrap();
+        assert_eq!(
+            planner.plan(&graph, |_| f64::NAN, distance),
+            Err(GraphError::InvalidEdgeCost)
+        );
+        assert!(DStarLitePlanner::new(&graph, nodes[0], 9999).is_err());
+    }
+}
@@ -0,0 +1 @@
+pub mod d_star_lite_planner;
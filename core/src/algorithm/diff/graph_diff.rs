@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+
+use crate::graph::{
+    graph::{EntityEdge, Graph},
+    graph_error::GraphError,
+    graph_view::GraphView,
+};
+
+/// Differences between an old and a new version of a graph, found by `GraphDiffAlgorithm`.
+///
+/// Matched pairs are given as (old ID, new ID) and sorted by old ID; added nodes and
+/// edges are given by their IDs in the new graph, removed ones by their IDs in the old
+/// graph.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GraphDiff {
+    pub matched_nodes: Vec<(u32, u32)>,
+    pub added_nodes: Vec<u32>,
+    pub removed_nodes: Vec<u32>,
+    /// Matched nodes displaced by more than the move threshold.
+    pub moved_nodes: Vec<(u32, u32)>,
+
+    pub matched_edges: Vec<(u32, u32)>,
+    pub added_edges: Vec<u32>,
+    pub removed_edges: Vec<u32>,
+    /// Matched edges with at least one moved end node.
+    pub moved_edges: Vec<(u32, u32)>,
+
+    /// Stable ID for every node of the new graph: the old ID of matched nodes, and a fresh
+    /// ID after the old graph's allocator for added nodes.
+    pub stable_node_ids: HashMap<u32, u32>,
+    /// Stable ID for every edge of the new graph, like `stable_node_ids`.
+    pub stable_edge_ids: HashMap<u32, u32>,
+    /// ID allocators for the new graph once stable IDs are applied, so that IDs of
+    /// removed nodes and edges are never reused.
+    pub node_id_alloc: u32,
+    pub edge_id_alloc: u32,
+}
+
+impl GraphDiff {
+    /// Check if nothing was added, removed or moved.
+    pub fn is_unchanged(&self) -> bool {
+        return self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.moved_nodes.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+            && self.moved_edges.is_empty();
+    }
+
+    /// Copy the new graph with its nodes and edges renumbered to their stable IDs, so that
+    /// references to the old map stay valid for everything that was matched.
+    pub fn apply_stable_ids<GraphT>(
+        &self,
+        new_graph: &GraphT,
+    ) -> Result<Graph<GraphT::NodeInfo, GraphT::EdgeInfo>, GraphError>
+    where
+        GraphT: GraphView,
+        GraphT::NodeInfo: Clone,
+        GraphT::EdgeInfo: Clone,
+    {
+        let mut node_ids: Vec<u32> = new_graph.node_ids().collect();
+        node_ids.sort();
+        let mut nodes: Vec<(u32, GraphT::NodeInfo)> = Vec::with_capacity(node_ids.len());
+        for node_id in node_ids {
+            let node = new_graph.get_node_by_id(&node_id).unwrap();
+            let stable_id = match self.stable_node_ids.get(&node_id) {
+                Some(i) => *i,
+                None => return Err(GraphError::NoSuchNode),
+            };
+            nodes.push((stable_id, node.node_info().clone()));
+        }
+
+        let mut edge_ids: Vec<u32> = new_graph.edge_ids().collect();
+        edge_ids.sort();
+        let mut edges: Vec<EntityEdge<GraphT::EdgeInfo>> = Vec::with_capacity(edge_ids.len());
+        for edge_id in edge_ids {
+            let edge = new_graph.get_edge_by_id(&edge_id).unwrap();
+            let stable_id = match self.stable_edge_ids.get(&edge_id) {
+                Some(i) => *i,
+                None => return Err(GraphError::NoSuchEdge),
+            };
+            edges.push((
+                stable_id,
+                (
+                    self.stable_node_ids[&edge.node1()],
+                    self.stable_node_ids[&edge.node2()],
+                ),
+                (edge.can_move_forward(), edge.can_move_backward()),
+                edge.edge_info().clone(),
+            ));
+        }
+
+        let mut graph = Graph::from_entities(
+            nodes,
+            edges,
+            new_graph.allows_cyclic_edges(),
+            new_graph.allows_duplicate_edges(),
+        )?;
+        graph.advance_id_alloc(self.node_id_alloc, self.edge_id_alloc);
+        return Ok(graph);
+    }
+}
@@ -0,0 +1,432 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    graph::graph_view::GraphView,
+    numerics::{vector::Vector, vector2d::Vector2D},
+};
+
+use super::graph_diff::GraphDiff;
+
+/// Matching and diff between two versions of a map, for example the topology graphs
+/// extracted before and after re-mapping a site.
+///
+/// Nodes are matched one-to-one when they lie within the position tolerance of each
+/// other. Among several candidates, nodes whose neighbors are matched to each other are
+/// preferred, so that a crowded intersection is not matched to the waypoint next to it.
+/// Edges are matched when their end nodes are matched to each other.
+///
+/// For the topology graphs of the map crate, positions are read from the node info:
+///
+/// ```ignore
+/// let old_graph: Graph<TopologyNode, TopologyEdge> = TopologyExtractor::extract(&old_grid_map);
+/// let new_graph: Graph<TopologyNode, TopologyEdge> = TopologyExtractor::extract(&new_grid_map);
+///
+/// let diff = GraphDiffAlgorithm::new(0.5).diff(&old_graph, &new_graph, |n| n.position);
+/// // New graph with matched nodes and edges keeping their IDs from the old graph.
+/// let merged_graph = diff.apply_stable_ids(&new_graph)?;
+/// ```
+pub struct GraphDiffAlgorithm {
+    position_tolerance: f64,
+    move_threshold: f64,
+    structure_weight: f64,
+}
+
+/// Number of times the node matching is refined with the structure of the previous one.
+const REFINEMENT_ROUND_COUNT: usize = 2;
+
+impl GraphDiffAlgorithm {
+    /// Nodes farther apart than the position tolerance are never matched.
+    pub fn new(position_tolerance: f64) -> Self {
+        return Self {
+            position_tolerance: position_tolerance,
+            move_threshold: position_tolerance / 4_f64,
+            structure_weight: 1_f64,
+        };
+    }
+
+    /// Report matched nodes displaced by more than given distance as moved.
+    /// Defaults to a quarter of the position tolerance.
+    pub fn set_move_threshold(&mut self, move_threshold: f64) {
+        self.move_threshold = move_threshold;
+    }
+
+    /// Weight of structural dissimilarity against distance relative to the tolerance when
+    /// choosing between candidates. Zero matches by position only. Defaults to 1.
+    pub fn set_structure_weight(&mut self, structure_weight: f64) {
+        self.structure_weight = structure_weight;
+    }
+
+    /// Compare the old and the new graph, reading node positions with given function.
+    pub fn diff<OldGraphT, NewGraphT, PositionFuncT>(
+        &self,
+        old_graph: &OldGraphT,
+        new_graph: &NewGraphT,
+        position_func: PositionFuncT,
+    ) -> GraphDiff
+    where
+        OldGraphT: GraphView,
+        NewGraphT: GraphView<NodeInfo = OldGraphT::NodeInfo>,
+        PositionFuncT: Fn(&OldGraphT::NodeInfo) -> Vector2D,
+    {
+        let mut old_node_ids: Vec<u32> = old_graph.node_ids().collect();
+        old_node_ids.sort();
+        let mut new_node_ids: Vec<u32> = new_graph.node_ids().collect();
+        new_node_ids.sort();
+
+        let old_positions: HashMap<u32, Vector2D> = old_node_ids
+            .iter()
+            .map(|n| {
+                (
+                    *n,
+                    position_func(old_graph.get_node_by_id(n).unwrap().node_info()),
+                )
+            })
+            .collect();
+        let new_positions: HashMap<u32, Vector2D> = new_node_ids
+            .iter()
+            .map(|n| {
+                (
+                    *n,
+                    position_func(new_graph.get_node_by_id(n).unwrap().node_info()),
+                )
+            })
+            .collect();
+
+        // Candidate pairs as (old ID, new ID, distance), found through a grid of cells as
+        // large as the tolerance.
+        let cell_size = if self.position_tolerance > 0_f64 {
+            self.position_tolerance
+        } else {
+            1_f64
+        };
+        let cell_of = |p: &Vector2D| {
+            return (
+                (p.x / cell_size).floor() as i64,
+                (p.y / cell_size).floor() as i64,
+            );
+        };
+        let mut cells: HashMap<(i64, i64), Vec<u32>> = HashMap::new();
+        for node_id in new_node_ids.iter() {
+            cells
+                .entry(cell_of(&new_positions[node_id]))
+                .or_default()
+                .push(*node_id);
+        }
+
+        let mut candidates: Vec<(u32, u32, f64)> = Vec::new();
+        for old_node_id in old_node_ids.iter() {
+            let position = old_positions[old_node_id];
+            let (cell_x, cell_y) = cell_of(&position);
+            for dx in -1..=1 {
+                for dy in -1..=1 {
+                    for new_node_id in cells.get(&(cell_x + dx, cell_y + dy)).into_iter().flatten()
+                    {
+                        let distance = (new_positions[new_node_id] - position).magnitude();
+                        if distance <= self.position_tolerance {
+                            candidates.push((*old_node_id, *new_node_id, distance));
+                        }
+                    }
+                }
+            }
+        }
+
+        let old_neighbors = GraphDiffAlgorithm::neighbor_sets(old_graph, &old_node_ids);
+        let new_neighbors = GraphDiffAlgorithm::neighbor_sets(new_graph, &new_node_ids);
+
+        let mut node_matches: HashMap<u32, u32> = self.match_nodes(&candidates, |_, _| 0_f64);
+        if self.structure_weight > 0_f64 {
+            for _ in 0..REFINEMENT_ROUND_COUNT {
+                let previous_matches = node_matches;
+                node_matches = self.match_nodes(&candidates, |old_node_id, new_node_id| {
+                    return 1_f64
+                        - GraphDiffAlgorithm::similarity(
+                            &old_neighbors[&old_node_id],
+                            &new_neighbors[&new_node_id],
+                            &previous_matches,
+                        );
+                });
+            }
+        }
+
+        let mut diff = GraphDiff::default();
+        let matched_new_nodes: HashSet<u32> = node_matches.values().copied().collect();
+        let mut moved_node_set: HashSet<u32> = HashSet::new();
+        for old_node_id in old_node_ids.iter() {
+            match node_matches.get(old_node_id) {
+                Some(new_node_id) => {
+                    diff.matched_nodes.push((*old_node_id, *new_node_id));
+                    let displacement =
+                        (new_positions[new_node_id] - old_positions[old_node_id]).magnitude();
+                    if displacement > self.move_threshold {
+                        diff.moved_nodes.push((*old_node_id, *new_node_id));
+                        moved_node_set.insert(*old_node_id);
+                    }
+                }
+                None => diff.removed_nodes.push(*old_node_id),
+            }
+        }
+        diff.added_nodes = new_node_ids
+            .iter()
+            .filter(|n| !matched_new_nodes.contains(n))
+            .copied()
+            .collect();
+
+        // Edges between matched nodes, preferring the same orientation and direction flags
+        // among parallel edges.
+        let mut old_edge_ids: Vec<u32> = old_graph.edge_ids().collect();
+        old_edge_ids.sort();
+        let mut matched_new_edges: HashSet<u32> = HashSet::new();
+        for old_edge_id in old_edge_ids {
+            let old_edge = old_graph.get_edge_by_id(&old_edge_id).unwrap();
+            let ends = (
+                node_matches.get(&old_edge.node1()),
+                node_matches.get(&old_edge.node2()),
+            );
+            let (new_node1, new_node2) = match ends {
+                (Some(n1), Some(n2)) => (*n1, *n2),
+                _ => {
+                    diff.removed_edges.push(old_edge_id);
+                    continue;
+                }
+            };
+
+            let best_edge = new_graph
+                .edges_between(new_node1, new_node2)
+                .into_iter()
+                .filter(|e| !matched_new_edges.contains(e))
+                .min_by_key(|e| {
+                    let new_edge = new_graph.get_edge_by_id(e).unwrap();
+                    let is_same_orientation = new_edge.node1() == new_node1;
+                    let (forward, backward) = if is_same_orientation {
+                        (new_edge.can_move_forward(), new_edge.can_move_backward())
+                    } else {
+                        (new_edge.can_move_backward(), new_edge.can_move_forward())
+                    };
+                    let is_same_flags = forward == old_edge.can_move_forward()
+                        && backward == old_edge.can_move_backward();
+                    return (!is_same_flags, !is_same_orientation, *e);
+                });
+
+            match best_edge {
+                Some(new_edge_id) => {
+                    matched_new_edges.insert(new_edge_id);
+                    diff.matched_edges.push((old_edge_id, new_edge_id));
+                    if moved_node_set.contains(&old_edge.node1())
+                        || moved_node_set.contains(&old_edge.node2())
+                    {
+                        diff.moved_edges.push((old_edge_id, new_edge_id));
+                    }
+                }
+                None => diff.removed_edges.push(old_edge_id),
+            }
+        }
+        let mut new_edge_ids: Vec<u32> = new_graph.edge_ids().collect();
+        new_edge_ids.sort();
+        diff.added_edges = new_edge_ids
+            .into_iter()
+            .filter(|e| !matched_new_edges.contains(e))
+            .collect();
+
+        // Matched entities keep their old IDs; added ones continue after the old allocators.
+        diff.node_id_alloc = old_graph.get_node_id_alloc();
+        for (old_node_id, new_node_id) in diff.matched_nodes.iter() {
+            diff.stable_node_ids.insert(*new_node_id, *old_node_id);
+        }
+        for new_node_id in diff.added_nodes.iter() {
+            diff.stable_node_ids
+                .insert(*new_node_id, diff.node_id_alloc);
+            diff.node_id_alloc += 1;
+        }
+        diff.edge_id_alloc = old_graph.get_edge_id_alloc();
+        for (old_edge_id, new_edge_id) in diff.matched_edges.iter() {
+            diff.stable_edge_ids.insert(*new_edge_id, *old_edge_id);
+        }
+        for new_edge_id in diff.added_edges.iter() {
+            diff.stable_edge_ids
+                .insert(*new_edge_id, diff.edge_id_alloc);
+            diff.edge_id_alloc += 1;
+        }
+
+        return diff;
+    }
+
+    /// Greedy one-to-one matching of candidate pairs in ascending order of distance
+    /// relative to the tolerance plus weighted structural dissimilarity.
+    fn match_nodes<DissimilarityFuncT>(
+        &self,
+        candidates: &[(u32, u32, f64)],
+        dissimilarity_func: DissimilarityFuncT,
+    ) -> HashMap<u32, u32>
+    where
+        DissimilarityFuncT: Fn(u32, u32) -> f64,
+    {
+        let tolerance = f64::max(self.position_tolerance, f64::MIN_POSITIVE);
+        let mut scored: Vec<(f64, u32, u32)> = candidates
+            .iter()
+            .map(|(old_node_id, new_node_id, distance)| {
+                let score = distance / tolerance
+                    + self.structure_weight * dissimilarity_func(*old_node_id, *new_node_id);
+                return (score, *old_node_id, *new_node_id);
+            })
+            .collect();
+        scored.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)).then(a.2.cmp(&b.2)));
+
+        let mut matches: HashMap<u32, u32> = HashMap::new();
+        let mut matched_new_nodes: HashSet<u32> = HashSet::new();
+        for (_, old_node_id, new_node_id) in scored {
+            if matches.contains_key(&old_node_id) || matched_new_nodes.contains(&new_node_id) {
+                continue;
+            }
+            matches.insert(old_node_id, new_node_id);
+            matched_new_nodes.insert(new_node_id);
+        }
+        return matches;
+    }
+
+    fn neighbor_sets<GraphT: GraphView>(
+        graph: &GraphT,
+        node_ids: &[u32],
+    ) -> HashMap<u32, HashSet<u32>> {
+        return node_ids
+            .iter()
+            .map(|n| {
+                let neighbors: HashSet<u32> = graph
+                    .connected_edges(*n)
+                    .map(|(_, next_node_id)| next_node_id)
+                    .filter(|next_node_id| next_node_id != n)
+                    .collect();
+                return (*n, neighbors);
+            })
+            .collect();
+    }
+
+    /// Share of neighbors matched to each other, between 0 and 1. Two isolated nodes are
+    /// fully similar.
+    fn similarity(
+        old_neighbors: &HashSet<u32>,
+        new_neighbors: &HashSet<u32>,
+        matches: &HashMap<u32, u32>,
+    ) -> f64 {
+        let neighbor_count = usize::max(old_neighbors.len(), new_neighbors.len());
+        if neighbor_count == 0 {
+            return 1_f64;
+        }
+
+        let shared_count = old_neighbors
+            .iter()
+            .filter(|n| match matches.get(n) {
+                Some(m) => new_neighbors.contains(m),
+                None => false,
+            })
+            .count();
+        return shared_count as f64 / neighbor_count as f64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::graph::Graph;
+
+    /// Square A-B-C-D of side 10.
+    fn old_graph() -> Graph<Vector2D, ()> {
+        let mut graph: Graph<Vector2D, ()> = Graph::new(false, false);
+        let a = graph.add_node(Vector2D::from_xy(0_f64, 0_f64));
+        let b = graph.add_node(Vector2D::from_xy(10_f64, 0_f64));
+        let c = graph.add_node(Vector2D::from_xy(10_f64, 10_f64));
+        let d = graph.add_node(Vector2D::from_xy(0_f64, 10_f64));
+        for (n1, n2) in [(a, b), (b, c), (c, d), (d, a)] {
+            graph.add_edge(n1, n2, ()).unwrap();
+        }
+        return graph;
+    }
+
+    /// The square with D removed, B moved by 0.3, a new node E after B and the nodes and
+    /// edges added in another order, so that IDs differ from the old graph.
+    fn new_graph() -> Graph<Vector2D, ()> {
+        let mut graph: Graph<Vector2D, ()> = Graph::new(false, false);
+        let e = graph.add_node(Vector2D::from_xy(20_f64, 0_f64));
+        let c = graph.add_node(Vector2D::from_xy(10_f64, 10_f64));
+        let a = graph.add_node(Vector2D::from_xy(0_f64, 0_f64));
+        let b = graph.add_node(Vector2D::from_xy(10.3, 0_f64));
+        for (n1, n2) in [(b, e), (c, b), (a, b)] {
+            graph.add_edge(n1, n2, ()).unwrap();
+        }
+        return graph;
+    }
+
+    #[test]
+    fn unchanged_graph_matches_itself() {
+        let graph = old_graph();
+        let diff = GraphDiffAlgorithm::new(1_f64).diff(&graph, &graph, |p| *p);
+        assert!(diff.is_unchanged());
+        assert_eq!(diff.matched_nodes, vec![(1, 1), (2, 2), (3, 3), (4, 4)]);
+        assert_eq!(diff.matched_edges, vec![(1, 1), (2, 2), (3, 3), (4, 4)]);
+        assert_eq!(diff.node_id_alloc, graph.get_node_id_alloc());
+        assert_eq!(diff.edge_id_alloc, graph.get_edge_id_alloc());
+    }
+
+    #[test]
+    fn reports_moved_added_and_removed_entities() {
+        let diff = GraphDiffAlgorithm::new(1_f64).diff(&old_graph(), &new_graph(), |p| *p);
+        assert!(!diff.is_unchanged());
+
+        assert_eq!(diff.matched_nodes, vec![(1, 3), (2, 4), (3, 2)]);
+        assert_eq!(diff.moved_nodes, vec![(2, 4)]);
+        assert_eq!(diff.added_nodes, vec![1]);
+        assert_eq!(diff.removed_nodes, vec![4]);
+
+        assert_eq!(diff.matched_edges, vec![(1, 3), (2, 2)]);
+        assert_eq!(diff.moved_edges, vec![(1, 3), (2, 2)]);
+        assert_eq!(diff.added_edges, vec![1]);
+        assert_eq!(diff.removed_edges, vec![3, 4]);
+
+        // With a move threshold above the displacement of B, nothing is reported as moved.
+        let mut algorithm = GraphDiffAlgorithm::new(1_f64);
+        algorithm.set_move_threshold(0.5);
+        let diff = algorithm.diff(&old_graph(), &new_graph(), |p| *p);
+        assert!(diff.moved_nodes.is_empty());
+        assert!(diff.moved_edges.is_empty());
+    }
+
+    #[test]
+    fn stable_ids_carry_over_old_ids() {
+        let old_graph = old_graph();
+        let new_graph = new_graph();
+        let diff = GraphDiffAlgorithm::new(1_f64).diff(&old_graph, &new_graph, |p| *p);
+
+        // Matched entities keep their old IDs, added ones continue after the old
+        // allocators so that the IDs of removed D and its edges are not reused.
+        let expected_nodes: HashMap<u32, u32> = [(3, 1), (4, 2), (2, 3), (1, 5)].into();
+        let expected_edges: HashMap<u32, u32> = [(3, 1), (2, 2), (1, 5)].into();
+        assert_eq!(diff.stable_node_ids, expected_nodes);
+        assert_eq!(diff.stable_edge_ids, expected_edges);
+        assert_eq!((diff.node_id_alloc, diff.edge_id_alloc), (6, 6));
+
+        let graph = diff.apply_stable_ids(&new_graph).unwrap();
+        let mut node_ids: Vec<u32> = graph.node_ids().collect();
+        node_ids.sort();
+        assert_eq!(node_ids, vec![1, 2, 3, 5]);
+        assert_eq!(
+            *graph.get_node_by_id(&2).unwrap().node_info(),
+            Vector2D::from_xy(10.3, 0_f64)
+        );
+        let mut edges: Vec<(u32, u32, u32)> = graph
+            .edge_ids()
+            .map(|e| {
+                let edge = graph.get_edge_by_id(&e).unwrap();
+                return (e, edge.node1(), edge.node2());
+            })
+            .collect();
+        edges.sort();
+        assert_eq!(edges, vec![(1, 1, 2), (2, 3, 2), (5, 2, 5)]);
+        assert_eq!(graph.get_node_id_alloc(), 6);
+        assert_eq!(graph.get_edge_id_alloc(), 6);
+        assert!(graph.validate().is_ok());
+
+        // Diffing the old graph against the renumbered one matches everything by ID.
+        let diff = GraphDiffAlgorithm::new(1_f64).diff(&old_graph, &graph, |p| *p);
+        assert_eq!(diff.matched_nodes, vec![(1, 1), (2, 2), (3, 3)]);
+        assert_eq!(diff.matched_edges, vec![(1, 1), (2, 2)]);
+    }
+}
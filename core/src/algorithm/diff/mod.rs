@@ -0,0 +1,2 @@
+pub mod graph_diff;
+pub mod graph_diff_algorithm;
@@ -0,0 +1,32 @@
+use std::cmp::Ordering;
+
+/// Entry of a priority queue ordered by ascending cost.
+/// `BinaryHeap<CostQueueEntry>` pops the entry with the lowest cost first.
+#[derive(Clone, Copy, Debug)]
+pub struct CostQueueEntry {
+    pub cost: f64,
+    pub node_id: u32,
+}
+
+impl PartialEq for CostQueueEntry {
+    fn eq(&self, other: &Self) -> bool {
+        return self.cmp(other) == Ordering::Equal;
+    }
+}
+
+impl Eq for CostQueueEntry {}
+
+impl PartialOrd for CostQueueEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        return Some(self.cmp(other));
+    }
+}
+
+impl Ord for CostQueueEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        return other
+            .cost
+            .total_cmp(&self.cost)
+            .then_with(|| other.node_id.cmp(&self.node_id));
+    }
+}
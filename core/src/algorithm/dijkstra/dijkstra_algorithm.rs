@@ -0,0 +1,239 @@
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use crate::graph::{graph_error::GraphError, graph_path::GraphPath, graph_view::GraphView};
+
+use super::{cost_queue_entry::CostQueueEntry, shortest_path_tree::ShortestPathTree};
+
+/// Dijkstra shortest path search.
+///
+/// Edge costs are computed from edge info by a caller-supplied function, such as
+/// `TopologyEdge::get_length`, and must be non-negative. Edges are only traversed
+/// in the directions allowed by `can_move_forward` and `can_move_backward`.
+pub struct DijkstraAlgorithm;
+
+impl DijkstraAlgorithm {
+    /// Find shortest path from start node to goal node.
+    /// Returns None if the goal node cannot be reached.
+    pub fn shortest_path<GraphT, CostFuncT>(
+        graph: &GraphT,
+        start_node: u32,
+        goal_node: u32,
+        cost_func: CostFuncT,
+    ) -> Result<Option<GraphPath>, GraphError>
+    where
+        GraphT: GraphView,
+        CostFuncT: Fn(&GraphT::EdgeInfo) -> f64,
+    {
+        if graph.get_node_by_id(&goal_node).is_none() {
+            return Err(GraphError::NoSuchNode);
+        }
+
+        let tree = DijkstraAlgorithm::search(graph, start_node, Some(goal_node), true, &cost_func)?;
+        return Ok(tree.path_to(goal_node));
+    }
+
+    /// Find shortest paths from start node to every reachable node.
+    pub fn shortest_path_tree<GraphT, CostFuncT>(
+        graph: &GraphT,
+        start_node: u32,
+        cost_func: CostFuncT,
+    ) -> Result<ShortestPathTree, GraphError>
+    where
+        GraphT: GraphView,
+        CostFuncT: Fn(&GraphT::EdgeInfo) -> f64,
+    {
+        return DijkstraAlgorithm::search(graph, start_node, None, true, &cost_func);
+    }
+
+    /// Find shortest paths from start node to every connected node, ignoring edge
+    /// directions, for example to plan cable runs or to cover every corridor.
+    pub fn undirected_shortest_path_tree<GraphT, CostFuncT>(
+        graph: &GraphT,
+        start_node: u32,
+        cost_func: CostFuncT,
+    ) -> Result<ShortestPathTree, GraphError>
+    where
+        GraphT: GraphView,
+        CostFuncT: Fn(&GraphT::EdgeInfo) -> f64,
+    {
+        return DijkstraAlgorithm::search(graph, start_node, None, false, &cost_func);
+    }
+
+    fn search<GraphT, CostFuncT>(
+        graph: &GraphT,
+        start_node: u32,
+        goal_node: Option<u32>,
+        is_directed: bool,
+        cost_func: &CostFuncT,
+    ) -> Result<ShortestPathTree, GraphError>
+    where
+        GraphT: GraphView,
+        CostFuncT: Fn(&GraphT::EdgeInfo) -> f64,
+    {
+        if graph.get_node_by_id(&start_node).is_none() {
+            return Err(GraphError::NoSuchNode);
+        }
+
+        let mut distances: HashMap<u32, f64> = HashMap::new();
+        let mut predecessors: HashMap<u32, (u32, u32)> = HashMap::new();
+        let mut settled: HashSet<u32> = HashSet::new();
+        let mut queue: BinaryHeap<CostQueueEntry> = BinaryHeap::new();
+
+        distances.insert(start_node, 0_f64);
+        queue.push(CostQueueEntry {
+            cost: 0_f64,
+            node_id: start_node,
+        });
+
+        while let Some(CostQueueEntry { cost, node_id }) = queue.pop() {
+            if !settled.insert(node_id) {
+                continue;
+            }
+            if goal_node == Some(node_id) {
+                break;
+            }
+
+            for (edge_id, next_node_id) in graph.connected_edges(node_id) {
+                let edge = graph.get_edge_by_id(&edge_id).unwrap();
+                if next_node_id == node_id || (is_directed && !edge.can_move_from(node_id)) {
+                    continue;
+                }
+                if settled.contains(&next_node_id) {
+                    continue;
+                }
+
+                let edge_cost = cost_func(edge.edge_info());
+                if edge_cost.is_nan() || edge_cost < 0_f64 {
+                    return Err(GraphError::InvalidEdgeCost);
+                }
+
+                let next_cost = cost + edge_cost;
+                let is_improved = match distances.get(&next_node_id) {
+                    Some(d) => next_cost < *d,
+                    None => true,
+                };
+                if is_improved {
+                    distances.insert(next_node_id, next_cost);
+                    predecessors.insert(next_node_id, (node_id, edge_id));
+                    queue.push(CostQueueEntry {
+                        cost: next_cost,
+                        node_id: next_node_id,
+                    });
+                }
+            }
+        }
+
+        // Drop tentative distances of nodes that were never settled.
+        distances.retain(|node_id, _| settled.contains(node_id));
+        predecessors.retain(|node_id, _| settled.contains(node_id));
+
+        return Ok(ShortestPathTree {
+            start_node: start_node,
+            distances: distances,
+            predecessors: predecessors,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::graph::Graph;
+
+    /// Nodes 1 to 4 in a path with a one-way edge from 2 to 3 and a costly shortcut
+    /// between 1 and 3, and isolated node 5.
+    fn sample_graph() -> Graph<(), f64> {
+        let mut graph: Graph<(), f64> = Graph::new(false, false);
+        for _ in 0..5 {
+            graph.add_node(());
+        }
+        graph.add_edge(1, 2, 1_f64).unwrap();
+        graph.add_directed_edge(2, 3, true, false, 1_f64).unwrap();
+        graph.add_edge(1, 3, 5_f64).unwrap();
+        graph.add_edge(3, 4, 1_f64).unwrap();
+        return graph;
+    }
+
+    #[test]
+    fn one_way_edge_is_not_traversed_backwards() {
+        let graph = sample_graph();
+        let path = DijkstraAlgorithm::shortest_path(&graph, 1, 3, |w| *w)
+            .unwrap()
+            .unwrap();
+        assert_eq!(path.nodes, vec![1, 2, 3]);
+        assert_eq!(path.edges, vec![1, 2]);
+        assert_eq!(path.cost, 2_f64);
+
+        let path = DijkstraAlgorithm::shortest_path(&graph, 3, 2, |w| *w)
+            .unwrap()
+            .unwrap();
+        assert_eq!(path.nodes, vec![3, 1, 2]);
+        assert_eq!(path.edges, vec![3, 1]);
+        assert_eq!(path.cost, 6_f64);
+
+        assert_eq!(
+            DijkstraAlgorithm::shortest_path(&graph, 1, 5, |w| *w),
+            Ok(None)
+        );
+        assert_eq!(
+            DijkstraAlgorithm::shortest_path(&graph, 1, 6, |w| *w),
+            Err(GraphError::NoSuchNode)
+        );
+        assert_eq!(
+            DijkstraAlgorithm::shortest_path(&graph, 6, 1, |w| *w),
+            Err(GraphError::NoSuchNode)
+        );
+    }
+
+    #[test]
+    fn shortest_path_tree_has_distances_and_predecessors() {
+        let graph = sample_graph();
+        let tree = DijkstraAlgorithm::shortest_path_tree(&graph, 1, |w| *w).unwrap();
+        assert_eq!(tree.start_node, 1);
+
+        let expected_distances: HashMap<u32, f64> =
+            [(1, 0_f64), (2, 1_f64), (3, 2_f64), (4, 3_f64)].into();
+        assert_eq!(tree.distances, expected_distances);
+        let expected_predecessors: HashMap<u32, (u32, u32)> =
+            [(2, (1, 1)), (3, (2, 2)), (4, (3, 4))].into();
+        assert_eq!(tree.predecessors, expected_predecessors);
+
+        assert_eq!(tree.distance_to(5), None);
+        assert_eq!(tree.path_to(4).unwrap().nodes, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn undirected_tree_ignores_direction_flags() {
+        let graph = sample_graph();
+        let directed = DijkstraAlgorithm::shortest_path_tree(&graph, 3, |w| *w).unwrap();
+        assert_eq!(directed.distance_to(2), Some(6_f64));
+
+        let undirected =
+            DijkstraAlgorithm::undirected_shortest_path_tree(&graph, 3, |w| *w).unwrap();
+        assert_eq!(undirected.distance_to(2), Some(1_f64));
+        assert_eq!(undirected.distance_to(1), Some(2_f64));
+        assert_eq!(undirected.predecessors[&2], (3, 2));
+        assert_eq!(undirected.distance_to(5), None);
+    }
+
+    #[test]
+    fn nan_and_negative_costs_are_rejected() {
+        for invalid_cost in [f64::NAN, -1_f64] {
+            let mut graph = sample_graph();
+            graph.update_edge_info(&4, |w| *w = invalid_cost).unwrap();
+
+            assert_eq!(
+                DijkstraAlgorithm::shortest_path(&graph, 1, 4, |w| *w),
+                Err(GraphError::InvalidEdgeCost)
+            );
+            assert_eq!(
+                DijkstraAlgorithm::shortest_path_tree(&graph, 1, |w| *w).err(),
+                Some(GraphError::InvalidEdgeCost)
+            );
+            assert_eq!(
+                DijkstraAlgorithm::undirected_shortest_path_tree(&graph, 4, |w| *w).err(),
+                Some(GraphError::InvalidEdgeCost)
+            );
+        }
+    }
+}
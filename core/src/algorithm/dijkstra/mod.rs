@@ -0,0 +1,3 @@
+pub mod cost_queue_entry;
+pub mod dijkstra_algorithm;
+pub mod shortest_path_tree;
@@ -0,0 +1,41 @@
+use std::collections::HashMap;
+
+use crate::graph::graph_path::GraphPath;
+
+/// Result of a one-to-all shortest path search.
+/// `predecessors` maps every reached node except the start to the node and edge it is reached through.
+#[derive(Clone, Debug)]
+pub struct ShortestPathTree {
+    pub start_node: u32,
+    pub distances: HashMap<u32, f64>,
+    pub predecessors: HashMap<u32, (u32, u32)>,
+}
+
+impl ShortestPathTree {
+    pub fn distance_to(&self, node_id: u32) -> Option<f64> {
+        return self.distances.get(&node_id).copied();
+    }
+
+    /// Build path from the start node to given node.
+    /// Returns None if the node was not reached.
+    pub fn path_to(&self, node_id: u32) -> Option<GraphPath> {
+        let cost = self.distance_to(node_id)?;
+        let mut nodes: Vec<u32> = vec![node_id];
+        let mut edges: Vec<u32> = Vec::new();
+
+        let mut current = node_id;
+        while let Some((previous_node, edge_id)) = self.predecessors.get(&current) {
+            nodes.push(*previous_node);
+            edges.push(*edge_id);
+            current = *previous_node;
+        }
+
+        nodes.reverse();
+        edges.reverse();
+        return Some(GraphPath {
+            nodes: nodes,
+            edges: edges,
+            cost: cost,
+        });
+    }
+}
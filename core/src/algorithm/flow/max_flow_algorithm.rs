@@ -0,0 +1,299 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::graph::{graph_error::GraphError, graph_view::GraphView};
+
+use super::max_flow_result::MaxFlowResult;
+
+/// Maximum flow and minimum cut with Dinic's algorithm.
+///
+/// Edge capacities are read from edge info by a caller-supplied function, for example
+/// robots per minute a corridor can carry, and must be non-negative. Flow only moves in
+/// the directions an edge allows; the capacity of a bidirectional edge is shared by both
+/// directions. Several source and sink nodes can be given, for example all nodes of two
+/// warehouse zones.
+pub struct MaxFlowAlgorithm;
+
+struct FlowArc {
+    to: usize,
+    capacity: f64,
+}
+
+/// Flows below this are treated as zero to absorb rounding errors.
+const FLOW_EPSILON: f64 = 1e-9;
+
+impl MaxFlowAlgorithm {
+    pub fn max_flow<GraphT, CapacityFuncT>(
+        graph: &GraphT,
+        source_nodes: &[u32],
+        sink_nodes: &[u32],
+        capacity_func: CapacityFuncT,
+    ) -> Result<MaxFlowResult, GraphError>
+    where
+        GraphT: GraphView,
+        CapacityFuncT: Fn(&GraphT::EdgeInfo) -> f64,
+    {
+        for node_id in source_nodes.iter().chain(sink_nodes.iter()) {
+            if graph.get_node_by_id(node_id).is_none() {
+                return Err(GraphError::NoSuchNode);
+            }
+        }
+
+        let mut node_ids: Vec<u32> = graph.node_ids().collect();
+        node_ids.sort();
+        let node_indices: HashMap<u32, usize> =
+            node_ids.iter().enumerate().map(|(i, n)| (*n, i)).collect();
+
+        // Super source and super sink follow the graph nodes.
+        let super_source = node_ids.len();
+        let super_sink = node_ids.len() + 1;
+        let mut arcs: Vec<FlowArc> = Vec::new();
+        let mut adjacency: Vec<Vec<usize>> = vec![Vec::new(); node_ids.len() + 2];
+
+        // Arcs come in pairs; arc `i ^ 1` is the residual of arc `i`.
+        let mut add_arc_pair = |from: usize, to: usize, capacity: f64, reverse_capacity: f64| {
+            adjacency[from].push(arcs.len());
+            arcs.push(FlowArc {
+                to: to,
+                capacity: capacity,
+            });
+            adjacency[to].push(arcs.len());
+            arcs.push(FlowArc {
+                to: from,
+                capacity: reverse_capacity,
+            });
+            return arcs.len() - 2;
+        };
+
+        let mut edge_ids: Vec<u32> = graph.edge_ids().collect();
+        edge_ids.sort();
+        let mut edge_arcs: Vec<(u32, usize, f64, f64)> = Vec::with_capacity(edge_ids.len());
+        for edge_id in edge_ids {
+            let edge = graph.get_edge_by_id(&edge_id).unwrap();
+            if edge.node1() == edge.node2() {
+                continue;
+            }
+
+            let capacity = capacity_func(edge.edge_info());
+            if capacity.is_nan() || capacity < 0_f64 {
+                return Err(GraphError::InvalidEdgeCost);
+            }
+
+            let forward_capacity = if edge.can_move_forward() {
+                capacity
+            } else {
+                0_f64
+            };
+            let backward_capacity = if edge.can_move_backward() {
+                capacity
+            } else {
+                0_f64
+            };
+            let arc = add_arc_pair(
+                node_indices[&edge.node1()],
+                node_indices[&edge.node2()],
+                forward_capacity,
+                backward_capacity,
+            );
+            edge_arcs.push((edge_id, arc, forward_capacity, backward_capacity));
+        }
+
+        let source_set: HashSet<u32> = source_nodes.iter().copied().collect();
+        for node_id in source_set.iter() {
+            add_arc_pair(super_source, node_indices[node_id], f64::INFINITY, 0_f64);
+        }
+        for node_id in sink_nodes.iter().collect::<HashSet<&u32>>() {
+            // A node that is both source and sink only acts as a source.
+            if !source_set.contains(node_id) {
+                add_arc_pair(node_indices[node_id], super_sink, f64::INFINITY, 0_f64);
+            }
+        }
+
+        let mut flow_value = 0_f64;
+        while let Some(levels) =
+            MaxFlowAlgorithm::build_levels(&arcs, &adjacency, super_source, super_sink)
+        {
+            flow_value += MaxFlowAlgorithm::blocking_flow(
+                &mut arcs,
+                &adjacency,
+                &levels,
+                super_source,
+                super_sink,
+            );
+        }
+
+        // Nodes still reachable from the sources form the source side of the minimum cut.
+        let reachable = MaxFlowAlgorithm::reachable(&arcs, &adjacency, super_source);
+        let source_side_nodes: HashSet<u32> = node_ids
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| reachable[*i])
+            .map(|(_, n)| *n)
+            .collect();
+
+        let mut edge_flows: HashMap<u32, f64> = HashMap::new();
+        let mut cut_edges: Vec<u32> = Vec::new();
+        for (edge_id, arc, forward_capacity, backward_capacity) in edge_arcs {
+            let flow = forward_capacity - arcs[arc].capacity;
+            if flow.abs() > FLOW_EPSILON {
+                edge_flows.insert(edge_id, flow);
+            }
+
+            let from = arcs[arc ^ 1].to;
+            let to = arcs[arc].to;
+            let is_cut = match (reachable[from], reachable[to]) {
+                (true, false) => forward_capacity > 0_f64,
+                (false, true) => backward_capacity > 0_f64,
+                _ => false,
+            };
+            if is_cut {
+                cut_edges.push(edge_id);
+            }
+        }
+
+        return Ok(MaxFlowResult {
+            flow_value: flow_value,
+            edge_flows: edge_flows,
+            source_side_nodes: source_side_nodes,
+            cut_edges: cut_edges,
+        });
+    }
+
+    /// Breadth-first distances from the source over arcs with capacity left.
+    /// Returns None once the sink cannot be reached.
+    fn build_levels(
+        arcs: &[FlowArc],
+        adjacency: &[Vec<usize>],
+        source: usize,
+        sink: usize,
+    ) -> Option<Vec<usize>> {
+        let mut levels: Vec<usize> = vec![usize::MAX; adjacency.len()];
+        let mut queue: VecDeque<usize> = VecDeque::from([source]);
+        levels[source] = 0;
+
+        while let Some(node) = queue.pop_front() {
+            for arc in adjacency[node].iter() {
+                let next = arcs[*arc].to;
+                if arcs[*arc].capacity > FLOW_EPSILON && levels[next] == usize::MAX {
+                    levels[next] = levels[node] + 1;
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        if levels[sink] == usize::MAX {
+            return None;
+        }
+        return Some(levels);
+    }
+
+    /// Saturate every shortest augmenting path of the level graph, walking with an explicit
+    /// stack so that long corridors do not overflow the thread stack.
+    fn blocking_flow(
+        arcs: &mut [FlowArc],
+        adjacency: &[Vec<usize>],
+        levels: &[usize],
+        source: usize,
+        sink: usize,
+    ) -> f64 {
+        let mut levels = levels.to_vec();
+        let mut next_arc: Vec<usize> = vec![0; adjacency.len()];
+        let mut path: Vec<usize> = Vec::new();
+        let mut total_flow = 0_f64;
+        let mut node = source;
+
+        loop {
+            if node == sink {
+                let bottleneck = path
+                    .iter()
+                    .map(|a| arcs[*a].capacity)
+                    .fold(f64::INFINITY, f64::min);
+                for arc in path.iter() {
+                    arcs[*arc].capacity -= bottleneck;
+                    arcs[*arc ^ 1].capacity += bottleneck;
+                }
+                total_flow += bottleneck;
+
+                path.clear();
+                node = source;
+                continue;
+            }
+
+            let mut advanced = false;
+            while next_arc[node] < adjacency[node].len() {
+                let arc = adjacency[node][next_arc[node]];
+                let next = arcs[arc].to;
+                if arcs[arc].capacity > FLOW_EPSILON && levels[next] == levels[node] + 1 {
+                    path.push(arc);
+                    node = next;
+                    advanced = true;
+                    break;
+                }
+                next_arc[node] += 1;
+            }
+            if advanced {
+                continue;
+            }
+
+            // Dead end: exclude the node from this phase and step back.
+            levels[node] = usize::MAX;
+            match path.pop() {
+                Some(arc) => {
+                    node = arcs[arc ^ 1].to;
+                    next_arc[node] += 1;
+                }
+                None => break,
+            }
+        }
+
+        return total_flow;
+    }
+
+    fn reachable(arcs: &[FlowArc], adjacency: &[Vec<usize>], source: usize) -> Vec<bool> {
+        let mut reachable: Vec<bool> = vec![false; adjacency.len()];
+        let mut stack: Vec<usize> = vec![source];
+        reachable[source] = true;
+
+        while let Some(node) = stack.pop() {
+            for arc in adjacency[node].iter() {
+                let next = arcs[*arc].to;
+                if arcs[*arc].capacity > FLOW_EPSILON && !reachable[next] {
+                    reachable[next] = true;
+                    stack.push(next);
+                }
+            }
+        }
+
+        return reachable;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::graph::Graph;
+
+    #[test]
+    fn finds_flow_value_and_cut_and_rejects_invalid_capacities() {
+        // Two routes of capacity 3 and 2 merge into a corridor of capacity 4.
+        let mut graph: Graph<(), f64> = Graph::new(false, false);
+        let n: Vec<u32> = (0..5).map(|_| graph.add_node(())).collect();
+        graph.add_edge(n[0], n[1], 3_f64).unwrap();
+        graph
+            .add_directed_edge(n[0], n[2], true, false, 2_f64)
+            .unwrap();
+        graph.add_edge(n[1], n[3], 5_f64).unwrap();
+        graph
+            .add_directed_edge(n[3], n[2], false, true, 5_f64)
+            .unwrap();
+        let corridor = graph.add_edge(n[3], n[4], 4_f64).unwrap();
+
+        let result = MaxFlowAlgorithm::max_flow(&graph, &[n[0]], &[n[4]], |c| *c).unwrap();
+        assert_eq!(result.flow_value, 4_f64);
+        assert_eq!(result.cut_edges, vec![corridor]);
+        assert_eq!(result.edge_flow(corridor), 4_f64);
+
+        for capacity in [f64::NAN, -1_f64] {
+            assert!(MaxFlowAlgorithm::max_flow(&graph, &[n[0]], &[n[4]], |_| capacity).is_err());
+        }
+    }
+}
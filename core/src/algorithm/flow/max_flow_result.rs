@@ -0,0 +1,23 @@
+use std::collections::{HashMap, HashSet};
+
+/// Result of a maximum flow computation, including a minimum cut.
+#[derive(Clone, Debug)]
+pub struct MaxFlowResult {
+    /// Total flow from the source nodes to the sink nodes, equal to the capacity of the cut.
+    pub flow_value: f64,
+    /// Net flow through each edge carrying flow. Positive values flow from node1 to node2,
+    /// negative values from node2 to node1.
+    pub edge_flows: HashMap<u32, f64>,
+    /// Nodes on the source side of the minimum cut, reachable from the sources through
+    /// edges with capacity left.
+    pub source_side_nodes: HashSet<u32>,
+    /// Saturated edges leading from the source side to the sink side of the minimum cut.
+    /// Their capacities add up to the flow value.
+    pub cut_edges: Vec<u32>,
+}
+
+impl MaxFlowResult {
+    pub fn edge_flow(&self, edge_id: u32) -> f64 {
+        return *self.edge_flows.get(&edge_id).unwrap_or(&0_f64);
+    }
+}
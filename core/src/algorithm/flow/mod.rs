@@ -0,0 +1,2 @@
+pub mod max_flow_algorithm;
+pub mod max_flow_result;
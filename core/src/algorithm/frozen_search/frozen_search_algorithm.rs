@@ -0,0 +1,386 @@
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+
+use crate::{
+    algorithm::{
+        dijkstra::{cost_queue_entry::CostQueueEntry, shortest_path_tree::ShortestPathTree},
+        traversal::traversal_direction::TraversalDirection,
+    },
+    graph::{frozen_graph::FrozenGraph, graph_error::GraphError, graph_path::GraphPath},
+};
+
+/// Dijkstra, A* and breadth-first searches on a `FrozenGraph`.
+///
+/// Same results as `DijkstraAlgorithm`, `AStarAlgorithm` and `BreadthFirstIterator`, but
+/// search state is kept in arrays over the dense indices of the snapshot instead of hash
+/// maps, which makes repeated queries on large maps several times faster. Node and edge
+/// IDs in arguments and results are the original IDs.
+pub struct FrozenSearchAlgorithm;
+
+/// Marks nodes without predecessor in the predecessor arrays.
+const NO_INDEX: u32 = u32::MAX;
+
+/// Dense search state: cost from the start, and predecessor node and edge indices.
+struct SearchState {
+    costs: Vec<f64>,
+    predecessor_nodes: Vec<u32>,
+    predecessor_edges: Vec<u32>,
+    is_settled: Vec<bool>,
+}
+
+impl FrozenSearchAlgorithm {
+    /// Find shortest path from start node to goal node with Dijkstra's algorithm.
+    /// Returns None if the goal node cannot be reached.
+    pub fn shortest_path<TNodeInfo, TEdgeInfo, CostFuncT>(
+        graph: &FrozenGraph<TNodeInfo, TEdgeInfo>,
+        start_node: u32,
+        goal_node: u32,
+        cost_func: CostFuncT,
+    ) -> Result<Option<GraphPath>, GraphError>
+    where
+        CostFuncT: Fn(&TEdgeInfo) -> f64,
+    {
+        return FrozenSearchAlgorithm::a_star(graph, start_node, goal_node, cost_func, |_, _| {
+            return 0_f64;
+        });
+    }
+
+    /// Find shortest paths from start node to every reachable node.
+    pub fn shortest_path_tree<TNodeInfo, TEdgeInfo, CostFuncT>(
+        graph: &FrozenGraph<TNodeInfo, TEdgeInfo>,
+        start_node: u32,
+        cost_func: CostFuncT,
+    ) -> Result<ShortestPathTree, GraphError>
+    where
+        CostFuncT: Fn(&TEdgeInfo) -> f64,
+    {
+        let start_index = match graph.get_node_index(start_node) {
+            Some(i) => i,
+            None => return Err(GraphError::NoSuchNode),
+        };
+
+        let state = FrozenSearchAlgorithm::search(graph, start_index, None, &cost_func, |_| 0_f64)?;
+
+        let mut distances: HashMap<u32, f64> = HashMap::new();
+        let mut predecessors: HashMap<u32, (u32, u32)> = HashMap::new();
+        for node_index in 0..graph.get_node_count() {
+            if !state.is_settled[node_index] {
+                continue;
+            }
+
+            let node_id = graph.get_node_id(node_index);
+            distances.insert(node_id, state.costs[node_index]);
+            if state.predecessor_nodes[node_index] != NO_INDEX {
+                predecessors.insert(
+                    node_id,
+                    (
+                        graph.get_node_id(state.predecessor_nodes[node_index] as usize),
+                        graph.get_edge_id(state.predecessor_edges[node_index] as usize),
+                    ),
+                );
+            }
+        }
+
+        return Ok(ShortestPathTree {
+            start_node: start_node,
+            distances: distances,
+            predecessors: predecessors,
+        });
+    }
+
+    /// Find shortest path from start node to goal node with A*, where the heuristic
+    /// estimates the remaining cost from a node to the goal node like in `AStarAlgorithm`.
+    /// Returns None if the goal node cannot be reached.
+    pub fn a_star<TNodeInfo, TEdgeInfo, CostFuncT, HeuristicFuncT>(
+        graph: &FrozenGraph<TNodeInfo, TEdgeInfo>,
+        start_node: u32,
+        goal_node: u32,
+        cost_func: CostFuncT,
+        heuristic_func: HeuristicFuncT,
+    ) -> Result<Option<GraphPath>, GraphError>
+    where
+        CostFuncT: Fn(&TEdgeInfo) -> f64,
+        HeuristicFuncT: Fn(&TNodeInfo, &TNodeInfo) -> f64,
+    {
+        let (start_index, goal_index) = match (
+            graph.get_node_index(start_node),
+            graph.get_node_index(goal_node),
+        ) {
+            (Some(s), Some(g)) => (s, g),
+            _ => return Err(GraphError::NoSuchNode),
+        };
+
+        let goal_info = graph.node_at(goal_index).node_info();
+        let state = FrozenSearchAlgorithm::search(
+            graph,
+            start_index,
+            Some(goal_index),
+            &cost_func,
+            |node_index| heuristic_func(graph.node_at(node_index).node_info(), goal_info),
+        )?;
+        if !state.is_settled[goal_index] {
+            return Ok(None);
+        }
+
+        let mut nodes: Vec<u32> = vec![goal_node];
+        let mut edges: Vec<u32> = Vec::new();
+        let mut current = goal_index;
+        while state.predecessor_nodes[current] != NO_INDEX {
+            edges.push(graph.get_edge_id(state.predecessor_edges[current] as usize));
+            current = state.predecessor_nodes[current] as usize;
+            nodes.push(graph.get_node_id(current));
+        }
+
+        nodes.reverse();
+        edges.reverse();
+        return Ok(Some(GraphPath {
+            nodes: nodes,
+            edges: edges,
+            cost: state.costs[goal_index],
+        }));
+    }
+
+    /// Nodes reachable from start node in breadth-first order, together with their depth
+    /// in edges, like `BreadthFirstIterator`.
+    pub fn breadth_first_order<TNodeInfo, TEdgeInfo>(
+        graph: &FrozenGraph<TNodeInfo, TEdgeInfo>,
+        start_node: u32,
+        direction: TraversalDirection,
+    ) -> Result<Vec<(u32, usize)>, GraphError> {
+        let start_index = match graph.get_node_index(start_node) {
+            Some(i) => i,
+            None => return Err(GraphError::NoSuchNode),
+        };
+
+        let mut order: Vec<(u32, usize)> = Vec::new();
+        let mut is_visited: Vec<bool> = vec![false; graph.get_node_count()];
+        let mut queue: VecDeque<(usize, usize)> = VecDeque::from([(start_index, 0)]);
+        is_visited[start_index] = true;
+
+        while let Some((node_index, depth)) = queue.pop_front() {
+            let node_id = graph.get_node_id(node_index);
+            order.push((node_id, depth));
+
+            for (edge_index, next_index) in graph.connections(node_index) {
+                if is_visited[next_index]
+                    || !direction.can_traverse(graph.edge_at(edge_index), node_id)
+                {
+                    continue;
+                }
+
+                is_visited[next_index] = true;
+                queue.push_back((next_index, depth + 1));
+            }
+        }
+
+        return Ok(order);
+    }
+
+    /// Best-first search ordered by cost plus heuristic. A node is expanded again if a
+    /// cheaper path to it is found later, which only happens with inconsistent heuristics.
+    fn search<TNodeInfo, TEdgeInfo, CostFuncT, HeuristicFuncT>(
+        graph: &FrozenGraph<TNodeInfo, TEdgeInfo>,
+        start_index: usize,
+        goal_index: Option<usize>,
+        cost_func: &CostFuncT,
+        heuristic_func: HeuristicFuncT,
+    ) -> Result<SearchState, GraphError>
+    where
+        CostFuncT: Fn(&TEdgeInfo) -> f64,
+        HeuristicFuncT: Fn(usize) -> f64,
+    {
+        let node_count = graph.get_node_count();
+        let mut state = SearchState {
+            costs: vec![f64::INFINITY; node_count],
+            predecessor_nodes: vec![NO_INDEX; node_count],
+            predecessor_edges: vec![NO_INDEX; node_count],
+            is_settled: vec![false; node_count],
+        };
+        let mut heuristics: Vec<f64> = vec![f64::NAN; node_count];
+        let mut queue: BinaryHeap<CostQueueEntry> = BinaryHeap::new();
+
+        heuristics[start_index] = heuristic_func(start_index);
+        state.costs[start_index] = 0_f64;
+        queue.push(CostQueueEntry {
+            cost: heuristics[start_index],
+            node_id: start_index as u32,
+        });
+
+        while let Some(entry) = queue.pop() {
+            let node_index = entry.node_id as usize;
+
+            // Skip stale entries superseded by a cheaper path to the same node.
+            if entry.cost > state.costs[node_index] + heuristics[node_index] {
+                continue;
+            }
+
+            state.is_settled[node_index] = true;
+            if goal_index == Some(node_index) {
+                break;
+            }
+
+            let cost = state.costs[node_index];
+            for (edge_index, next_index) in graph.outgoing_connections(node_index) {
+                if next_index == node_index {
+                    continue;
+                }
+
+                let edge_cost = cost_func(graph.edge_at(edge_index).edge_info());
+                if edge_cost.is_nan() || edge_cost < 0_f64 {
+                    return Err(GraphError::InvalidEdgeCost);
+                }
+
+                let next_cost = cost + edge_cost;
+                if next_cost >= state.costs[next_index] {
+                    continue;
+                }
+
+                if heuristics[next_index].is_nan() {
+                    heuristics[next_index] = heuristic_func(next_index);
+                }
+                state.costs[next_index] = next_cost;
+                state.predecessor_nodes[next_index] = node_index as u32;
+                state.predecessor_edges[next_index] = edge_index as u32;
+                queue.push(CostQueueEntry {
+                    cost: next_cost + heuristics[next_index],
+                    node_id: next_index as u32,
+                });
+            }
+        }
+
+        return Ok(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::{
+        a_star::a_star_algorithm::AStarAlgorithm, dijkstra::dijkstra_algorithm::DijkstraAlgorithm,
+        traversal::breadth_first_iterator::BreadthFirstIterator,
+    };
+    use crate::graph::{graph::Graph, graph_view::GraphView};
+
+    fn distance(a: &(f64, f64), b: &(f64, f64)) -> f64 {
+        return ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt();
+    }
+
+    /// Grid with pseudo-random costs of at least the edge length, one-way edges and
+    /// removed nodes, so that IDs are not dense.
+    fn sample_graph() -> Graph<(f64, f64), f64> {
+        let size = 12;
+        let mut graph: Graph<(f64, f64), f64> = Graph::new(false, false);
+        let nodes: Vec<u32> = (0..size * size)
+            .map(|i| graph.add_node(((i % size) as f64, (i / size) as f64)))
+            .collect();
+        let mut state: u32 = 77;
+        for i in 0..size * size {
+            for next in [i + 1, i + size] {
+                if (next == i + 1 && next % size == 0) || next >= size * size {
+                    continue;
+                }
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                let cost = 1_f64 + ((state >> 16) % 1000) as f64 / 500_f64;
+                graph
+                    .add_directed_edge(nodes[i], nodes[next], true, !state.is_multiple_of(7), cost)
+                    .unwrap();
+            }
+        }
+        for i in (5..size * size).step_by(17) {
+            graph.remove_node(&nodes[i]).unwrap();
+        }
+        return graph;
+    }
+
+    #[test]
+    fn results_match_graph_algorithms() {
+        let graph = sample_graph();
+        let frozen = graph.freeze();
+        let mut node_ids: Vec<u32> = graph.node_ids().collect();
+        node_ids.sort();
+        let a_star = AStarAlgorithm::new();
+
+        for start in node_ids.iter().step_by(13) {
+            let tree = DijkstraAlgorithm::shortest_path_tree(&graph, *start, |w| *w).unwrap();
+            let frozen_tree =
+                FrozenSearchAlgorithm::shortest_path_tree(&frozen, *start, |w| *w).unwrap();
+            for goal in node_ids.iter() {
+                let expected = tree.distance_to(*goal);
+                let actual = frozen_tree.distance_to(*goal);
+                assert_eq!(expected.is_some(), actual.is_some());
+                if let (Some(e), Some(a)) = (expected, actual) {
+                    assert!((e - a).abs() < 1e-9);
+                }
+            }
+
+            for goal in node_ids.iter().step_by(7) {
+                let expected = DijkstraAlgorithm::shortest_path(&graph, *start, *goal, |w| *w)
+                    .unwrap()
+                    .map(|p| p.cost);
+                let path =
+                    FrozenSearchAlgorithm::shortest_path(&frozen, *start, *goal, |w| *w).unwrap();
+                let a_star_path = a_star
+                    .search(&graph, *start, *goal, |w| *w, distance)
+                    .unwrap()
+                    .path;
+                let frozen_a_star_path =
+                    FrozenSearchAlgorithm::a_star(&frozen, *start, *goal, |w| *w, distance)
+                        .unwrap();
+                for found in [&path, &a_star_path, &frozen_a_star_path] {
+                    assert_eq!(found.is_some(), expected.is_some());
+                    if let (Some(found), Some(expected)) = (found, expected) {
+                        assert!((found.cost - expected).abs() < 1e-9);
+                        let edge_costs: f64 = found
+                            .edges
+                            .iter()
+                            .map(|e| *graph.get_edge_by_id(e).unwrap().edge_info())
+                            .sum();
+                        assert!((found.cost - edge_costs).abs() < 1e-9);
+                    }
+                }
+            }
+
+            for direction in [
+                TraversalDirection::Forward,
+                TraversalDirection::Backward,
+                TraversalDirection::Undirected,
+            ] {
+                let mut expected: Vec<(u32, usize)> =
+                    BreadthFirstIterator::new(&graph, *start, direction)
+                        .unwrap()
+                        .collect();
+                let mut actual =
+                    FrozenSearchAlgorithm::breadth_first_order(&frozen, *start, direction).unwrap();
+                assert_eq!(actual[0], (*start, 0));
+                expected.sort();
+                actual.sort();
+                assert_eq!(actual, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn reports_missing_nodes_and_invalid_costs() {
+        let graph = sample_graph();
+        let frozen = graph.freeze();
+        let start = graph.node_ids().min().unwrap();
+        let missing = graph.get_node_id_alloc();
+
+        assert_eq!(
+            FrozenSearchAlgorithm::shortest_path(&frozen, start, missing, |w| *w),
+            Err(GraphError::NoSuchNode)
+        );
+        assert!(FrozenSearchAlgorithm::shortest_path_tree(&frozen, missing, |w| *w).is_err());
+        assert!(FrozenSearchAlgorithm::breadth_first_order(
+            &frozen,
+            missing,
+            TraversalDirection::Forward
+        )
+        .is_err());
+        for cost in [f64::NAN, -1_f64] {
+            assert_eq!(
+                FrozenSearchAlgorithm::shortest_path_tree(&frozen, start, |_| cost).err(),
+                Some(GraphError::InvalidEdgeCost)
+            );
+        }
+    }
+}
@@ -0,0 +1 @@
+pub mod frozen_search_algorithm;
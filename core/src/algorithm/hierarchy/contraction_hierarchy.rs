@@ -0,0 +1,998 @@
+use std::{
+    collections::{BinaryHeap, HashMap},
+    fs,
+};
+
+use serde_json::{json, Value};
+
+use crate::{
+    algorithm::dijkstra::cost_queue_entry::CostQueueEntry,
+    graph::{
+        graph_error::GraphError, graph_path::GraphPath, graph_view::GraphView,
+        io::graph_import_error::GraphImportError,
+    },
+};
+
+use super::hierarchy_arc::{HierarchyArc, HierarchyArcOrigin};
+
+/// Contraction hierarchy of a static graph, answering shortest path queries with a
+/// bidirectional search that only settles a few hundred nodes even on large maps.
+///
+/// Built once by `build`, which contracts nodes in order of importance and adds shortcut
+/// arcs so that every shortest path leads upward in rank and then downward. Edge costs
+/// are fixed at build time; rebuild the hierarchy when the graph or the costs change,
+/// for example when `get_revision` no longer matches the graph. The hierarchy can be
+/// saved to JSON with `export` and loaded at startup with `import` instead of building
+/// it again; `check_graph` then tells whether it still fits the graph and costs at hand,
+/// by comparing a fingerprint of the node and edge IDs, edge directions and edge costs.
+#[derive(Clone, Debug)]
+pub struct ContractionHierarchy {
+    node_ids: Vec<u32>,
+    node_indices: HashMap<u32, u32>,
+    ranks: Vec<u32>,
+    arcs: Vec<HierarchyArc>,
+    revision: u64,
+    fingerprint: u64,
+
+    // Arcs leading to higher ranked nodes, by tail node, for the forward search.
+    upward_offsets: Vec<u32>,
+    upward_arcs: Vec<u32>,
+    // Arcs coming from higher ranked nodes, by head node, for the backward search.
+    downward_offsets: Vec<u32>,
+    downward_arcs: Vec<u32>,
+}
+
+/// Version of the document layout written by `export_to_value`. Documents of other
+/// versions are rejected by `import_from_value`.
+pub const HIERARCHY_FORMAT_VERSION: u64 = 1;
+
+/// Witness searches give up after settling this many nodes and add the shortcut instead.
+/// Lower values build faster but add more unnecessary shortcuts.
+const WITNESS_SETTLE_LIMIT: usize = 64;
+
+/// Graph remaining during contraction, as arc indices by neighbor node.
+struct ContractionState {
+    outgoing: Vec<HashMap<u32, u32>>,
+    incoming: Vec<HashMap<u32, u32>>,
+    contracted_neighbor_counts: Vec<i64>,
+    is_contracted: Vec<bool>,
+}
+
+impl ContractionHierarchy {
+    /// Build the hierarchy of given graph, with edge costs computed from edge info like in
+    /// `DijkstraAlgorithm`. Edges are only traversed in the directions they allow.
+    pub fn build<GraphT, CostFuncT>(
+        graph: &GraphT,
+        cost_func: CostFuncT,
+    ) -> Result<ContractionHierarchy, GraphError>
+    where
+        GraphT: GraphView,
+        CostFuncT: Fn(&GraphT::EdgeInfo) -> f64,
+    {
+        let mut node_ids: Vec<u32> = graph.node_ids().collect();
+        node_ids.sort();
+        let node_indices: HashMap<u32, u32> = node_ids
+            .iter()
+            .enumerate()
+            .map(|(i, n)| (*n, i as u32))
+            .collect();
+
+        let node_count = node_ids.len();
+        let mut state = ContractionState {
+            outgoing: vec![HashMap::new(); node_count],
+            incoming: vec![HashMap::new(); node_count],
+            contracted_neighbor_counts: vec![0; node_count],
+            is_contracted: vec![false; node_count],
+        };
+        let mut arcs: Vec<HierarchyArc> = Vec::new();
+
+        // Only the cheapest of parallel edges is kept.
+        let mut edge_ids: Vec<u32> = graph.edge_ids().collect();
+        edge_ids.sort();
+        for edge_id in edge_ids {
+            let edge = graph.get_edge_by_id(&edge_id).unwrap();
+            let cost = cost_func(edge.edge_info());
+            if cost.is_nan() || cost < 0_f64 {
+                return Err(GraphError::InvalidEdgeCost);
+            }
+            if edge.node1() == edge.node2() {
+                continue;
+            }
+
+            let node1 = node_indices[&edge.node1()];
+            let node2 = node_indices[&edge.node2()];
+            for (from_node, to_node, can_move) in [
+                (node1, node2, edge.can_move_forward()),
+                (node2, node1, edge.can_move_backward()),
+            ] {
+                if can_move {
+                    ContractionHierarchy::add_arc(
+                        &mut state,
+                        &mut arcs,
+                        HierarchyArc {
+                            from_node: from_node,
+                            to_node: to_node,
+                            cost: cost,
+                            origin: HierarchyArcOrigin::Edge(edge_id),
+                        },
+                    );
+                }
+            }
+        }
+
+        // Contract the least important node first, re-evaluating priorities lazily since
+        // they change as neighbors get contracted.
+        let mut queue: BinaryHeap<CostQueueEntry> = BinaryHeap::new();
+        for node in 0..node_count as u32 {
+            queue.push(CostQueueEntry {
+                cost: ContractionHierarchy::priority(&state, &arcs, node),
+                node_id: node,
+            });
+        }
+
+        let mut ranks: Vec<u32> = vec![0; node_count];
+        let mut next_rank: u32 = 0;
+        while let Some(entry) = queue.pop() {
+            let node = entry.node_id;
+            if state.is_contracted[node as usize] {
+                continue;
+            }
+
+            let priority = ContractionHierarchy::priority(&state, &arcs, node);
+            if let Some(next) = queue.peek() {
+                if priority > next.cost {
+                    queue.push(CostQueueEntry {
+                        cost: priority,
+                        node_id: node,
+                    });
+                    continue;
+                }
+            }
+
+            for shortcut in ContractionHierarchy::find_shortcuts(&state, &arcs, node) {
+                ContractionHierarchy::add_arc(&mut state, &mut arcs, shortcut);
+            }
+            ContractionHierarchy::contract(&mut state, node);
+            ranks[node as usize] = next_rank;
+            next_rank += 1;
+        }
+
+        return Ok(ContractionHierarchy::from_parts(
+            node_ids,
+            node_indices,
+            ranks,
+            arcs,
+            graph.get_revision(),
+            ContractionHierarchy::fingerprint(graph, &cost_func)?,
+        ));
+    }
+
+    /// Revision of the graph the hierarchy was built from.
+    pub fn get_revision(&self) -> u64 {
+        return self.revision;
+    }
+
+    /// Fingerprint of the graph and costs the hierarchy was built from.
+    pub fn get_fingerprint(&self) -> u64 {
+        return self.fingerprint;
+    }
+
+    /// Fingerprint of given graph with edge costs from given function: a 64-bit FNV-1a
+    /// hash of the node IDs and of the ID, end nodes, directions and cost of every edge,
+    /// in ascending ID order. Stable across runs and platforms, unlike the hashers of
+    /// the standard library.
+    pub fn fingerprint<GraphT, CostFuncT>(
+        graph: &GraphT,
+        cost_func: CostFuncT,
+    ) -> Result<u64, GraphError>
+    where
+        GraphT: GraphView,
+        CostFuncT: Fn(&GraphT::EdgeInfo) -> f64,
+    {
+        let mut hash: u64 = 0xCBF2_9CE4_8422_2325;
+        let mut write = |bytes: &[u8]| {
+            for byte in bytes {
+                hash ^= *byte as u64;
+                hash = hash.wrapping_mul(0x0100_0000_01B3);
+            }
+        };
+
+        let mut node_ids: Vec<u32> = graph.node_ids().collect();
+        node_ids.sort();
+        write(&(node_ids.len() as u64).to_le_bytes());
+        for node_id in node_ids {
+            write(&node_id.to_le_bytes());
+        }
+
+        let mut edge_ids: Vec<u32> = graph.edge_ids().collect();
+        edge_ids.sort();
+        write(&(edge_ids.len() as u64).to_le_bytes());
+        for edge_id in edge_ids {
+            let edge = graph.get_edge_by_id(&edge_id).unwrap();
+            let cost = cost_func(edge.edge_info());
+            if cost.is_nan() || cost < 0_f64 {
+                return Err(GraphError::InvalidEdgeCost);
+            }
+
+            write(&edge_id.to_le_bytes());
+            write(&edge.node1().to_le_bytes());
+            write(&edge.node2().to_le_bytes());
+            write(&[
+                edge.can_move_forward() as u8,
+                edge.can_move_backward() as u8,
+            ]);
+            write(&cost.to_bits().to_le_bytes());
+        }
+
+        return Ok(hash);
+    }
+
+    /// Check that the hierarchy was built from given graph with the same edge costs, for
+    /// example after loading it with `import`. Returns `FingerprintMismatch` otherwise.
+    pub fn check_graph<GraphT, CostFuncT>(
+        &self,
+        graph: &GraphT,
+        cost_func: CostFuncT,
+    ) -> Result<(), GraphError>
+    where
+        GraphT: GraphView,
+        CostFuncT: Fn(&GraphT::EdgeInfo) -> f64,
+    {
+        let fingerprint = ContractionHierarchy::fingerprint(graph, cost_func)?;
+        if fingerprint != self.fingerprint {
+            return Err(GraphError::FingerprintMismatch {
+                expected: self.fingerprint,
+                actual: fingerprint,
+            });
+        }
+        return Ok(());
+    }
+
+    pub fn get_node_count(&self) -> usize {
+        return self.node_ids.len();
+    }
+
+    /// Number of arcs, counting original edges once per allowed direction and shortcuts.
+    pub fn get_arc_count(&self) -> usize {
+        return self.arcs.len();
+    }
+
+    pub fn get_arcs(&self) -> &[HierarchyArc] {
+        return &self.arcs;
+    }
+
+    /// Original ID of the node at given dense index.
+    pub fn get_node_id(&self, node_index: u32) -> u32 {
+        return self.node_ids[node_index as usize];
+    }
+
+    /// Contraction rank of given node; nodes contracted later rank higher.
+    pub fn get_rank(&self, node_id: u32) -> Option<u32> {
+        let index = self.node_indices.get(&node_id)?;
+        return Some(self.ranks[*index as usize]);
+    }
+
+    /// Find shortest path from start node to goal node, with shortcuts unpacked into the
+    /// original edges. Returns None if the goal node cannot be reached.
+    pub fn shortest_path(
+        &self,
+        start_node: u32,
+        goal_node: u32,
+    ) -> Result<Option<GraphPath>, GraphError> {
+        let (start, goal) = match (
+            self.node_indices.get(&start_node),
+            self.node_indices.get(&goal_node),
+        ) {
+            (Some(s), Some(g)) => (*s, *g),
+            _ => return Err(GraphError::NoSuchNode),
+        };
+
+        // Costs and the arcs reaching each node, for the forward and backward searches.
+        let mut costs: [HashMap<u32, (f64, Option<u32>)>; 2] = [HashMap::new(), HashMap::new()];
+        let mut queues: [BinaryHeap<CostQueueEntry>; 2] = [BinaryHeap::new(), BinaryHeap::new()];
+        for (side, node) in [(0, start), (1, goal)] {
+            costs[side].insert(node, (0_f64, None));
+            queues[side].push(CostQueueEntry {
+                cost: 0_f64,
+                node_id: node,
+            });
+        }
+
+        let mut best_cost = f64::INFINITY;
+        let mut meeting_node: Option<u32> = None;
+        loop {
+            let forward_top = queues[0].peek().map(|e| e.cost).unwrap_or(f64::INFINITY);
+            let backward_top = queues[1].peek().map(|e| e.cost).unwrap_or(f64::INFINITY);
+            if f64::min(forward_top, backward_top) >= best_cost
+                || (forward_top == f64::INFINITY && backward_top == f64::INFINITY)
+            {
+                break;
+            }
+
+            let side = if forward_top <= backward_top { 0 } else { 1 };
+            let CostQueueEntry {
+                cost,
+                node_id: node,
+            } = queues[side].pop().unwrap();
+            if cost > costs[side][&node].0 {
+                continue;
+            }
+
+            if let Some((other_cost, _)) = costs[1 - side].get(&node) {
+                if cost + other_cost < best_cost {
+                    best_cost = cost + other_cost;
+                    meeting_node = Some(node);
+                }
+            }
+
+            let (offsets, arc_list) = if side == 0 {
+                (&self.upward_offsets, &self.upward_arcs)
+            } else {
+                (&self.downward_offsets, &self.downward_arcs)
+            };
+            let range = offsets[node as usize] as usize..offsets[node as usize + 1] as usize;
+            for arc_index in arc_list[range].iter() {
+                let arc = &self.arcs[*arc_index as usize];
+                let next_node = if side == 0 {
+                    arc.to_node
+                } else {
+                    arc.from_node
+                };
+                let next_cost = cost + arc.cost;
+                let is_improved = match costs[side].get(&next_node) {
+                    Some((c, _)) => next_cost < *c,
+                    None => true,
+                };
+                if is_improved {
+                    costs[side].insert(next_node, (next_cost, Some(*arc_index)));
+                    queues[side].push(CostQueueEntry {
+                        cost: next_cost,
+                        node_id: next_node,
+                    });
+                }
+            }
+        }
+
+        let meeting_node = match meeting_node {
+            Some(n) => n,
+            None => return Ok(None),
+        };
+
+        // Arcs from the start up to the meeting node, then down to the goal.
+        let mut path_arcs: Vec<u32> = Vec::new();
+        let mut node = meeting_node;
+        while let Some((_, Some(arc_index))) = costs[0].get(&node) {
+            path_arcs.push(*arc_index);
+            node = self.arcs[*arc_index as usize].from_node;
+        }
+        path_arcs.reverse();
+        let mut node = meeting_node;
+        while let Some((_, Some(arc_index))) = costs[1].get(&node) {
+            path_arcs.push(*arc_index);
+            node = self.arcs[*arc_index as usize].to_node;
+        }
+
+        let mut nodes: Vec<u32> = vec![start_node];
+        let mut edges: Vec<u32> = Vec::new();
+        for arc_index in path_arcs {
+            self.unpack_arc(arc_index, &mut nodes, &mut edges);
+        }
+
+        return Ok(Some(GraphPath {
+            nodes: nodes,
+            edges: edges,
+            cost: best_cost,
+        }));
+    }
+
+    pub fn export(&self, file_name: &str) -> std::io::Result<()> {
+        return fs::write(file_name, self.export_to_string());
+    }
+
+    pub fn export_to_string(&self) -> String {
+        return serde_json::to_string(&self.export_to_value()).unwrap();
+    }
+
+    /// Layout of the document:
+    ///
+    /// ```text
+    /// {
+    ///   "version": 1,
+    ///   "revision": 12,
+    ///   "fingerprint": 1234567890123,
+    ///   "nodes": [ { "id": 1, "rank": 0 }, ... ],
+    ///   "arcs": [ { "from": 1, "to": 2, "cost": 1.5, "edge": 3 },
+    ///             { "from": 1, "to": 4, "cost": 2.5, "shortcut": [0, 5] }, ... ]
+    /// }
+    /// ```
+    ///
+    /// Arcs refer to nodes by ID, and shortcuts refer to their two arcs by position in
+    /// the arc list. The version is `HIERARCHY_FORMAT_VERSION` and the fingerprint is
+    /// the one of `get_fingerprint`.
+    pub fn export_to_value(&self) -> Value {
+        let nodes: Vec<Value> = self
+            .node_ids
+            .iter()
+            .zip(self.ranks.iter())
+            .map(|(node_id, rank)| json!({ "id": node_id, "rank": rank }))
+            .collect();
+
+        let arcs: Vec<Value> = self
+            .arcs
+            .iter()
+            .map(|arc| {
+                let from = self.node_ids[arc.from_node as usize];
+                let to = self.node_ids[arc.to_node as usize];
+                return match arc.origin {
+                    HierarchyArcOrigin::Edge(edge_id) => {
+                        json!({ "from": from, "to": to, "cost": arc.cost, "edge": edge_id })
+                    }
+                    HierarchyArcOrigin::Shortcut {
+                        first_arc,
+                        second_arc,
+                    } => json!({
+                        "from": from,
+                        "to": to,
+                        "cost": arc.cost,
+                        "shortcut": [first_arc, second_arc]
+                    }),
+                };
+            })
+            .collect();
+
+        return json!({
+            "version": HIERARCHY_FORMAT_VERSION,
+            "revision": self.revision,
+            "fingerprint": self.fingerprint,
+            "nodes": nodes,
+            "arcs": arcs,
+        });
+    }
+
+    pub fn import(file_name: &str) -> Result<ContractionHierarchy, GraphImportError> {
+        let text = match fs::read_to_string(file_name) {
+            Ok(t) => t,
+            Err(e) => return Err(GraphImportError::Io(e.to_string())),
+        };
+        return ContractionHierarchy::import_from_str(&text);
+    }
+
+    pub fn import_from_str(text: &str) -> Result<ContractionHierarchy, GraphImportError> {
+        let value: Value = match serde_json::from_str(text) {
+            Ok(v) => v,
+            Err(e) => return Err(GraphImportError::Syntax(e.to_string())),
+        };
+        return ContractionHierarchy::import_from_value(&value);
+    }
+
+    /// Load a hierarchy written by `export_to_value`, checking the format version and that
+    /// every shortcut consists of two earlier arcs joined at a lower ranked node, with a
+    /// cost equal to the sum of their costs. Use `check_graph` to check that the hierarchy
+    /// fits the graph it is used with.
+    pub fn import_from_value(value: &Value) -> Result<ContractionHierarchy, GraphImportError> {
+        match value["version"].as_u64() {
+            Some(HIERARCHY_FORMAT_VERSION) => {}
+            Some(version) => {
+                return Err(ContractionHierarchy::invalid(&format!(
+                    "unsupported version {}, expected {}",
+                    version, HIERARCHY_FORMAT_VERSION
+                )))
+            }
+            None => {
+                return Err(ContractionHierarchy::invalid(
+                    "\"version\" must be an integer",
+                ))
+            }
+        }
+        let fingerprint = match value["fingerprint"].as_u64() {
+            Some(f) => f,
+            None => {
+                return Err(ContractionHierarchy::invalid(
+                    "\"fingerprint\" must be an integer",
+                ))
+            }
+        };
+        let revision = match value["revision"].as_u64() {
+            Some(r) => r,
+            None => {
+                return Err(ContractionHierarchy::invalid(
+                    "\"revision\" must be an integer",
+                ))
+            }
+        };
+        let node_values = match value["nodes"].as_array() {
+            Some(n) => n,
+            None => return Err(ContractionHierarchy::invalid("\"nodes\" must be an array")),
+        };
+        let arc_values = match value["arcs"].as_array() {
+            Some(a) => a,
+            None => return Err(ContractionHierarchy::invalid("\"arcs\" must be an array")),
+        };
+
+        let mut node_ids: Vec<u32> = Vec::with_capacity(node_values.len());
+        let mut ranks: Vec<u32> = Vec::with_capacity(node_values.len());
+        let mut node_indices: HashMap<u32, u32> = HashMap::new();
+        for node in node_values {
+            let node_id = ContractionHierarchy::get_u32(&node["id"], "node id")?;
+            let rank = ContractionHierarchy::get_u32(&node["rank"], "node rank")?;
+            if node_indices
+                .insert(node_id, node_ids.len() as u32)
+                .is_some()
+            {
+                return Err(GraphImportError::Graph(GraphError::DuplicateNodeId(
+                    node_id,
+                )));
+            }
+            node_ids.push(node_id);
+            ranks.push(rank);
+        }
+
+        let mut sorted_ranks = ranks.clone();
+        sorted_ranks.sort();
+        if sorted_ranks.iter().enumerate().any(|(i, r)| *r != i as u32) {
+            return Err(ContractionHierarchy::invalid(
+                "node ranks must be distinct and start at 0",
+            ));
+        }
+
+        let mut arcs: Vec<HierarchyArc> = Vec::with_capacity(arc_values.len());
+        for (arc_index, arc) in arc_values.iter().enumerate() {
+            let mut ends: [u32; 2] = [0; 2];
+            for (i, key) in ["from", "to"].iter().enumerate() {
+                let node_id = ContractionHierarchy::get_u32(&arc[*key], "arc end node")?;
+                ends[i] = match node_indices.get(&node_id) {
+                    Some(n) => *n,
+                    None => {
+                        return Err(ContractionHierarchy::invalid(&format!(
+                            "arc {} refers to missing node {}",
+                            arc_index, node_id
+                        )))
+                    }
+                };
+            }
+
+            let cost = match arc["cost"].as_f64() {
+                Some(c) if c >= 0_f64 => c,
+                _ => return Err(GraphImportError::Graph(GraphError::InvalidEdgeCost)),
+            };
+
+            let origin = if let Some(parts) = arc["shortcut"].as_array() {
+                let part_indices: Vec<u32> = parts
+                    .iter()
+                    .map(|p| ContractionHierarchy::get_u32(p, "shortcut arc"))
+                    .collect::<Result<Vec<u32>, GraphImportError>>()?;
+                let is_valid = match part_indices.as_slice() {
+                    [first, second]
+                        if (*first as usize) < arc_index && (*second as usize) < arc_index =>
+                    {
+                        let first_arc = &arcs[*first as usize];
+                        let second_arc = &arcs[*second as usize];
+                        let part_cost = first_arc.cost + second_arc.cost;
+                        first_arc.from_node == ends[0]
+                            && second_arc.to_node == ends[1]
+                            && first_arc.to_node == second_arc.from_node
+                            && ranks[first_arc.to_node as usize] < ranks[ends[0] as usize]
+                            && ranks[first_arc.to_node as usize] < ranks[ends[1] as usize]
+                            && (cost - part_cost).abs() <= 1e-9 * f64::max(1_f64, part_cost)
+                    }
+                    _ => false,
+                };
+                if !is_valid {
+                    return Err(ContractionHierarchy::invalid(&format!(
+                        "arc {} is not a valid shortcut",
+                        arc_index
+                    )));
+                }
+                HierarchyArcOrigin::Shortcut {
+                    first_arc: part_indices[0],
+                    second_arc: part_indices[1],
+                }
+            } else {
+                HierarchyArcOrigin::Edge(ContractionHierarchy::get_u32(&arc["edge"], "arc edge")?)
+            };
+
+            if ends[0] == ends[1] {
+                return Err(ContractionHierarchy::invalid(&format!(
+                    "arc {} is a loop",
+                    arc_index
+                )));
+            }
+            arcs.push(HierarchyArc {
+                from_node: ends[0],
+                to_node: ends[1],
+                cost: cost,
+                origin: origin,
+            });
+        }
+
+        return Ok(ContractionHierarchy::from_parts(
+            node_ids,
+            node_indices,
+            ranks,
+            arcs,
+            revision,
+            fingerprint,
+        ));
+    }
+
+    /// Sort arcs into the upward and downward search graphs by the ranks of their ends.
+    fn from_parts(
+        node_ids: Vec<u32>,
+        node_indices: HashMap<u32, u32>,
+        ranks: Vec<u32>,
+        arcs: Vec<HierarchyArc>,
+        revision: u64,
+        fingerprint: u64,
+    ) -> ContractionHierarchy {
+        let node_count = node_ids.len();
+        let mut upward: Vec<Vec<u32>> = vec![Vec::new(); node_count];
+        let mut downward: Vec<Vec<u32>> = vec![Vec::new(); node_count];
+        for (arc_index, arc) in arcs.iter().enumerate() {
+            if ranks[arc.from_node as usize] < ranks[arc.to_node as usize] {
+                upward[arc.from_node as usize].push(arc_index as u32);
+            } else {
+                downward[arc.to_node as usize].push(arc_index as u32);
+            }
+        }
+
+        let (upward_offsets, upward_arcs) = ContractionHierarchy::compress(upward);
+        let (downward_offsets, downward_arcs) = ContractionHierarchy::compress(downward);
+        return ContractionHierarchy {
+            node_ids: node_ids,
+            node_indices: node_indices,
+            ranks: ranks,
+            arcs: arcs,
+            revision: revision,
+            fingerprint: fingerprint,
+            upward_offsets: upward_offsets,
+            upward_arcs: upward_arcs,
+            downward_offsets: downward_offsets,
+            downward_arcs: downward_arcs,
+        };
+    }
+
+    fn compress(lists: Vec<Vec<u32>>) -> (Vec<u32>, Vec<u32>) {
+        let mut offsets: Vec<u32> = Vec::with_capacity(lists.len() + 1);
+        let mut values: Vec<u32> = Vec::new();
+        for list in lists {
+            offsets.push(values.len() as u32);
+            values.extend(list);
+        }
+        offsets.push(values.len() as u32);
+        return (offsets, values);
+    }
+
+    /// Append the nodes and original edges of given arc to a path ending at its tail node.
+    fn unpack_arc(&self, arc_index: u32, nodes: &mut Vec<u32>, edges: &mut Vec<u32>) {
+        let mut stack: Vec<u32> = vec![arc_index];
+        while let Some(arc_index) = stack.pop() {
+            let arc = &self.arcs[arc_index as usize];
+            match arc.origin {
+                HierarchyArcOrigin::Edge(edge_id) => {
+                    nodes.push(self.node_ids[arc.to_node as usize]);
+                    edges.push(edge_id);
+                }
+                HierarchyArcOrigin::Shortcut {
+                    first_arc,
+                    second_arc,
+                } => {
+                    stack.push(second_arc);
+                    stack.push(first_arc);
+                }
+            }
+        }
+    }
+
+    /// Add arc to the remaining graph, unless an arc between the same nodes is cheaper.
+    fn add_arc(state: &mut ContractionState, arcs: &mut Vec<HierarchyArc>, arc: HierarchyArc) {
+        if let Some(existing) = state.outgoing[arc.from_node as usize].get(&arc.to_node) {
+            if arcs[*existing as usize].cost <= arc.cost {
+                return;
+            }
+        }
+
+        let arc_index = arcs.len() as u32;
+        state.outgoing[arc.from_node as usize].insert(arc.to_node, arc_index);
+        state.incoming[arc.to_node as usize].insert(arc.from_node, arc_index);
+        arcs.push(arc);
+    }
+
+    fn contract(state: &mut ContractionState, node: u32) {
+        let outgoing = std::mem::take(&mut state.outgoing[node as usize]);
+        let incoming = std::mem::take(&mut state.incoming[node as usize]);
+        for next_node in outgoing.keys() {
+            state.incoming[*next_node as usize].remove(&node);
+            state.contracted_neighbor_counts[*next_node as usize] += 1;
+        }
+        for previous_node in incoming.keys() {
+            state.outgoing[*previous_node as usize].remove(&node);
+            state.contracted_neighbor_counts[*previous_node as usize] += 1;
+        }
+        state.is_contracted[node as usize] = true;
+    }
+
+    /// Contraction order priority: shortcuts added minus arcs removed, plus the number of
+    /// contracted neighbors to spread contraction evenly over the graph.
+    fn priority(state: &ContractionState, arcs: &[HierarchyArc], node: u32) -> f64 {
+        let shortcut_count = ContractionHierarchy::find_shortcuts(state, arcs, node).len() as i64;
+        let removed_count =
+            (state.outgoing[node as usize].len() + state.incoming[node as usize].len()) as i64;
+        return (shortcut_count - removed_count + state.contracted_neighbor_counts[node as usize])
+            as f64;
+    }
+
+    /// Shortcuts needed to keep shortest paths through given node once it is contracted.
+    /// A shortcut is skipped if a witness path avoiding the node is no longer.
+    fn find_shortcuts(
+        state: &ContractionState,
+        arcs: &[HierarchyArc],
+        node: u32,
+    ) -> Vec<HierarchyArc> {
+        let mut shortcuts: Vec<HierarchyArc> = Vec::new();
+        let outgoing = &state.outgoing[node as usize];
+        if outgoing.is_empty() {
+            return shortcuts;
+        }
+
+        let mut incoming: Vec<(u32, u32)> = state.incoming[node as usize]
+            .iter()
+            .map(|(n, a)| (*n, *a))
+            .collect();
+        incoming.sort();
+        let mut outgoing: Vec<(u32, u32)> = outgoing.iter().map(|(n, a)| (*n, *a)).collect();
+        outgoing.sort();
+        let max_outgoing_cost = outgoing
+            .iter()
+            .map(|(_, a)| arcs[*a as usize].cost)
+            .fold(0_f64, f64::max);
+
+        for (previous_node, first_arc) in incoming {
+            let first_cost = arcs[first_arc as usize].cost;
+            let witness_costs = ContractionHierarchy::witness_search(
+                state,
+                arcs,
+                previous_node,
+                node,
+                first_cost + max_outgoing_cost,
+            );
+
+            for (next_node, second_arc) in outgoing.iter() {
+                if *next_node == previous_node {
+                    continue;
+                }
+
+                let cost = first_cost + arcs[*second_arc as usize].cost;
+                if let Some(witness_cost) = witness_costs.get(next_node) {
+                    if *witness_cost <= cost {
+                        continue;
+                    }
+                }
+
+                shortcuts.push(HierarchyArc {
+                    from_node: previous_node,
+                    to_node: *next_node,
+                    cost: cost,
+                    origin: HierarchyArcOrigin::Shortcut {
+                        first_arc: first_arc,
+                        second_arc: *second_arc,
+                    },
+                });
+            }
+        }
+
+        return shortcuts;
+    }
+
+    /// Bounded Dijkstra search from given node over the remaining graph, avoiding the node
+    /// being contracted.
+    fn witness_search(
+        state: &ContractionState,
+        arcs: &[HierarchyArc],
+        start: u32,
+        avoided_node: u32,
+        max_cost: f64,
+    ) -> HashMap<u32, f64> {
+        let mut costs: HashMap<u32, f64> = HashMap::from([(start, 0_f64)]);
+        let mut queue: BinaryHeap<CostQueueEntry> = BinaryHeap::from([CostQueueEntry {
+            cost: 0_f64,
+            node_id: start,
+        }]);
+
+        let mut settled_count: usize = 0;
+        while let Some(CostQueueEntry {
+            cost,
+            node_id: node,
+        }) = queue.pop()
+        {
+            if cost > costs[&node] {
+                continue;
+            }
+            if cost > max_cost || settled_count >= WITNESS_SETTLE_LIMIT {
+                break;
+            }
+            settled_count += 1;
+
+            for (next_node, arc_index) in state.outgoing[node as usize].iter() {
+                if *next_node == avoided_node {
+                    continue;
+                }
+
+                let next_cost = cost + arcs[*arc_index as usize].cost;
+                let is_improved = match costs.get(next_node) {
+                    Some(c) => next_cost < *c,
+                    None => true,
+                };
+                if is_improved {
+                    costs.insert(*next_node, next_cost);
+                    queue.push(CostQueueEntry {
+                        cost: next_cost,
+                        node_id: *next_node,
+                    });
+                }
+            }
+        }
+
+        return costs;
+    }
+
+    fn get_u32(value: &Value, name: &str) -> Result<u32, GraphImportError> {
+        return match value.as_u64() {
+            Some(v) if v <= u32::MAX as u64 => Ok(v as u32),
+            _ => Err(ContractionHierarchy::invalid(&format!(
+                "{} must be an unsigned 32-bit integer",
+                name
+            ))),
+        };
+    }
+
+    fn invalid(message: &str) -> GraphImportError {
+        return GraphImportError::InvalidStructure(String::from(message));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::dijkstra::dijkstra_algorithm::DijkstraAlgorithm;
+    use crate::graph::graph::Graph;
+
+    /// Random sparse graph with one-way edges and a few isolated nodes, so that some
+    /// pairs cannot reach each other in one or both directions.
+    fn random_graph(seed: u32, node_count: usize, edge_count: usize) -> Graph<(), f64> {
+        let mut graph: Graph<(), f64> = Graph::new(false, false);
+        let nodes: Vec<u32> = (0..node_count).map(|_| graph.add_node(())).collect();
+        let mut state = seed;
+        let mut next = || {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            return state >> 8;
+        };
+        for _ in 0..edge_count {
+            let node1 = nodes[next() as usize % (node_count - 2)];
+            let node2 = nodes[next() as usize % (node_count - 2)];
+            let cost = (next() % 100) as f64 / 10_f64;
+            let (forward, backward) = match next() % 3 {
+                0 => (true, true),
+                1 => (true, false),
+                _ => (false, true),
+            };
+            // Self-loops and duplicates are rejected, which is fine here.
+            let _ = graph.add_directed_edge(node1, node2, forward, backward, cost);
+        }
+        return graph;
+    }
+
+    fn assert_matches_dijkstra(hierarchy: &ContractionHierarchy, graph: &Graph<(), f64>) {
+        let mut node_ids: Vec<u32> = graph.node_ids().collect();
+        node_ids.sort();
+        for start in node_ids.iter() {
+            let tree = DijkstraAlgorithm::shortest_path_tree(graph, *start, |w| *w).unwrap();
+            for goal in node_ids.iter() {
+                let path = hierarchy.shortest_path(*start, *goal).unwrap();
+                let expected = tree.distance_to(*goal);
+                assert_eq!(path.is_some(), expected.is_some(), "{} -> {}", start, goal);
+                if let (Some(path), Some(expected)) = (path, expected) {
+                    assert!((path.cost - expected).abs() < 1e-9);
+                    assert_eq!(path.start_node(), *start);
+                    assert_eq!(path.goal_node(), *goal);
+                    assert_eq!(path.edges.len() + 1, path.nodes.len());
+
+                    let mut cost = 0_f64;
+                    for (i, edge_id) in path.edges.iter().enumerate() {
+                        let edge = graph.get_edge_by_id(edge_id).unwrap();
+                        let (from, to) = (path.nodes[i], path.nodes[i + 1]);
+                        assert!(
+                            (edge.node1() == from && edge.node2() == to && edge.can_move_forward())
+                                || (edge.node2() == from
+                                    && edge.node1() == to
+                                    && edge.can_move_backward())
+                        );
+                        cost += edge.edge_info();
+                    }
+                    assert!((path.cost - cost).abs() < 1e-9);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn results_match_dijkstra_on_random_graphs() {
+        for seed in 1..6 {
+            let graph = random_graph(seed, 40, 90);
+            let hierarchy = ContractionHierarchy::build(&graph, |w| *w).unwrap();
+            assert_eq!(hierarchy.get_node_count(), graph.get_node_count());
+            assert_matches_dijkstra(&hierarchy, &graph);
+        }
+    }
+
+    #[test]
+    fn export_import_round_trip() {
+        let graph = random_graph(7, 40, 90);
+        let hierarchy = ContractionHierarchy::build(&graph, |w| *w).unwrap();
+        let text = hierarchy.export_to_string();
+        let imported = ContractionHierarchy::import_from_str(&text).unwrap();
+
+        assert_eq!(imported.get_revision(), hierarchy.get_revision());
+        assert_eq!(imported.get_fingerprint(), hierarchy.get_fingerprint());
+        assert_eq!(imported.get_arcs(), hierarchy.get_arcs());
+        assert_eq!(imported.export_to_string(), text);
+        assert!(imported.check_graph(&graph, |w| *w).is_ok());
+        assert_matches_dijkstra(&imported, &graph);
+    }
+
+    #[test]
+    fn import_rejects_other_version_and_wrong_shortcut_cost() {
+        let graph = random_graph(3, 30, 70);
+        let hierarchy = ContractionHierarchy::build(&graph, |w| *w).unwrap();
+        let value = hierarchy.export_to_value();
+
+        let mut other_version = value.clone();
+        other_version["version"] = json!(HIERARCHY_FORMAT_VERSION + 1);
+        assert!(matches!(
+            ContractionHierarchy::import_from_value(&other_version),
+            Err(GraphImportError::InvalidStructure(_))
+        ));
+        let mut no_version = value.clone();
+        no_version.as_object_mut().unwrap().remove("version");
+        assert!(ContractionHierarchy::import_from_value(&no_version).is_err());
+
+        let shortcut = value["arcs"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .position(|arc| arc.get("shortcut").is_some())
+            .expect("expected at least one shortcut");
+        let mut tampered = value.clone();
+        let cost = tampered["arcs"][shortcut]["cost"].as_f64().unwrap();
+        tampered["arcs"][shortcut]["cost"] = json!(cost + 0.5);
+        assert!(matches!(
+            ContractionHierarchy::import_from_value(&tampered),
+            Err(GraphImportError::InvalidStructure(_))
+        ));
+    }
+
+    #[test]
+    fn check_graph_detects_changed_graph() {
+        let mut graph = random_graph(5, 30, 70);
+        let hierarchy = ContractionHierarchy::build(&graph, |w| *w).unwrap();
+        assert!(hierarchy.check_graph(&graph, |w| *w).is_ok());
+        assert!(matches!(
+            hierarchy.check_graph(&graph, |w| *w * 2_f64),
+            Err(GraphError::FingerprintMismatch { .. })
+        ));
+
+        let edge_id = graph.edge_ids().min().unwrap();
+        graph.update_edge_info(&edge_id, |w| *w += 1_f64).unwrap();
+        assert!(matches!(
+            hierarchy.check_graph(&graph, |w| *w),
+            Err(GraphError::FingerprintMismatch { .. })
+        ));
+        graph.update_edge_info(&edge_id, |w| *w -= 1_f64).unwrap();
+        assert!(hierarchy.check_graph(&graph, |w| *w).is_ok());
+
+        graph.remove_edge(&edge_id).unwrap();
+        assert!(matches!(
+            hierarchy.check_graph(&graph, |w| *w),
+            Err(GraphError::FingerprintMismatch { .. })
+        ));
+    }
+}
@@ -0,0 +1,19 @@
+/// Directed connection of a `ContractionHierarchy`, either an edge of the original graph
+/// or a shortcut standing for two consecutive arcs.
+/// Nodes are given as dense indices of the hierarchy, see `ContractionHierarchy::get_node_id`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HierarchyArc {
+    pub from_node: u32,
+    pub to_node: u32,
+    pub cost: f64,
+    pub origin: HierarchyArcOrigin,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HierarchyArcOrigin {
+    /// Edge of the original graph with given ID, traversed from `from_node` to `to_node`.
+    Edge(u32),
+    /// Shortcut replacing the path through a contracted node, given as indices of the two
+    /// arcs it consists of. Both arcs were added before the shortcut.
+    Shortcut { first_arc: u32, second_arc: u32 },
+}
@@ -0,0 +1,2 @@
+pub mod contraction_hierarchy;
+pub mod hierarchy_arc;
@@ -0,0 +1 @@
+pub mod yen_algorithm;
@@ -0,0 +1,585 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::{
+    algorithm::dijkstra::dijkstra_algorithm::DijkstraAlgorithm,
+    graph::{
+        filtered_graph::FilteredGraph, graph_error::GraphError, graph_path::GraphPath,
+        graph_view::GraphView,
+    },
+};
+
+/// Restriction on how alternative paths may overlap.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PathDisjointness {
+    /// Paths may share nodes and edges, but no two paths are identical.
+    None,
+    /// Paths share no edge.
+    EdgeDisjoint,
+    /// Paths share no node other than the start and goal nodes.
+    NodeDisjoint,
+}
+
+/// K shortest loopless paths with Yen's algorithm.
+///
+/// Edge costs are computed from edge info by a caller-supplied function, like in
+/// `DijkstraAlgorithm`, and edges are only traversed in the directions they allow.
+/// Paths are returned in ascending cost order, starting with the shortest path.
+///
+/// Edge-disjoint and node-disjoint alternatives are not found by Yen's algorithm but as a
+/// minimum cost flow of up to k units from the start to the goal node, with successive
+/// shortest paths in the style of Suurballe and Bhandari. This returns as many disjoint
+/// paths as the graph allows, up to k, with the lowest total cost, even where taking the
+/// shortest path first would leave no room for a second one. Fewer than k paths are
+/// returned once the start and goal nodes are separated.
+pub struct YenAlgorithm;
+
+/// Arc of the residual network used for disjoint paths. Arcs come in pairs; arc `i ^ 1`
+/// is the residual of arc `i`.
+struct DisjointArc {
+    to: usize,
+    capacity: usize,
+    cost: f64,
+    edge_id: Option<u32>,
+}
+
+impl YenAlgorithm {
+    pub fn k_shortest_paths<GraphT, CostFuncT>(
+        graph: &GraphT,
+        start_node: u32,
+        goal_node: u32,
+        path_count: usize,
+        disjointness: PathDisjointness,
+        cost_func: CostFuncT,
+    ) -> Result<Vec<GraphPath>, GraphError>
+    where
+        GraphT: GraphView,
+        CostFuncT: Fn(&GraphT::EdgeInfo) -> f64,
+    {
+        if graph.get_node_by_id(&start_node).is_none() || graph.get_node_by_id(&goal_node).is_none()
+        {
+            return Err(GraphError::NoSuchNode);
+        }
+        if path_count == 0 {
+            return Ok(Vec::new());
+        }
+
+        return match disjointness {
+            PathDisjointness::None => {
+                YenAlgorithm::yen(graph, start_node, goal_node, path_count, &cost_func)
+            }
+            _ => YenAlgorithm::disjoint(
+                graph,
+                start_node,
+                goal_node,
+                path_count,
+                disjointness,
+                &cost_func,
+            ),
+        };
+    }
+
+    fn yen<GraphT, CostFuncT>(
+        graph: &GraphT,
+        start_node: u32,
+        goal_node: u32,
+        path_count: usize,
+        cost_func: &CostFuncT,
+    ) -> Result<Vec<GraphPath>, GraphError>
+    where
+        GraphT: GraphView,
+        CostFuncT: Fn(&GraphT::EdgeInfo) -> f64,
+    {
+        let mut paths: Vec<GraphPath> =
+            match DijkstraAlgorithm::shortest_path(graph, start_node, goal_node, cost_func)? {
+                Some(p) => vec![p],
+                None => return Ok(Vec::new()),
+            };
+        let mut candidates: Vec<GraphPath> = Vec::new();
+        let mut known_paths: HashSet<Vec<u32>> = HashSet::from([paths[0].edges.clone()]);
+
+        while paths.len() < path_count {
+            let previous_path = paths.last().unwrap().clone();
+
+            for spur_index in 0..previous_path.edges.len() {
+                let spur_node = previous_path.nodes[spur_index];
+                let root_nodes = &previous_path.nodes[..=spur_index];
+                let root_edges = &previous_path.edges[..spur_index];
+
+                // Leave the spur node by an edge no found path with the same root takes,
+                // and never return to the root path.
+                let removed_edges: HashSet<u32> = paths
+                    .iter()
+                    .filter(|p| p.edges.len() > spur_index && p.edges[..spur_index] == *root_edges)
+                    .map(|p| p.edges[spur_index])
+                    .collect();
+                let removed_nodes: HashSet<u32> =
+                    root_nodes[..spur_index].iter().copied().collect();
+
+                let view = FilteredGraph::new(
+                    graph,
+                    |n| !removed_nodes.contains(&n.get_id()),
+                    |e| !removed_edges.contains(&e.get_id()),
+                );
+                let spur_path =
+                    match DijkstraAlgorithm::shortest_path(&view, spur_node, goal_node, cost_func)?
+                    {
+                        Some(p) => p,
+                        None => continue,
+                    };
+
+                let mut root_cost = 0_f64;
+                for edge_id in root_edges {
+                    root_cost += cost_func(graph.get_edge_by_id(edge_id).unwrap().edge_info());
+                }
+
+                let mut nodes = root_nodes[..spur_index].to_vec();
+                nodes.extend(spur_path.nodes);
+                let mut edges = root_edges.to_vec();
+                edges.extend(spur_path.edges);
+                if known_paths.insert(edges.clone()) {
+                    candidates.push(GraphPath {
+                        nodes: nodes,
+                        edges: edges,
+                        cost: root_cost + spur_path.cost,
+                    });
+                }
+            }
+
+            // Take the cheapest candidate, preferring fewer edges, then lower edge IDs.
+            let best_index = (0..candidates.len()).min_by(|a, b| {
+                let (a, b) = (&candidates[*a], &candidates[*b]);
+                return a
+                    .cost
+                    .total_cmp(&b.cost)
+                    .then(a.edges.len().cmp(&b.edges.len()))
+                    .then(a.edges.cmp(&b.edges));
+            });
+            match best_index {
+                Some(i) => paths.push(candidates.swap_remove(i)),
+                None => break,
+            }
+        }
+
+        return Ok(paths);
+    }
+
+    /// Minimum cost flow of up to `path_count` units, decomposed into paths.
+    ///
+    /// Every node is split into an entry and an exit joined by an arc of capacity 1 for
+    /// node-disjoint paths, so that at most one path passes it, and of capacity
+    /// `path_count` otherwise. Every edge direction allowed becomes an arc of capacity 1
+    /// from the exit of one end node to the entry of the other.
+    fn disjoint<GraphT, CostFuncT>(
+        graph: &GraphT,
+        start_node: u32,
+        goal_node: u32,
+        path_count: usize,
+        disjointness: PathDisjointness,
+        cost_func: &CostFuncT,
+    ) -> Result<Vec<GraphPath>, GraphError>
+    where
+        GraphT: GraphView,
+        CostFuncT: Fn(&GraphT::EdgeInfo) -> f64,
+    {
+        if start_node == goal_node {
+            // Path from a node to itself shares that node with any other.
+            return Ok(vec![GraphPath {
+                nodes: vec![start_node],
+                edges: Vec::new(),
+                cost: 0_f64,
+            }]);
+        }
+
+        let mut node_ids: Vec<u32> = graph.node_ids().collect();
+        node_ids.sort();
+        let node_indices: HashMap<u32, usize> =
+            node_ids.iter().enumerate().map(|(i, n)| (*n, i)).collect();
+
+        let mut arcs: Vec<DisjointArc> = Vec::new();
+        let mut adjacency: Vec<Vec<usize>> = vec![Vec::new(); 2 * node_ids.len()];
+        let mut add_arc_pair = |from: usize, to: usize, capacity: usize, cost: f64, edge_id| {
+            adjacency[from].push(arcs.len());
+            arcs.push(DisjointArc {
+                to: to,
+                capacity: capacity,
+                cost: cost,
+                edge_id: edge_id,
+            });
+            adjacency[to].push(arcs.len());
+            arcs.push(DisjointArc {
+                to: from,
+                capacity: 0,
+                cost: -cost,
+                edge_id: edge_id,
+            });
+        };
+
+        // Node with index i enters at 2 * i and exits at 2 * i + 1.
+        for (i, node_id) in node_ids.iter().enumerate() {
+            let capacity = match disjointness {
+                PathDisjointness::NodeDisjoint
+                    if *node_id != start_node && *node_id != goal_node =>
+                {
+                    1
+                }
+                _ => path_count,
+            };
+            add_arc_pair(2 * i, 2 * i + 1, capacity, 0_f64, None);
+        }
+
+        let mut edge_ids: Vec<u32> = graph.edge_ids().collect();
+        edge_ids.sort();
+        for edge_id in edge_ids {
+            let edge = graph.get_edge_by_id(&edge_id).unwrap();
+            if edge.node1() == edge.node2() {
+                continue;
+            }
+
+            let cost = cost_func(edge.edge_info());
+            if cost.is_nan() || cost < 0_f64 {
+                return Err(GraphError::InvalidEdgeCost);
+            }
+
+            let index1 = node_indices[&edge.node1()];
+            let index2 = node_indices[&edge.node2()];
+            if edge.can_move_forward() {
+                add_arc_pair(2 * index1 + 1, 2 * index2, 1, cost, Some(edge_id));
+            }
+            if edge.can_move_backward() {
+                add_arc_pair(2 * index2 + 1, 2 * index1, 1, cost, Some(edge_id));
+            }
+        }
+
+        let source = 2 * node_indices[&start_node] + 1;
+        let sink = 2 * node_indices[&goal_node];
+        let mut flow_value: usize = 0;
+        while flow_value < path_count {
+            let path_arcs =
+                match YenAlgorithm::cheapest_augmenting_path(&arcs, &adjacency, source, sink) {
+                    Some(p) => p,
+                    None => break,
+                };
+            for arc in path_arcs {
+                arcs[arc].capacity -= 1;
+                arcs[arc ^ 1].capacity += 1;
+            }
+            flow_value += 1;
+        }
+
+        // Net flow through each edge, from node to node. Opposite flows through the same
+        // edge cancel out, so that no edge is used twice.
+        let mut edge_flows: HashMap<u32, (u32, u32, isize)> = HashMap::new();
+        for arc in (0..arcs.len()).step_by(2) {
+            let edge_id = match arcs[arc].edge_id {
+                Some(e) => e,
+                None => continue,
+            };
+            let flow = arcs[arc ^ 1].capacity as isize;
+            if flow == 0 {
+                continue;
+            }
+
+            let from = node_ids[arcs[arc ^ 1].to / 2];
+            let to = node_ids[arcs[arc].to / 2];
+            let entry = edge_flows.entry(edge_id).or_insert((from, to, 0));
+            if entry.0 == from {
+                entry.2 += flow;
+            } else {
+                entry.2 -= flow;
+            }
+        }
+
+        let mut outgoing: HashMap<u32, Vec<(u32, u32)>> = HashMap::new();
+        for (edge_id, (from, to, flow)) in edge_flows {
+            let (from, to) = if flow > 0 { (from, to) } else { (to, from) };
+            for _ in 0..flow.unsigned_abs() {
+                outgoing.entry(from).or_default().push((edge_id, to));
+            }
+        }
+        for edges in outgoing.values_mut() {
+            // Popped from the back, so lower edge IDs are followed first.
+            edges.sort_by(|a, b| b.cmp(a));
+        }
+
+        let mut paths: Vec<GraphPath> = Vec::with_capacity(flow_value);
+        for _ in 0..flow_value {
+            let mut nodes: Vec<u32> = vec![start_node];
+            let mut edges: Vec<u32> = Vec::new();
+            let mut positions: HashMap<u32, usize> = HashMap::from([(start_node, 0)]);
+            let mut current = start_node;
+            while current != goal_node {
+                let (edge_id, next_node_id) = outgoing.get_mut(&current).unwrap().pop().unwrap();
+                // Drop cycles of zero cost edges the flow may contain.
+                if let Some(position) = positions.get(&next_node_id).copied() {
+                    for node_id in nodes.drain(position + 1..) {
+                        positions.remove(&node_id);
+                    }
+                    edges.truncate(position);
+                } else {
+                    positions.insert(next_node_id, nodes.len());
+                    nodes.push(next_node_id);
+                    edges.push(edge_id);
+                }
+                current = next_node_id;
+            }
+
+            let cost = edges
+                .iter()
+                .map(|e| cost_func(graph.get_edge_by_id(e).unwrap().edge_info()))
+                .sum();
+            paths.push(GraphPath {
+                nodes: nodes,
+                edges: edges,
+                cost: cost,
+            });
+        }
+
+        paths.sort_by(|a, b| {
+            return a
+                .cost
+                .total_cmp(&b.cost)
+                .then(a.edges.len().cmp(&b.edges.len()))
+                .then(a.edges.cmp(&b.edges));
+        });
+        return Ok(paths);
+    }
+
+    /// Cheapest path from source to sink over arcs with capacity left, found with the
+    /// Bellman-Ford queue variant since residual arcs have negative costs. The residual
+    /// network of a minimum cost flow has no negative cycles.
+    fn cheapest_augmenting_path(
+        arcs: &[DisjointArc],
+        adjacency: &[Vec<usize>],
+        source: usize,
+        sink: usize,
+    ) -> Option<Vec<usize>> {
+        let mut costs: Vec<f64> = vec![f64::INFINITY; adjacency.len()];
+        let mut previous_arcs: Vec<Option<usize>> = vec![None; adjacency.len()];
+        let mut queued: Vec<bool> = vec![false; adjacency.len()];
+        let mut queue: VecDeque<usize> = VecDeque::from([source]);
+        costs[source] = 0_f64;
+        queued[source] = true;
+
+        while let Some(node) = queue.pop_front() {
+            queued[node] = false;
+            for arc in adjacency[node].iter() {
+                let DisjointArc {
+                    to, capacity, cost, ..
+                } = arcs[*arc];
+                // Tolerance keeps rounding errors from cycling through zero cost loops.
+                if capacity > 0 && costs[node] + cost < costs[to] - 1e-12 {
+                    costs[to] = costs[node] + cost;
+                    previous_arcs[to] = Some(*arc);
+                    if !queued[to] {
+                        queued[to] = true;
+                        queue.push_back(to);
+                    }
+                }
+            }
+        }
+
+        if costs[sink].is_infinite() {
+            return None;
+        }
+
+        let mut path_arcs: Vec<usize> = Vec::new();
+        let mut node = sink;
+        while let Some(arc) = previous_arcs[node] {
+            path_arcs.push(arc);
+            node = arcs[arc ^ 1].to;
+        }
+        return Some(path_arcs);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::graph::Graph;
+
+    /// Directed example graph of Yen's algorithm on Wikipedia, nodes C to H.
+    fn example_graph() -> (Graph<(), f64>, Vec<u32>) {
+        let mut graph: Graph<(), f64> = Graph::new(false, true);
+        let n: Vec<u32> = (0..6).map(|_| graph.add_node(())).collect();
+        let (c, d, e, f, g, h) = (n[0], n[1], n[2], n[3], n[4], n[5]);
+        for (from, to, cost) in [
+            (c, d, 3_f64),
+            (c, e, 2_f64),
+            (d, f, 4_f64),
+            (e, d, 1_f64),
+            (e, f, 2_f64),
+            (e, g, 3_f64),
+            (f, g, 2_f64),
+            (f, h, 1_f64),
+            (g, h, 2_f64),
+        ] {
+            graph
+                .add_directed_edge(from, to, true, false, cost)
+                .unwrap();
+        }
+        return (graph, n);
+    }
+
+    /// Every simple path by depth-first enumeration, as (cost, edges).
+    fn all_simple_paths(graph: &Graph<(), f64>, start: u32, goal: u32) -> Vec<(f64, Vec<u32>)> {
+        let mut paths: Vec<(f64, Vec<u32>)> = Vec::new();
+        let mut stack: Vec<(u32, Vec<u32>, Vec<u32>, f64)> =
+            vec![(start, vec![start], Vec::new(), 0_f64)];
+        while let Some((node_id, nodes, edges, cost)) = stack.pop() {
+            if node_id == goal {
+                paths.push((cost, edges));
+                continue;
+            }
+            for (edge_id, next_node_id) in graph.connected_edges(node_id) {
+                let edge = graph.get_edge_by_id(&edge_id).unwrap();
+                if !edge.can_move_from(node_id) || nodes.contains(&next_node_id) {
+                    continue;
+                }
+                let mut next_nodes = nodes.clone();
+                next_nodes.push(next_node_id);
+                let mut next_edges = edges.clone();
+                next_edges.push(edge_id);
+                stack.push((
+                    next_node_id,
+                    next_nodes,
+                    next_edges,
+                    cost + edge.edge_info(),
+                ));
+            }
+        }
+        return paths;
+    }
+
+    fn assert_valid_path(graph: &Graph<(), f64>, path: &GraphPath) {
+        assert_eq!(path.nodes.len(), path.edges.len() + 1);
+        let mut cost = 0_f64;
+        for (i, edge_id) in path.edges.iter().enumerate() {
+            let edge = graph.get_edge_by_id(edge_id).unwrap();
+            assert!(edge.can_move_from(path.nodes[i]));
+            assert!(edge.node1() == path.nodes[i + 1] || edge.node2() == path.nodes[i + 1]);
+            cost += edge.edge_info();
+        }
+        assert!((cost - path.cost).abs() < 1e-9);
+    }
+
+    #[test]
+    fn yen_returns_distinct_paths_in_cost_order() {
+        let (graph, n) = example_graph();
+        let (c, d, e, f, g, h) = (n[0], n[1], n[2], n[3], n[4], n[5]);
+
+        let paths = YenAlgorithm::k_shortest_paths(&graph, c, h, 3, PathDisjointness::None, |w| *w)
+            .unwrap();
+        let nodes: Vec<Vec<u32>> = paths.iter().map(|p| p.nodes.clone()).collect();
+        assert_eq!(
+            nodes,
+            vec![vec![c, e, f, h], vec![c, e, g, h], vec![c, d, f, h]]
+        );
+        assert_eq!(
+            paths.iter().map(|p| p.cost).collect::<Vec<f64>>(),
+            vec![5_f64, 7_f64, 8_f64]
+        );
+
+        // Asking for more paths than exist returns each simple path once, parallel edges
+        // making distinct paths.
+        let mut graph = graph;
+        graph.add_directed_edge(c, e, true, false, 2_f64).unwrap();
+        let paths =
+            YenAlgorithm::k_shortest_paths(&graph, c, h, 100, PathDisjointness::None, |w| *w)
+                .unwrap();
+        let mut expected = all_simple_paths(&graph, c, h);
+        expected.sort_by(|a, b| a.0.total_cmp(&b.0));
+        assert_eq!(paths.len(), expected.len());
+        let mut found: Vec<Vec<u32>> = paths.iter().map(|p| p.edges.clone()).collect();
+        found.sort();
+        found.dedup();
+        assert_eq!(found.len(), expected.len());
+        for (path, (cost, _)) in paths.iter().zip(expected.iter()) {
+            assert_valid_path(&graph, path);
+            assert!((path.cost - cost).abs() < 1e-9);
+        }
+        assert!(paths.windows(2).all(|p| p[0].cost <= p[1].cost));
+
+        assert_eq!(
+            YenAlgorithm::k_shortest_paths(&graph, h, c, 3, PathDisjointness::None, |w| *w),
+            Ok(Vec::new())
+        );
+    }
+
+    #[test]
+    fn disjoint_paths_avoid_the_shortest_path_trap() {
+        // The shortest path s-a-b-t blocks every other path, but s-a-t and s-b-t are
+        // disjoint.
+        let mut graph: Graph<(), f64> = Graph::new(false, false);
+        let s = graph.add_node(());
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        let t = graph.add_node(());
+        graph.add_edge(s, a, 1_f64).unwrap();
+        graph.add_edge(a, b, 1_f64).unwrap();
+        graph.add_edge(b, t, 1_f64).unwrap();
+        graph.add_edge(s, b, 2_f64).unwrap();
+        graph.add_edge(a, t, 2_f64).unwrap();
+
+        for disjointness in [
+            PathDisjointness::EdgeDisjoint,
+            PathDisjointness::NodeDisjoint,
+        ] {
+            let paths =
+                YenAlgorithm::k_shortest_paths(&graph, s, t, 3, disjointness, |w| *w).unwrap();
+            let nodes: Vec<Vec<u32>> = paths.iter().map(|p| p.nodes.clone()).collect();
+            assert_eq!(nodes, vec![vec![s, a, t], vec![s, b, t]]);
+            for path in paths.iter() {
+                assert_valid_path(&graph, path);
+            }
+        }
+    }
+
+    #[test]
+    fn edge_disjoint_paths_may_share_nodes() {
+        // Two ways into m and two ways out of it, plus a costly one-way bypass.
+        let mut graph: Graph<(), f64> = Graph::new(false, false);
+        let n: Vec<u32> = (0..7).map(|_| graph.add_node(())).collect();
+        let (s, a, b, m, c, d, t) = (n[0], n[1], n[2], n[3], n[4], n[5], n[6]);
+        for (from, to) in [
+            (s, a),
+            (a, m),
+            (s, b),
+            (b, m),
+            (m, c),
+            (c, t),
+            (m, d),
+            (d, t),
+        ] {
+            graph.add_edge(from, to, 1_f64).unwrap();
+        }
+        graph.add_directed_edge(t, s, true, false, 1_f64).unwrap();
+
+        let paths =
+            YenAlgorithm::k_shortest_paths(&graph, s, t, 5, PathDisjointness::EdgeDisjoint, |w| *w)
+                .unwrap();
+        assert_eq!(paths.len(), 2);
+        let mut edges: Vec<u32> = paths.iter().flat_map(|p| p.edges.clone()).collect();
+        edges.sort();
+        edges.dedup();
+        assert_eq!(edges.len(), 8);
+        assert!(paths.iter().all(|p| p.nodes.contains(&m)));
+
+        let paths =
+            YenAlgorithm::k_shortest_paths(&graph, s, t, 5, PathDisjointness::NodeDisjoint, |w| *w)
+                .unwrap();
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths[0].cost, 4_f64);
+
+        let paths =
+            YenAlgorithm::k_shortest_paths(&graph, s, s, 5, PathDisjointness::NodeDisjoint, |w| *w)
+                .unwrap();
+        assert_eq!(paths.len(), 1);
+        assert!(paths[0].edges.is_empty());
+        assert_eq!(
+            YenAlgorithm::k_shortest_paths(&graph, s, t, 2, PathDisjointness::EdgeDisjoint, |_| {
+                f64::NAN
+            }),
+            Err(GraphError::InvalidEdgeCost)
+        );
+    }
+}
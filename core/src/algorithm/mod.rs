@@ -0,0 +1,13 @@
+pub mod a_star;
+pub mod connectivity;
+pub mod d_star_lite;
+pub mod diff;
+pub mod dijkstra;
+pub mod flow;
+pub mod frozen_search;
+pub mod hierarchy;
+pub mod k_shortest_paths;
+pub mod routing;
+pub mod simplification;
+pub mod spanning_tree;
+pub mod traversal;
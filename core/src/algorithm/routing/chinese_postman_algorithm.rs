@@ -0,0 +1,709 @@
+use std::collections::{hash_map::Entry, HashMap, VecDeque};
+
+use crate::{
+    algorithm::dijkstra::{
+        dijkstra_algorithm::DijkstraAlgorithm, shortest_path_tree::ShortestPathTree,
+    },
+    graph::{graph_error::GraphError, graph_path::GraphPath, graph_view::GraphView},
+};
+
+/// Chinese Postman routes on `Graph`: the cheapest closed walk that traverses every edge at
+/// least once, for example the patrol of a security robot or the round of a cleaning robot
+/// over a topology map.
+///
+/// The returned `GraphPath` starts and ends at the chosen start node and lists edges in the
+/// order they are traversed; edges walked more than once appear more than once. Edge costs
+/// are computed from edge info and must be non-negative.
+pub struct ChinesePostmanAlgorithm;
+
+/// Odd node counts up to this are paired by exact dynamic programming; larger counts fall
+/// back to a greedy pairing improved by pairwise exchanges.
+const EXACT_MATCHING_LIMIT: usize = 20;
+
+/// One walk along an edge: (edge id, from node, to node).
+type Traversal = (u32, u32, u32);
+
+impl ChinesePostmanAlgorithm {
+    /// Find the route ignoring edge directions.
+    /// Returns None if the edges are not all connected to the start node.
+    pub fn undirected_route<GraphT, CostFuncT>(
+        graph: &GraphT,
+        start_node: u32,
+        cost_func: CostFuncT,
+    ) -> Result<Option<GraphPath>, GraphError>
+    where
+        GraphT: GraphView,
+        CostFuncT: Fn(&GraphT::EdgeInfo) -> f64,
+    {
+        if graph.get_node_by_id(&start_node).is_none() {
+            return Err(GraphError::NoSuchNode);
+        }
+
+        let mut edge_ids: Vec<u32> = graph.edge_ids().collect();
+        edge_ids.sort();
+        let mut traversals: Vec<Traversal> = Vec::with_capacity(edge_ids.len());
+        let mut degrees: HashMap<u32, usize> = HashMap::new();
+        for edge_id in edge_ids {
+            let edge = graph.get_edge_by_id(&edge_id).unwrap();
+            ChinesePostmanAlgorithm::validate_cost(cost_func(edge.edge_info()))?;
+            traversals.push((edge_id, edge.node1(), edge.node2()));
+            *degrees.entry(edge.node1()).or_insert(0) += 1;
+            *degrees.entry(edge.node2()).or_insert(0) += 1;
+        }
+
+        let mut odd_nodes: Vec<u32> = degrees
+            .iter()
+            .filter(|(_, d)| *d % 2 == 1)
+            .map(|(n, _)| *n)
+            .collect();
+        odd_nodes.sort();
+
+        // Walking the shortest path between paired odd nodes a second time makes every
+        // degree even.
+        let mut trees: Vec<ShortestPathTree> = Vec::with_capacity(odd_nodes.len());
+        for node_id in odd_nodes.iter() {
+            trees.push(DijkstraAlgorithm::undirected_shortest_path_tree(
+                graph, *node_id, &cost_func,
+            )?);
+        }
+        let distances: Vec<Vec<f64>> = trees
+            .iter()
+            .map(|t| {
+                return odd_nodes
+                    .iter()
+                    .map(|n| t.distance_to(*n).unwrap_or(f64::INFINITY))
+                    .collect();
+            })
+            .collect();
+
+        let pairs = match ChinesePostmanAlgorithm::pair_odd_nodes(&distances) {
+            Some(p) => p,
+            None => return Ok(None),
+        };
+        for (i, j) in pairs {
+            let path = trees[i].path_to(odd_nodes[j]).unwrap();
+            for (k, edge_id) in path.edges.iter().enumerate() {
+                traversals.push((*edge_id, path.nodes[k], path.nodes[k + 1]));
+            }
+        }
+
+        return Ok(ChinesePostmanAlgorithm::build_route(
+            graph,
+            &traversals,
+            start_node,
+            false,
+            &cost_func,
+        ));
+    }
+
+    /// Find the route honoring one-way edges; bidirectional edges can be walked either way.
+    /// Edges that allow no direction of movement are left out.
+    /// Returns None if the edges cannot all be walked in a closed route from the start node.
+    ///
+    /// The mixed problem is NP-hard, so bidirectional edges are first oriented to balance
+    /// the nodes as far as possible and the remaining imbalance is then fixed with the
+    /// cheapest extra walks. The route is optimal when every edge is one-way, and close to
+    /// optimal otherwise; use `undirected_route` when no edge is one-way.
+    pub fn mixed_route<GraphT, CostFuncT>(
+        graph: &GraphT,
+        start_node: u32,
+        cost_func: CostFuncT,
+    ) -> Result<Option<GraphPath>, GraphError>
+    where
+        GraphT: GraphView,
+        CostFuncT: Fn(&GraphT::EdgeInfo) -> f64,
+    {
+        if graph.get_node_by_id(&start_node).is_none() {
+            return Err(GraphError::NoSuchNode);
+        }
+
+        let mut edge_ids: Vec<u32> = graph.edge_ids().collect();
+        edge_ids.sort();
+        let mut traversals: Vec<Traversal> = Vec::with_capacity(edge_ids.len());
+        let mut two_way_traversals: Vec<Traversal> = Vec::new();
+        for edge_id in edge_ids {
+            let edge = graph.get_edge_by_id(&edge_id).unwrap();
+            ChinesePostmanAlgorithm::validate_cost(cost_func(edge.edge_info()))?;
+            let traversal = (edge_id, edge.node1(), edge.node2());
+            match (edge.can_move_forward(), edge.can_move_backward()) {
+                (true, true) if edge.node1() != edge.node2() => two_way_traversals.push(traversal),
+                (true, _) => traversals.push(traversal),
+                (false, true) => traversals.push((edge_id, edge.node2(), edge.node1())),
+                (false, false) => {}
+            }
+        }
+
+        // Balance is out-degree minus in-degree; bidirectional edges start out forward.
+        let mut balances: HashMap<u32, i64> = HashMap::new();
+        for (_, from, to) in traversals.iter().chain(two_way_traversals.iter()) {
+            *balances.entry(*from).or_insert(0) += 1;
+            *balances.entry(*to).or_insert(0) -= 1;
+        }
+        ChinesePostmanAlgorithm::orient_two_way_edges(&mut two_way_traversals, &mut balances);
+        traversals.extend(two_way_traversals);
+
+        // Nodes entered more often than left start extra walks, which end at nodes left more
+        // often than entered.
+        let mut surplus_nodes: Vec<(u32, i64)> = Vec::new();
+        let mut deficit_nodes: Vec<(u32, i64)> = Vec::new();
+        for (node_id, balance) in balances.iter() {
+            if *balance < 0 {
+                surplus_nodes.push((*node_id, -*balance));
+            } else if *balance > 0 {
+                deficit_nodes.push((*node_id, *balance));
+            }
+        }
+        surplus_nodes.sort();
+        deficit_nodes.sort();
+
+        let mut trees: Vec<ShortestPathTree> = Vec::with_capacity(surplus_nodes.len());
+        for (node_id, _) in surplus_nodes.iter() {
+            trees.push(DijkstraAlgorithm::shortest_path_tree(
+                graph, *node_id, &cost_func,
+            )?);
+        }
+        let distances: Vec<Vec<f64>> = trees
+            .iter()
+            .map(|t| {
+                return deficit_nodes
+                    .iter()
+                    .map(|(n, _)| t.distance_to(*n).unwrap_or(f64::INFINITY))
+                    .collect();
+            })
+            .collect();
+
+        let supplies: Vec<i64> = surplus_nodes.iter().map(|(_, s)| *s).collect();
+        let demands: Vec<i64> = deficit_nodes.iter().map(|(_, d)| *d).collect();
+        let transports = match ChinesePostmanAlgorithm::transport(&supplies, &demands, &distances) {
+            Some(t) => t,
+            None => return Ok(None),
+        };
+        for (i, j, count) in transports {
+            let path = trees[i].path_to(deficit_nodes[j].0).unwrap();
+            for _ in 0..count {
+                for (k, edge_id) in path.edges.iter().enumerate() {
+                    traversals.push((*edge_id, path.nodes[k], path.nodes[k + 1]));
+                }
+            }
+        }
+
+        return Ok(ChinesePostmanAlgorithm::build_route(
+            graph,
+            &traversals,
+            start_node,
+            true,
+            &cost_func,
+        ));
+    }
+
+    fn validate_cost(cost: f64) -> Result<(), GraphError> {
+        if cost.is_nan() || cost < 0_f64 {
+            return Err(GraphError::InvalidEdgeCost);
+        }
+        return Ok(());
+    }
+
+    /// Pair up odd nodes with minimum total distance.
+    /// Returns pairs of indices, or None if some node cannot be paired.
+    fn pair_odd_nodes(distances: &[Vec<f64>]) -> Option<Vec<(usize, usize)>> {
+        let count = distances.len();
+        if count == 0 {
+            return Some(Vec::new());
+        }
+
+        if count <= EXACT_MATCHING_LIMIT {
+            // costs[mask] is the cheapest pairing of the nodes in mask; the lowest unpaired
+            // node is always paired next, so every pairing is built exactly once.
+            let full_mask = (1_usize << count) - 1;
+            let mut costs: Vec<f64> = vec![f64::INFINITY; full_mask + 1];
+            let mut choices: Vec<(u8, u8)> = vec![(0, 0); full_mask + 1];
+            costs[0] = 0_f64;
+            for mask in 0..full_mask {
+                if costs[mask] == f64::INFINITY {
+                    continue;
+                }
+
+                let i = (!mask).trailing_zeros() as usize;
+                for (j, distance) in distances[i].iter().enumerate().skip(i + 1) {
+                    if mask & (1 << j) != 0 {
+                        continue;
+                    }
+
+                    let next_mask = mask | (1 << i) | (1 << j);
+                    let cost = costs[mask] + distance;
+                    if cost < costs[next_mask] {
+                        costs[next_mask] = cost;
+                        choices[next_mask] = (i as u8, j as u8);
+                    }
+                }
+            }
+
+            if costs[full_mask] == f64::INFINITY {
+                return None;
+            }
+
+            let mut pairs: Vec<(usize, usize)> = Vec::with_capacity(count / 2);
+            let mut mask = full_mask;
+            while mask != 0 {
+                let (i, j) = choices[mask];
+                pairs.push((i as usize, j as usize));
+                mask &= !((1 << i) | (1 << j));
+            }
+            return Some(pairs);
+        }
+
+        let mut candidates: Vec<(f64, usize, usize)> = Vec::new();
+        for (i, row) in distances.iter().enumerate() {
+            for (j, distance) in row.iter().enumerate().skip(i + 1) {
+                if *distance < f64::INFINITY {
+                    candidates.push((*distance, i, j));
+                }
+            }
+        }
+        candidates.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut is_paired: Vec<bool> = vec![false; count];
+        let mut pairs: Vec<(usize, usize)> = Vec::with_capacity(count / 2);
+        for (_, i, j) in candidates {
+            if !is_paired[i] && !is_paired[j] {
+                is_paired[i] = true;
+                is_paired[j] = true;
+                pairs.push((i, j));
+            }
+        }
+        if pairs.len() * 2 != count {
+            return None;
+        }
+
+        // Exchange partners between two pairs while that shortens the total distance.
+        let mut is_improved = true;
+        while is_improved {
+            is_improved = false;
+            for p in 0..pairs.len() {
+                for q in (p + 1)..pairs.len() {
+                    let (a, b) = pairs[p];
+                    let (c, d) = pairs[q];
+                    let current = distances[a][b] + distances[c][d];
+                    if distances[a][c] + distances[b][d] < current {
+                        pairs[p] = (a, c);
+                        pairs[q] = (b, d);
+                        is_improved = true;
+                    } else if distances[a][d] + distances[b][c] < current {
+                        pairs[p] = (a, d);
+                        pairs[q] = (b, c);
+                        is_improved = true;
+                    }
+                }
+            }
+        }
+
+        return Some(pairs);
+    }
+
+    /// Reverse chains of bidirectional edges leading from nodes left too often to nodes
+    /// entered too often; each reversal moves two units of balance without extra walking.
+    fn orient_two_way_edges(traversals: &mut [Traversal], balances: &mut HashMap<u32, i64>) {
+        loop {
+            let mut outgoing: HashMap<u32, Vec<usize>> = HashMap::new();
+            for (i, (_, from, _)) in traversals.iter().enumerate() {
+                outgoing.entry(*from).or_default().push(i);
+            }
+
+            let mut predecessors: HashMap<u32, Option<usize>> = HashMap::new();
+            let mut queue: VecDeque<u32> = VecDeque::new();
+            let mut sources: Vec<u32> = balances
+                .iter()
+                .filter(|(_, b)| **b >= 2)
+                .map(|(n, _)| *n)
+                .collect();
+            sources.sort();
+            for node_id in sources {
+                predecessors.insert(node_id, None);
+                queue.push_back(node_id);
+            }
+
+            let mut target: Option<u32> = None;
+            while let Some(node_id) = queue.pop_front() {
+                if balances[&node_id] <= -2 {
+                    target = Some(node_id);
+                    break;
+                }
+                for i in outgoing.get(&node_id).into_iter().flatten() {
+                    let next_node_id = traversals[*i].2;
+                    if let Entry::Vacant(entry) = predecessors.entry(next_node_id) {
+                        entry.insert(Some(*i));
+                        queue.push_back(next_node_id);
+                    }
+                }
+            }
+
+            let target = match target {
+                Some(t) => t,
+                None => return,
+            };
+
+            let mut current = target;
+            while let Some(Some(i)) = predecessors.get(&current) {
+                let (edge_id, from, to) = traversals[*i];
+                traversals[*i] = (edge_id, to, from);
+                current = from;
+            }
+            *balances.get_mut(&current).unwrap() -= 2;
+            *balances.get_mut(&target).unwrap() += 2;
+        }
+    }
+
+    /// Cheapest transport of supplies to demands over given distances, by successive
+    /// shortest paths. Returns (supply index, demand index, count) triples, or None if some
+    /// supply cannot reach any remaining demand.
+    fn transport(
+        supplies: &[i64],
+        demands: &[i64],
+        distances: &[Vec<f64>],
+    ) -> Option<Vec<(usize, usize, i64)>> {
+        // Network nodes: source, supplies, demands, sink. Arcs come in pairs; arc `i ^ 1`
+        // is the residual of arc `i`.
+        let source = 0;
+        let sink = supplies.len() + demands.len() + 1;
+        let mut arc_to: Vec<usize> = Vec::new();
+        let mut arc_capacity: Vec<i64> = Vec::new();
+        let mut arc_cost: Vec<f64> = Vec::new();
+        let mut adjacency: Vec<Vec<usize>> = vec![Vec::new(); sink + 1];
+        let mut add_arc_pair = |from: usize, to: usize, capacity: i64, cost: f64| {
+            adjacency[from].push(arc_to.len());
+            arc_to.push(to);
+            arc_capacity.push(capacity);
+            arc_cost.push(cost);
+            adjacency[to].push(arc_to.len());
+            arc_to.push(from);
+            arc_capacity.push(0);
+            arc_cost.push(-cost);
+            return arc_to.len() - 2;
+        };
+
+        let total: i64 = supplies.iter().sum();
+        for (i, supply) in supplies.iter().enumerate() {
+            add_arc_pair(source, 1 + i, *supply, 0_f64);
+        }
+        for (j, demand) in demands.iter().enumerate() {
+            add_arc_pair(1 + supplies.len() + j, sink, *demand, 0_f64);
+        }
+        let mut transport_arcs: Vec<(usize, usize, usize)> = Vec::new();
+        for (i, row) in distances.iter().enumerate() {
+            for (j, distance) in row.iter().enumerate() {
+                if *distance < f64::INFINITY {
+                    let arc = add_arc_pair(1 + i, 1 + supplies.len() + j, total, *distance);
+                    transport_arcs.push((i, j, arc));
+                }
+            }
+        }
+
+        let mut flow: i64 = 0;
+        while flow < total {
+            // Bellman-Ford, since residual arcs have negative costs.
+            let mut costs: Vec<f64> = vec![f64::INFINITY; sink + 1];
+            let mut via_arcs: Vec<Option<usize>> = vec![None; sink + 1];
+            costs[source] = 0_f64;
+            let mut is_updated = true;
+            while is_updated {
+                is_updated = false;
+                for node in 0..=sink {
+                    if costs[node] == f64::INFINITY {
+                        continue;
+                    }
+                    for arc in adjacency[node].iter() {
+                        let next = arc_to[*arc];
+                        let cost = costs[node] + arc_cost[*arc];
+                        if arc_capacity[*arc] > 0 && cost < costs[next] - 1e-12 {
+                            costs[next] = cost;
+                            via_arcs[next] = Some(*arc);
+                            is_updated = true;
+                        }
+                    }
+                }
+            }
+
+            via_arcs[sink]?;
+
+            let mut amount = total - flow;
+            let mut node = sink;
+            while let Some(arc) = via_arcs[node] {
+                amount = i64::min(amount, arc_capacity[arc]);
+                node = arc_to[arc ^ 1];
+            }
+            let mut node = sink;
+            while let Some(arc) = via_arcs[node] {
+                arc_capacity[arc] -= amount;
+                arc_capacity[arc ^ 1] += amount;
+                node = arc_to[arc ^ 1];
+            }
+            flow += amount;
+        }
+
+        return Some(
+            transport_arcs
+                .into_iter()
+                .map(|(i, j, arc)| (i, j, arc_capacity[arc ^ 1]))
+                .filter(|(_, _, count)| *count > 0)
+                .collect(),
+        );
+    }
+
+    /// Chain all traversals into a closed route from the start node with Hierholzer's
+    /// algorithm. Undirected traversals may be walked either way.
+    /// Returns None if some traversal is not connected to the start node.
+    fn build_route<GraphT, CostFuncT>(
+        graph: &GraphT,
+        traversals: &[Traversal],
+        start_node: u32,
+        is_directed: bool,
+        cost_func: &CostFuncT,
+    ) -> Option<GraphPath>
+    where
+        GraphT: GraphView,
+        CostFuncT: Fn(&GraphT::EdgeInfo) -> f64,
+    {
+        let mut adjacency: HashMap<u32, Vec<usize>> = HashMap::new();
+        for (i, (_, from, to)) in traversals.iter().enumerate() {
+            adjacency.entry(*from).or_default().push(i);
+            if !is_directed && from != to {
+                adjacency.entry(*to).or_default().push(i);
+            }
+        }
+
+        let mut is_used: Vec<bool> = vec![false; traversals.len()];
+        let mut next_positions: HashMap<u32, usize> = HashMap::new();
+        let mut stack: Vec<(u32, Option<u32>)> = vec![(start_node, None)];
+        let mut nodes: Vec<u32> = Vec::with_capacity(traversals.len() + 1);
+        let mut edges: Vec<u32> = Vec::with_capacity(traversals.len());
+
+        while let Some((node_id, via_edge)) = stack.last().copied() {
+            let candidates = adjacency.get(&node_id).map(|a| a.as_slice()).unwrap_or(&[]);
+            let position = next_positions.entry(node_id).or_insert(0);
+            while *position < candidates.len() && is_used[candidates[*position]] {
+                *position += 1;
+            }
+
+            if *position < candidates.len() {
+                let i = candidates[*position];
+                is_used[i] = true;
+                let (edge_id, from, to) = traversals[i];
+                let next_node_id = if from == node_id { to } else { from };
+                stack.push((next_node_id, Some(edge_id)));
+                continue;
+            }
+
+            stack.pop();
+            nodes.push(node_id);
+            if let Some(edge_id) = via_edge {
+                edges.push(edge_id);
+            }
+        }
+
+        if edges.len() != traversals.len() {
+            return None;
+        }
+
+        nodes.reverse();
+        edges.reverse();
+        let cost = edges
+            .iter()
+            .map(|e| cost_func(graph.get_edge_by_id(e).unwrap().edge_info()))
+            .sum();
+        return Some(GraphPath {
+            nodes: nodes,
+            edges: edges,
+            cost: cost,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::graph::Graph;
+
+    /// Check that given route is a closed walk from the start node over every edge of the
+    /// graph that allows movement, in allowed directions if `is_directed`.
+    fn assert_valid_route(
+        graph: &Graph<(), f64>,
+        route: &GraphPath,
+        start_node: u32,
+        is_directed: bool,
+    ) {
+        assert_eq!(route.start_node(), start_node);
+        assert_eq!(route.goal_node(), start_node);
+        assert_eq!(route.nodes.len(), route.edges.len() + 1);
+        let mut cost = 0_f64;
+        for (i, edge_id) in route.edges.iter().enumerate() {
+            let edge = graph.get_edge_by_id(edge_id).unwrap();
+            let (from, to) = (route.nodes[i], route.nodes[i + 1]);
+            assert!(
+                (edge.node1() == from && edge.node2() == to)
+                    || (edge.node2() == from && edge.node1() == to)
+            );
+            if is_directed {
+                assert!(edge.can_move_from(from));
+            }
+            cost += edge.edge_info();
+        }
+        assert!((cost - route.cost).abs() < 1e-9);
+        for edge in graph.get_edges().values() {
+            if edge.can_move_forward() || edge.can_move_backward() {
+                assert!(route.edges.contains(&edge.get_id()));
+            }
+        }
+    }
+
+    #[test]
+    fn eulerian_graph_needs_no_duplicate_edges() {
+        // Two squares sharing a node, with a self-loop.
+        let mut graph: Graph<(), f64> = Graph::new(true, false);
+        let n: Vec<u32> = (0..7).map(|_| graph.add_node(())).collect();
+        for (a, b) in [
+            (0, 1),
+            (1, 2),
+            (2, 3),
+            (3, 0),
+            (0, 4),
+            (4, 5),
+            (5, 6),
+            (6, 0),
+            (5, 5),
+        ] {
+            graph.add_edge(n[a], n[b], 1_f64 + a as f64).unwrap();
+        }
+
+        let route = ChinesePostmanAlgorithm::undirected_route(&graph, n[2], |w| *w)
+            .unwrap()
+            .unwrap();
+        assert_valid_route(&graph, &route, n[2], false);
+        assert_eq!(route.edges.len(), graph.get_edge_count());
+        let total: f64 = graph.get_edges().values().map(|e| *e.edge_info()).sum();
+        assert_eq!(route.cost, total);
+    }
+
+    #[test]
+    fn odd_nodes_are_paired_along_shortest_paths() {
+        // Complete graph on four nodes, all of odd degree. Pairing a-b and c-d is cheapest.
+        let mut graph: Graph<(), f64> = Graph::new(false, false);
+        let n: Vec<u32> = (0..4).map(|_| graph.add_node(())).collect();
+        let ab = graph.add_edge(n[0], n[1], 1_f64).unwrap();
+        let cd = graph.add_edge(n[2], n[3], 1_f64).unwrap();
+        graph.add_edge(n[0], n[2], 3_f64).unwrap();
+        graph.add_edge(n[1], n[3], 3_f64).unwrap();
+        graph.add_edge(n[0], n[3], 4_f64).unwrap();
+        graph.add_edge(n[1], n[2], 4_f64).unwrap();
+
+        let route = ChinesePostmanAlgorithm::undirected_route(&graph, n[0], |w| *w)
+            .unwrap()
+            .unwrap();
+        assert_valid_route(&graph, &route, n[0], false);
+        assert_eq!(route.cost, 18_f64);
+        for edge_id in [ab, cd] {
+            assert_eq!(route.edges.iter().filter(|e| **e == edge_id).count(), 2);
+        }
+
+        // Rectangle with a costly diagonal: the odd ends of the diagonal are joined by the
+        // cheaper way around.
+        let mut graph: Graph<(), f64> = Graph::new(false, false);
+        let n: Vec<u32> = (0..4).map(|_| graph.add_node(())).collect();
+        graph.add_edge(n[0], n[1], 1_f64).unwrap();
+        graph.add_edge(n[1], n[2], 1_f64).unwrap();
+        graph.add_edge(n[2], n[3], 2_f64).unwrap();
+        graph.add_edge(n[3], n[0], 2_f64).unwrap();
+        graph.add_edge(n[0], n[2], 3_f64).unwrap();
+
+        let route = ChinesePostmanAlgorithm::undirected_route(&graph, n[3], |w| *w)
+            .unwrap()
+            .unwrap();
+        assert_valid_route(&graph, &route, n[3], false);
+        assert_eq!(route.cost, 11_f64);
+    }
+
+    #[test]
+    fn one_way_route_is_optimal() {
+        // Directed triangle a-b-c with a one-way shortcut a-c, given against its direction
+        // flags. Closing the route takes one extra walk from c back to a.
+        let mut graph: Graph<(), f64> = Graph::new(false, true);
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        let c = graph.add_node(());
+        graph.add_directed_edge(a, b, true, false, 1_f64).unwrap();
+        graph.add_directed_edge(b, c, true, false, 1_f64).unwrap();
+        let ca = graph.add_directed_edge(c, a, true, false, 1_f64).unwrap();
+        graph.add_directed_edge(c, a, false, true, 1_f64).unwrap();
+
+        let route = ChinesePostmanAlgorithm::mixed_route(&graph, b, |w| *w)
+            .unwrap()
+            .unwrap();
+        assert_valid_route(&graph, &route, b, true);
+        assert_eq!(route.cost, 5_f64);
+        assert_eq!(route.edges.iter().filter(|e| **e == ca).count(), 2);
+
+        // Bidirectional edges are oriented to balance the one-way edges for free.
+        let mut graph: Graph<(), f64> = Graph::new(false, false);
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        let c = graph.add_node(());
+        graph.add_directed_edge(a, b, true, false, 1_f64).unwrap();
+        graph.add_edge(b, c, 1_f64).unwrap();
+        graph.add_edge(a, c, 1_f64).unwrap();
+        let route = ChinesePostmanAlgorithm::mixed_route(&graph, a, |w| *w)
+            .unwrap()
+            .unwrap();
+        assert_valid_route(&graph, &route, a, true);
+        assert_eq!(route.cost, 3_f64);
+    }
+
+    #[test]
+    fn unreachable_edges_give_no_route() {
+        let mut graph: Graph<(), f64> = Graph::new(false, false);
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        let c = graph.add_node(());
+        let d = graph.add_node(());
+        graph.add_edge(a, b, 1_f64).unwrap();
+        graph.add_edge(c, d, 1_f64).unwrap();
+        assert_eq!(
+            ChinesePostmanAlgorithm::undirected_route(&graph, a, |w| *w),
+            Ok(None)
+        );
+        assert_eq!(
+            ChinesePostmanAlgorithm::mixed_route(&graph, a, |w| *w),
+            Ok(None)
+        );
+
+        // A one-way dead end cannot be left again.
+        let mut graph: Graph<(), f64> = Graph::new(false, true);
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        let c = graph.add_node(());
+        graph.add_edge(a, b, 1_f64).unwrap();
+        graph.add_directed_edge(b, c, true, false, 1_f64).unwrap();
+        assert_eq!(
+            ChinesePostmanAlgorithm::mixed_route(&graph, a, |w| *w),
+            Ok(None)
+        );
+        assert!(ChinesePostmanAlgorithm::undirected_route(&graph, a, |w| *w)
+            .unwrap()
+            .is_some());
+
+        // Edges allowing no movement are left out of the mixed route.
+        let blocked = graph.add_directed_edge(c, a, false, false, 1_f64).unwrap();
+        graph.add_directed_edge(c, b, true, false, 1_f64).unwrap();
+        let route = ChinesePostmanAlgorithm::mixed_route(&graph, a, |w| *w)
+            .unwrap()
+            .unwrap();
+        assert!(!route.edges.contains(&blocked));
+
+        assert_eq!(
+            ChinesePostmanAlgorithm::undirected_route(&graph, 99, |w| *w),
+            Err(GraphError::NoSuchNode)
+        );
+        assert_eq!(
+            ChinesePostmanAlgorithm::mixed_route(&graph, a, |_| f64::NAN),
+            Err(GraphError::InvalidEdgeCost)
+        );
+    }
+}
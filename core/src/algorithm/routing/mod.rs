@@ -0,0 +1,3 @@
+pub mod chinese_postman_algorithm;
+pub mod stop_tour;
+pub mod traveling_salesman_algorithm;
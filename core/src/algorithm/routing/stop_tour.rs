@@ -0,0 +1,10 @@
+use crate::graph::graph_path::GraphPath;
+
+/// Route visiting a set of stops, found by `TravelingSalesmanAlgorithm`.
+#[derive(Clone, Debug)]
+pub struct StopTour {
+    /// Stops in visiting order, including the fixed start and end nodes if given.
+    pub stops: Vec<u32>,
+    /// Full node path through all stops, following shortest paths between them.
+    pub path: GraphPath,
+}
@@ -0,0 +1,582 @@
+use crate::{
+    algorithm::dijkstra::{
+        dijkstra_algorithm::DijkstraAlgorithm, shortest_path_tree::ShortestPathTree,
+    },
+    graph::{graph_error::GraphError, graph_path::GraphPath, graph_view::GraphView},
+};
+
+use super::stop_tour::StopTour;
+
+/// Order of visiting a set of stops on `Graph` with the least total cost, for example the
+/// stations of a pick-and-deliver mission.
+///
+/// Stops are connected by shortest paths, so edges are only traversed in the directions
+/// they allow and the cost from one stop to another may differ from the way back. The
+/// start and end nodes can be fixed independently; give the same node for both to get a
+/// round trip. A free start or end lets the route begin or finish at any stop.
+///
+/// All solvers return None if the stops cannot all be visited in one route. Stops listed
+/// more than once or equal to the start or end node are visited once.
+pub struct TravelingSalesmanAlgorithm;
+
+/// Largest number of stops, not counting fixed start and end nodes, `held_karp` accepts.
+pub const HELD_KARP_STOP_LIMIT: usize = 16;
+
+/// Improvements smaller than this are ignored to avoid cycling on rounding errors.
+const COST_EPSILON: f64 = 1e-9;
+
+/// Shortest path costs between stops. Stop `i` is row and column `i`; the start node is
+/// row `stops.len()` and the end node is column `stops.len() + 1`. A free start or end
+/// costs nothing to reach or leave.
+struct StopCosts {
+    stops: Vec<u32>,
+    start_node: Option<u32>,
+    end_node: Option<u32>,
+    trees: Vec<ShortestPathTree>,
+    costs: Vec<Vec<f64>>,
+}
+
+impl TravelingSalesmanAlgorithm {
+    /// Visit stops in the order given by the nearest neighbor heuristic. Fast, but routes
+    /// are often 20% longer than optimal.
+    pub fn nearest_neighbor<GraphT, CostFuncT>(
+        graph: &GraphT,
+        stops: &[u32],
+        start_node: Option<u32>,
+        end_node: Option<u32>,
+        cost_func: CostFuncT,
+    ) -> Result<Option<StopTour>, GraphError>
+    where
+        GraphT: GraphView,
+        CostFuncT: Fn(&GraphT::EdgeInfo) -> f64,
+    {
+        let stop_costs = TravelingSalesmanAlgorithm::build_costs(
+            graph, stops, start_node, end_node, &cost_func,
+        )?;
+        let order = TravelingSalesmanAlgorithm::nearest_neighbor_order(&stop_costs);
+        return Ok(TravelingSalesmanAlgorithm::build_tour(&stop_costs, &order));
+    }
+
+    /// Improve the nearest neighbor order with 2-opt segment reversals and Or-opt moves of
+    /// up to three consecutive stops, until no move shortens the route.
+    pub fn local_search<GraphT, CostFuncT>(
+        graph: &GraphT,
+        stops: &[u32],
+        start_node: Option<u32>,
+        end_node: Option<u32>,
+        cost_func: CostFuncT,
+    ) -> Result<Option<StopTour>, GraphError>
+    where
+        GraphT: GraphView,
+        CostFuncT: Fn(&GraphT::EdgeInfo) -> f64,
+    {
+        let stop_costs = TravelingSalesmanAlgorithm::build_costs(
+            graph, stops, start_node, end_node, &cost_func,
+        )?;
+        let mut order = TravelingSalesmanAlgorithm::nearest_neighbor_order(&stop_costs);
+        TravelingSalesmanAlgorithm::improve_order(&stop_costs, &mut order);
+        return Ok(TravelingSalesmanAlgorithm::build_tour(&stop_costs, &order));
+    }
+
+    /// Find the optimal order with Held-Karp dynamic programming, which takes time
+    /// exponential in the number of stops.
+    ///
+    /// Returns `TooManyStops` if there are more than `HELD_KARP_STOP_LIMIT` stops.
+    pub fn held_karp<GraphT, CostFuncT>(
+        graph: &GraphT,
+        stops: &[u32],
+        start_node: Option<u32>,
+        end_node: Option<u32>,
+        cost_func: CostFuncT,
+    ) -> Result<Option<StopTour>, GraphError>
+    where
+        GraphT: GraphView,
+        CostFuncT: Fn(&GraphT::EdgeInfo) -> f64,
+    {
+        let stop_costs = TravelingSalesmanAlgorithm::build_costs(
+            graph, stops, start_node, end_node, &cost_func,
+        )?;
+        if stop_costs.stops.len() > HELD_KARP_STOP_LIMIT {
+            return Err(GraphError::TooManyStops {
+                stop_count: stop_costs.stops.len(),
+                limit: HELD_KARP_STOP_LIMIT,
+            });
+        }
+        let order = TravelingSalesmanAlgorithm::held_karp_order(&stop_costs);
+        return Ok(TravelingSalesmanAlgorithm::build_tour(&stop_costs, &order));
+    }
+
+    /// Use `held_karp` for up to `HELD_KARP_STOP_LIMIT` stops, and `local_search` above.
+    pub fn solve<GraphT, CostFuncT>(
+        graph: &GraphT,
+        stops: &[u32],
+        start_node: Option<u32>,
+        end_node: Option<u32>,
+        cost_func: CostFuncT,
+    ) -> Result<Option<StopTour>, GraphError>
+    where
+        GraphT: GraphView,
+        CostFuncT: Fn(&GraphT::EdgeInfo) -> f64,
+    {
+        let stop_costs = TravelingSalesmanAlgorithm::build_costs(
+            graph, stops, start_node, end_node, &cost_func,
+        )?;
+        let order = if stop_costs.stops.len() <= HELD_KARP_STOP_LIMIT {
+            TravelingSalesmanAlgorithm::held_karp_order(&stop_costs)
+        } else {
+            let mut order = TravelingSalesmanAlgorithm::nearest_neighbor_order(&stop_costs);
+            TravelingSalesmanAlgorithm::improve_order(&stop_costs, &mut order);
+            order
+        };
+        return Ok(TravelingSalesmanAlgorithm::build_tour(&stop_costs, &order));
+    }
+
+    fn build_costs<GraphT, CostFuncT>(
+        graph: &GraphT,
+        stops: &[u32],
+        start_node: Option<u32>,
+        end_node: Option<u32>,
+        cost_func: &CostFuncT,
+    ) -> Result<StopCosts, GraphError>
+    where
+        GraphT: GraphView,
+        CostFuncT: Fn(&GraphT::EdgeInfo) -> f64,
+    {
+        let mut unique_stops: Vec<u32> = Vec::with_capacity(stops.len());
+        for node_id in stops.iter().chain(start_node.iter()).chain(end_node.iter()) {
+            if graph.get_node_by_id(node_id).is_none() {
+                return Err(GraphError::NoSuchNode);
+            }
+            if Some(*node_id) != start_node
+                && Some(*node_id) != end_node
+                && !unique_stops.contains(node_id)
+            {
+                unique_stops.push(*node_id);
+            }
+        }
+
+        let stop_count = unique_stops.len();
+        let mut trees: Vec<ShortestPathTree> = Vec::with_capacity(stop_count + 1);
+        for node_id in unique_stops.iter().chain(start_node.iter()) {
+            trees.push(DijkstraAlgorithm::shortest_path_tree(
+                graph, *node_id, cost_func,
+            )?);
+        }
+
+        let mut costs: Vec<Vec<f64>> = vec![vec![0_f64; stop_count + 2]; stop_count + 1];
+        for (i, tree) in trees.iter().enumerate() {
+            for (j, node_id) in unique_stops.iter().chain(end_node.iter()).enumerate() {
+                let column = if j < stop_count { j } else { stop_count + 1 };
+                costs[i][column] = tree.distance_to(*node_id).unwrap_or(f64::INFINITY);
+            }
+        }
+
+        return Ok(StopCosts {
+            stops: unique_stops,
+            start_node: start_node,
+            end_node: end_node,
+            trees: trees,
+            costs: costs,
+        });
+    }
+
+    fn order_cost(stop_costs: &StopCosts, order: &[usize]) -> f64 {
+        let start = stop_costs.stops.len();
+        let end = stop_costs.stops.len() + 1;
+        let costs = &stop_costs.costs;
+
+        let (first, last) = match (order.first(), order.last()) {
+            (Some(f), Some(l)) => (*f, *l),
+            _ => return costs[start][end],
+        };
+        let mut cost = costs[start][first] + costs[last][end];
+        for i in 1..order.len() {
+            cost += costs[order[i - 1]][order[i]];
+        }
+        return cost;
+    }
+
+    /// Without a fixed start node every stop is tried as the first one.
+    fn nearest_neighbor_order(stop_costs: &StopCosts) -> Vec<usize> {
+        let stop_count = stop_costs.stops.len();
+        let first_stops: Vec<Option<usize>> = if stop_costs.start_node.is_some() {
+            vec![None]
+        } else {
+            (0..stop_count).map(Some).collect()
+        };
+
+        let mut best_order: Vec<usize> = (0..stop_count).collect();
+        let mut best_cost = f64::INFINITY;
+        for first_stop in first_stops {
+            let mut is_visited: Vec<bool> = vec![false; stop_count];
+            let mut order: Vec<usize> = Vec::with_capacity(stop_count);
+            let mut current = match first_stop {
+                Some(s) => {
+                    is_visited[s] = true;
+                    order.push(s);
+                    s
+                }
+                None => stop_count,
+            };
+
+            while order.len() < stop_count {
+                let next = (0..stop_count)
+                    .filter(|s| !is_visited[*s])
+                    .min_by(|a, b| {
+                        let costs = &stop_costs.costs[current];
+                        return costs[*a].total_cmp(&costs[*b]);
+                    })
+                    .unwrap();
+                is_visited[next] = true;
+                order.push(next);
+                current = next;
+            }
+
+            let cost = TravelingSalesmanAlgorithm::order_cost(stop_costs, &order);
+            if cost < best_cost {
+                best_cost = cost;
+                best_order = order;
+            }
+        }
+
+        return best_order;
+    }
+
+    fn improve_order(stop_costs: &StopCosts, order: &mut Vec<usize>) {
+        let mut best_cost = TravelingSalesmanAlgorithm::order_cost(stop_costs, order);
+        let mut is_improved = true;
+        while is_improved {
+            is_improved = false;
+
+            // 2-opt: reverse a segment. Costs are recomputed in full since they may differ
+            // by direction.
+            for i in 0..order.len() {
+                for j in (i + 1)..order.len() {
+                    order[i..=j].reverse();
+                    let cost = TravelingSalesmanAlgorithm::order_cost(stop_costs, order);
+                    if cost < best_cost - COST_EPSILON {
+                        best_cost = cost;
+                        is_improved = true;
+                    } else {
+                        order[i..=j].reverse();
+                    }
+                }
+            }
+
+            // Or-opt: move a segment of up to three stops elsewhere.
+            for length in 1..=usize::min(3, order.len()) {
+                for i in 0..=(order.len() - length) {
+                    let mut rest = order.clone();
+                    let segment: Vec<usize> = rest.drain(i..(i + length)).collect();
+                    for k in 0..=rest.len() {
+                        if k == i {
+                            continue;
+                        }
+
+                        let mut candidate = rest.clone();
+                        candidate.splice(k..k, segment.iter().copied());
+                        let cost = TravelingSalesmanAlgorithm::order_cost(stop_costs, &candidate);
+                        if cost < best_cost - COST_EPSILON {
+                            best_cost = cost;
+                            *order = candidate;
+                            is_improved = true;
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn held_karp_order(stop_costs: &StopCosts) -> Vec<usize> {
+        let stop_count = stop_costs.stops.len();
+        if stop_count == 0 {
+            return Vec::new();
+        }
+
+        let start = stop_count;
+        let end = stop_count + 1;
+        let costs = &stop_costs.costs;
+
+        // route_costs[mask][last] is the cheapest route from the start through the stops of
+        // mask, ending at stop last.
+        let mask_count = 1_usize << stop_count;
+        let mut route_costs: Vec<Vec<f64>> = vec![vec![f64::INFINITY; stop_count]; mask_count];
+        let mut previous_stops: Vec<Vec<usize>> = vec![vec![usize::MAX; stop_count]; mask_count];
+        for s in 0..stop_count {
+            route_costs[1 << s][s] = costs[start][s];
+        }
+
+        for mask in 1..mask_count {
+            for last in 0..stop_count {
+                let cost = route_costs[mask][last];
+                if mask & (1 << last) == 0 || cost == f64::INFINITY {
+                    continue;
+                }
+
+                for next in 0..stop_count {
+                    if mask & (1 << next) != 0 {
+                        continue;
+                    }
+
+                    let next_mask = mask | (1 << next);
+                    let next_cost = cost + costs[last][next];
+                    if next_cost < route_costs[next_mask][next] {
+                        route_costs[next_mask][next] = next_cost;
+                        previous_stops[next_mask][next] = last;
+                    }
+                }
+            }
+        }
+
+        let full_mask = mask_count - 1;
+        let mut last = (0..stop_count)
+            .min_by(|a, b| {
+                let cost_a = route_costs[full_mask][*a] + costs[*a][end];
+                let cost_b = route_costs[full_mask][*b] + costs[*b][end];
+                return cost_a.total_cmp(&cost_b);
+            })
+            .unwrap();
+
+        let mut order: Vec<usize> = Vec::with_capacity(stop_count);
+        let mut mask = full_mask;
+        while last != usize::MAX {
+            order.push(last);
+            let previous = previous_stops[mask][last];
+            mask &= !(1 << last);
+            last = previous;
+        }
+
+        // An unreachable route leaves the chain incomplete; any order reports it.
+        if order.len() != stop_count {
+            return (0..stop_count).collect();
+        }
+
+        order.reverse();
+        return order;
+    }
+
+    /// Expand the stop order into the full node path.
+    /// Returns None if some leg of the route is unreachable.
+    fn build_tour(stop_costs: &StopCosts, order: &[usize]) -> Option<StopTour> {
+        let stop_count = stop_costs.stops.len();
+        let mut legs: Vec<usize> = Vec::with_capacity(stop_count + 2);
+        if stop_costs.start_node.is_some() {
+            legs.push(stop_count);
+        }
+        legs.extend(order.iter().copied());
+
+        let mut stops: Vec<u32> = legs
+            .iter()
+            .map(|i| {
+                return if *i < stop_count {
+                    stop_costs.stops[*i]
+                } else {
+                    stop_costs.start_node.unwrap()
+                };
+            })
+            .collect();
+        stops.extend(stop_costs.end_node.iter());
+
+        let first_node = *stops.first()?;
+        let mut path = GraphPath {
+            nodes: vec![first_node],
+            edges: Vec::new(),
+            cost: 0_f64,
+        };
+        for (k, leg) in legs.iter().enumerate() {
+            let next_node = match stops.get(k + 1) {
+                Some(n) => *n,
+                None => break,
+            };
+
+            let leg_path = stop_costs.trees[*leg].path_to(next_node)?;
+            path.nodes.extend(leg_path.nodes.iter().skip(1));
+            path.edges.extend(leg_path.edges.iter());
+            path.cost += leg_path.cost;
+        }
+
+        return Some(StopTour {
+            stops: stops,
+            path: path,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::graph::Graph;
+
+    /// Ring of nodes with pseudo-random costs and one-way chords, so that costs between
+    /// stops depend on direction.
+    fn sample_graph(node_count: usize) -> (Graph<(), f64>, Vec<u32>) {
+        let mut graph: Graph<(), f64> = Graph::new(false, true);
+        let nodes: Vec<u32> = (0..node_count).map(|_| graph.add_node(())).collect();
+        let mut state: u32 = 2024;
+        let mut next_cost = || {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            return 1_f64 + ((state >> 16) % 9) as f64;
+        };
+        for i in 0..node_count {
+            let next = nodes[(i + 1) % node_count];
+            graph.add_edge(nodes[i], next, next_cost()).unwrap();
+            let chord = nodes[(i * 7 + 3) % node_count];
+            if chord != nodes[i] {
+                graph
+                    .add_directed_edge(nodes[i], chord, true, false, next_cost())
+                    .unwrap();
+            }
+        }
+        return (graph, nodes);
+    }
+
+    /// Cheapest route over every order of given stops.
+    fn brute_force(
+        graph: &Graph<(), f64>,
+        stops: &[u32],
+        start_node: Option<u32>,
+        end_node: Option<u32>,
+    ) -> f64 {
+        let distance = |a: u32, b: u32| {
+            return DijkstraAlgorithm::shortest_path(graph, a, b, |w| *w)
+                .unwrap()
+                .map(|p| p.cost)
+                .unwrap_or(f64::INFINITY);
+        };
+        let mut best = f64::INFINITY;
+        let mut order: Vec<u32> = stops.to_vec();
+        let mut counters: Vec<usize> = vec![0; order.len()];
+        let mut i = 0;
+        // Heap's algorithm, checking the initial order first.
+        loop {
+            let mut cost = 0_f64;
+            let mut route: Vec<u32> = start_node.into_iter().collect();
+            route.extend(order.iter());
+            route.extend(end_node.iter());
+            for leg in route.windows(2) {
+                cost += distance(leg[0], leg[1]);
+            }
+            best = f64::min(best, cost);
+
+            while i < order.len() && counters[i] >= i {
+                counters[i] = 0;
+                i += 1;
+            }
+            if i >= order.len() {
+                return best;
+            }
+            if i % 2 == 0 {
+                order.swap(0, i);
+            } else {
+                order.swap(counters[i], i);
+            }
+            counters[i] += 1;
+            i = 0;
+        }
+    }
+
+    fn assert_tour(graph: &Graph<(), f64>, tour: &StopTour, stops: &[u32]) {
+        for stop in stops {
+            assert!(tour.stops.contains(stop));
+        }
+        let cost: f64 = tour
+            .path
+            .edges
+            .iter()
+            .map(|e| *graph.get_edge_by_id(e).unwrap().edge_info())
+            .sum();
+        assert!((cost - tour.path.cost).abs() < 1e-9);
+    }
+
+    #[test]
+    fn held_karp_matches_brute_force() {
+        let (graph, nodes) = sample_graph(20);
+        let stops: Vec<u32> = vec![
+            nodes[2], nodes[5], nodes[9], nodes[11], nodes[14], nodes[18],
+        ];
+        for (start_node, end_node) in [
+            (Some(nodes[0]), Some(nodes[0])),
+            (Some(nodes[0]), Some(nodes[7])),
+            (Some(nodes[3]), None),
+            (None, Some(nodes[3])),
+            (None, None),
+        ] {
+            let tour =
+                TravelingSalesmanAlgorithm::held_karp(&graph, &stops, start_node, end_node, |w| *w)
+                    .unwrap()
+                    .unwrap();
+            assert_tour(&graph, &tour, &stops);
+            let expected = brute_force(&graph, &stops, start_node, end_node);
+            assert!((tour.path.cost - expected).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn local_search_never_loses_to_nearest_neighbor() {
+        let (graph, nodes) = sample_graph(40);
+        let stops: Vec<u32> = nodes.iter().step_by(2).copied().collect();
+        for (start_node, end_node) in [
+            (Some(nodes[1]), Some(nodes[1])),
+            (Some(nodes[1]), Some(nodes[21])),
+            (None, None),
+        ] {
+            let nearest = TravelingSalesmanAlgorithm::nearest_neighbor(
+                &graph,
+                &stops,
+                start_node,
+                end_node,
+                |w| *w,
+            )
+            .unwrap()
+            .unwrap();
+            let improved = TravelingSalesmanAlgorithm::local_search(
+                &graph,
+                &stops,
+                start_node,
+                end_node,
+                |w| *w,
+            )
+            .unwrap()
+            .unwrap();
+            assert_tour(&graph, &nearest, &stops);
+            assert_tour(&graph, &improved, &stops);
+            assert!(improved.path.cost <= nearest.path.cost + 1e-9);
+            if let Some(start_node) = start_node {
+                assert_eq!(improved.stops[0], start_node);
+            }
+            if let Some(end_node) = end_node {
+                assert_eq!(*improved.stops.last().unwrap(), end_node);
+            }
+        }
+    }
+
+    #[test]
+    fn held_karp_reports_too_many_stops_and_unreachable_stops() {
+        let (mut graph, nodes) = sample_graph(40);
+        let stops: Vec<u32> = nodes[..HELD_KARP_STOP_LIMIT + 1].to_vec();
+        assert_eq!(
+            TravelingSalesmanAlgorithm::held_karp(&graph, &stops, None, None, |w| *w).err(),
+            Some(GraphError::TooManyStops {
+                stop_count: HELD_KARP_STOP_LIMIT + 1,
+                limit: HELD_KARP_STOP_LIMIT,
+            })
+        );
+        assert!(
+            TravelingSalesmanAlgorithm::solve(&graph, &stops, None, None, |w| *w)
+                .unwrap()
+                .is_some()
+        );
+
+        let isolated = graph.add_node(());
+        assert!(TravelingSalesmanAlgorithm::held_karp(
+            &graph,
+            &[nodes[0], isolated],
+            Some(nodes[1]),
+            None,
+            |w| *w
+        )
+        .unwrap()
+        .is_none());
+    }
+}
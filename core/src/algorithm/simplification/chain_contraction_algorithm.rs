@@ -0,0 +1,346 @@
+use std::collections::HashSet;
+
+use crate::graph::{edge::Edge, graph::Graph, graph_error::GraphError};
+
+use super::contracted_chain::ContractedChain;
+
+/// Contraction of chains of degree-2 nodes into single edges, such as the `Waypoint`
+/// nodes created by `TopologyVectorizer`.
+///
+/// A node is contracted if it has exactly two connected edges, none of them a self-loop,
+/// and the caller-supplied predicate accepts its info. Movement along the chain is kept
+/// as it is: a chain is only merged where its edges allow the same directions, so a
+/// node where a one-way corridor meets a two-way corridor is kept. Chains whose
+/// replacement edge would break the cyclic or duplicate edge policy of the graph are
+/// left untouched, as are closed rings consisting only of contractible nodes.
+pub struct ChainContractionAlgorithm;
+
+impl ChainContractionAlgorithm {
+    /// Contract every chain of contractible nodes.
+    ///
+    /// The merge function receives the edge infos of a chain in order from its first to
+    /// its last node, each with a flag telling if the edge runs against the chain, that is
+    /// from its node2 to its node1, and the infos of the removed nodes in the same order.
+    /// It returns the info of the replacement edge, which runs from the first to the last
+    /// node of the chain. Each chain is replaced as a whole: if adding the replacement
+    /// edge fails, the removed nodes and edges are restored before the error is returned.
+    pub fn contract_chains<TNodeInfo, TEdgeInfo, PredicateT, MergeFuncT>(
+        graph: &mut Graph<TNodeInfo, TEdgeInfo>,
+        predicate: PredicateT,
+        merge_func: MergeFuncT,
+    ) -> Result<Vec<ContractedChain>, GraphError>
+    where
+        PredicateT: Fn(&TNodeInfo) -> bool,
+        MergeFuncT: Fn(&[(&TEdgeInfo, bool)], &[&TNodeInfo]) -> TEdgeInfo,
+    {
+        let mut candidates: Vec<u32> = graph
+            .get_nodes()
+            .values()
+            .filter(|n| ChainContractionAlgorithm::is_contractible(graph, n.get_id(), &predicate))
+            .map(|n| n.get_id())
+            .collect();
+        candidates.sort();
+
+        let mut visited: HashSet<u32> = HashSet::new();
+        let mut contracted_chains: Vec<ContractedChain> = Vec::new();
+        for node_id in candidates {
+            if visited.contains(&node_id) {
+                continue;
+            }
+
+            let (nodes, edges) =
+                match ChainContractionAlgorithm::find_chain(graph, node_id, &predicate) {
+                    Some(chain) => chain,
+                    None => {
+                        // Closed ring of contractible nodes.
+                        visited.insert(node_id);
+                        continue;
+                    }
+                };
+            visited.extend(nodes.iter());
+
+            for (first, last) in
+                ChainContractionAlgorithm::split_by_direction(graph, &nodes, &edges)
+            {
+                if let Some(chain) = ChainContractionAlgorithm::contract(
+                    graph,
+                    &nodes[first..=last],
+                    &edges[first..last],
+                    &merge_func,
+                )? {
+                    contracted_chains.push(chain);
+                }
+            }
+        }
+
+        return Ok(contracted_chains);
+    }
+
+    fn is_contractible<TNodeInfo, TEdgeInfo, PredicateT>(
+        graph: &Graph<TNodeInfo, TEdgeInfo>,
+        node_id: u32,
+        predicate: &PredicateT,
+    ) -> bool
+    where
+        PredicateT: Fn(&TNodeInfo) -> bool,
+    {
+        let node = match graph.get_node_by_id(&node_id) {
+            Some(n) => n,
+            None => return false,
+        };
+        return node.degree() == 2
+            && !node.adjacent_nodes().contains_key(&node_id)
+            && predicate(node.node_info());
+    }
+
+    /// Walk from given contractible node to both ends of its chain.
+    /// Returns nodes and edges of the chain in order, or None for a closed ring.
+    fn find_chain<TNodeInfo, TEdgeInfo, PredicateT>(
+        graph: &Graph<TNodeInfo, TEdgeInfo>,
+        node_id: u32,
+        predicate: &PredicateT,
+    ) -> Option<(Vec<u32>, Vec<u32>)>
+    where
+        PredicateT: Fn(&TNodeInfo) -> bool,
+    {
+        let mut connections: Vec<(u32, u32)> = graph
+            .get_node_by_id(&node_id)
+            .unwrap()
+            .connected_edges()
+            .iter()
+            .map(|(e, n)| (*e, *n))
+            .collect();
+        connections.sort();
+
+        let mut halves: Vec<(Vec<u32>, Vec<u32>)> = Vec::new();
+        for (first_edge, first_node) in connections {
+            let mut nodes: Vec<u32> = vec![first_node];
+            let mut edges: Vec<u32> = vec![first_edge];
+            let mut current_node = first_node;
+            let mut current_edge = first_edge;
+
+            while ChainContractionAlgorithm::is_contractible(graph, current_node, predicate) {
+                if current_node == node_id {
+                    return None;
+                }
+
+                let (next_edge, next_node) = graph
+                    .get_node_by_id(&current_node)
+                    .unwrap()
+                    .connected_edges()
+                    .iter()
+                    .find(|(e, _)| **e != current_edge)
+                    .map(|(e, n)| (*e, *n))
+                    .unwrap();
+                nodes.push(next_node);
+                edges.push(next_edge);
+                current_node = next_node;
+                current_edge = next_edge;
+            }
+            halves.push((nodes, edges));
+        }
+
+        let (mut nodes, mut edges) = halves.remove(0);
+        nodes.reverse();
+        edges.reverse();
+        nodes.push(node_id);
+        let (other_nodes, other_edges) = halves.remove(0);
+        nodes.extend(other_nodes);
+        edges.extend(other_edges);
+        return Some((nodes, edges));
+    }
+
+    /// Split chain into ranges of node indices whose edges allow the same directions.
+    fn split_by_direction<TNodeInfo, TEdgeInfo>(
+        graph: &Graph<TNodeInfo, TEdgeInfo>,
+        nodes: &[u32],
+        edges: &[u32],
+    ) -> Vec<(usize, usize)> {
+        let directions: Vec<(bool, bool)> = edges
+            .iter()
+            .enumerate()
+            .map(|(i, edge_id)| {
+                let edge = graph.get_edge_by_id(edge_id).unwrap();
+                return (
+                    edge.can_move_from(nodes[i]),
+                    edge.can_move_from(nodes[i + 1]),
+                );
+            })
+            .collect();
+
+        let mut ranges: Vec<(usize, usize)> = Vec::new();
+        let mut first: usize = 0;
+        for i in 1..=directions.len() {
+            if i == directions.len() || directions[i] != directions[first] {
+                if i - first >= 2 {
+                    ranges.push((first, i));
+                }
+                first = i;
+            }
+        }
+
+        return ranges;
+    }
+
+    fn contract<TNodeInfo, TEdgeInfo, MergeFuncT>(
+        graph: &mut Graph<TNodeInfo, TEdgeInfo>,
+        nodes: &[u32],
+        edges: &[u32],
+        merge_func: &MergeFuncT,
+    ) -> Result<Option<ContractedChain>, GraphError>
+    where
+        MergeFuncT: Fn(&[(&TEdgeInfo, bool)], &[&TNodeInfo]) -> TEdgeInfo,
+    {
+        let first_node = nodes[0];
+        let last_node = nodes[nodes.len() - 1];
+        if first_node == last_node && !graph.allows_cyclic_edges() {
+            return Ok(None);
+        }
+        if !graph.allows_duplicate_edges()
+            && graph
+                .get_node_by_id(&first_node)
+                .unwrap()
+                .adjacent_nodes()
+                .contains_key(&last_node)
+        {
+            return Ok(None);
+        }
+
+        let first_edge = graph.get_edge_by_id(&edges[0]).unwrap();
+        let can_move_forward = first_edge.can_move_from(first_node);
+        let can_move_backward = first_edge.can_move_from(nodes[1]);
+
+        let edge_infos: Vec<(&TEdgeInfo, bool)> = edges
+            .iter()
+            .enumerate()
+            .map(|(i, edge_id)| {
+                let edge = graph.get_edge_by_id(edge_id).unwrap();
+                return (edge.edge_info(), edge.node1() != nodes[i]);
+            })
+            .collect();
+        let removed_nodes: Vec<u32> = nodes[1..nodes.len() - 1].to_vec();
+        let node_infos: Vec<&TNodeInfo> = removed_nodes
+            .iter()
+            .map(|n| graph.get_node_by_id(n).unwrap().node_info())
+            .collect();
+        let merged_info = merge_func(&edge_infos, &node_infos);
+
+        let mut taken_nodes: Vec<(u32, TNodeInfo, Vec<Edge<TEdgeInfo>>)> =
+            Vec::with_capacity(removed_nodes.len());
+        for node_id in removed_nodes.iter() {
+            let (node_info, node_edges) = graph.take_node(node_id)?;
+            taken_nodes.push((*node_id, node_info, node_edges));
+        }
+        let edge_id = match graph.add_directed_edge(
+            first_node,
+            last_node,
+            can_move_forward,
+            can_move_backward,
+            merged_info,
+        ) {
+            Ok(e) => e,
+            Err(error) => {
+                ChainContractionAlgorithm::restore(graph, taken_nodes);
+                return Err(error);
+            }
+        };
+
+        return Ok(Some(ContractedChain {
+            edge_id: edge_id,
+            end_nodes: (first_node, last_node),
+            removed_nodes: removed_nodes,
+            removed_edges: edges.to_vec(),
+        }));
+    }
+
+    /// Put back nodes taken from the graph, then the edges taken with them.
+    fn restore<TNodeInfo, TEdgeInfo>(
+        graph: &mut Graph<TNodeInfo, TEdgeInfo>,
+        taken_nodes: Vec<(u32, TNodeInfo, Vec<Edge<TEdgeInfo>>)>,
+    ) {
+        let mut taken_edges: Vec<Edge<TEdgeInfo>> = Vec::new();
+        for (node_id, node_info, node_edges) in taken_nodes {
+            graph.insert_node(node_id, node_info).unwrap();
+            taken_edges.extend(node_edges);
+        }
+        for edge in taken_edges {
+            graph.insert_edge(edge).unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn merge(edge_infos: &[(&String, bool)], node_infos: &[&String]) -> String {
+        let edges: Vec<String> = edge_infos
+            .iter()
+            .map(|(info, reversed)| match reversed {
+                true => format!("~{}", info),
+                false => info.to_string(),
+            })
+            .collect();
+        let nodes: Vec<&str> = node_infos.iter().map(|n| n.as_str()).collect();
+        return format!("{}|{}", edges.join(","), nodes.join(","));
+    }
+
+    #[test]
+    fn contracts_waypoint_chain_passing_edge_and_node_infos_in_order() {
+        let mut graph: Graph<String, String> = Graph::new(false, false);
+        let a = graph.add_node("a".to_string());
+        let w1 = graph.add_node("w1".to_string());
+        let w2 = graph.add_node("w2".to_string());
+        let b = graph.add_node("b".to_string());
+        let e1 = graph.add_edge(a, w1, "e1".to_string()).unwrap();
+        let e2 = graph.add_edge(w2, w1, "e2".to_string()).unwrap();
+        let e3 = graph.add_edge(w2, b, "e3".to_string()).unwrap();
+        for end_node in [a, a, b, b] {
+            let leaf = graph.add_node("leaf".to_string());
+            graph.add_edge(end_node, leaf, "leaf".to_string()).unwrap();
+        }
+
+        let chains =
+            ChainContractionAlgorithm::contract_chains(&mut graph, |n| n.starts_with('w'), merge)
+                .unwrap();
+
+        assert_eq!(chains.len(), 1);
+        assert_eq!(chains[0].end_nodes, (a, b));
+        assert_eq!(chains[0].removed_nodes, vec![w1, w2]);
+        assert_eq!(chains[0].removed_edges, vec![e1, e2, e3]);
+        let edge = graph.get_edge_by_id(&chains[0].edge_id).unwrap();
+        assert_eq!(edge.edge_info(), "e1,~e2,e3|w1,w2");
+        assert!(graph.get_node_by_id(&w1).is_none());
+        assert_eq!(graph.validate(), Ok(()));
+    }
+
+    #[test]
+    fn keeps_nodes_where_directions_change_and_duplicates_are_not_allowed() {
+        let mut graph: Graph<String, String> = Graph::new(false, false);
+        let a = graph.add_node("a".to_string());
+        let w1 = graph.add_node("w1".to_string());
+        let w2 = graph.add_node("w2".to_string());
+        let b = graph.add_node("b".to_string());
+        graph
+            .add_directed_edge(a, w1, true, false, "e1".to_string())
+            .unwrap();
+        graph.add_edge(w1, w2, "e2".to_string()).unwrap();
+        graph.add_edge(w2, b, "e3".to_string()).unwrap();
+        // Triangle whose contraction would duplicate the a-b edge.
+        let w3 = graph.add_node("w3".to_string());
+        graph.add_edge(a, w3, "e4".to_string()).unwrap();
+        graph.add_edge(w3, b, "e5".to_string()).unwrap();
+        graph.add_edge(a, b, "e6".to_string()).unwrap();
+
+        let chains =
+            ChainContractionAlgorithm::contract_chains(&mut graph, |n| n.starts_with('w'), merge)
+                .unwrap();
+
+        // Only w2 goes: w1 joins a one-way and a two-way edge.
+        assert_eq!(chains.len(), 1);
+        assert_eq!(chains[0].end_nodes, (w1, b));
+        assert_eq!(chains[0].removed_nodes, vec![w2]);
+        assert!(graph.get_node_by_id(&w3).is_some());
+        assert_eq!(graph.validate(), Ok(()));
+    }
+}
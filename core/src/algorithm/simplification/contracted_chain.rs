@@ -0,0 +1,12 @@
+/// Chain of degree-2 nodes replaced by a single edge.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContractedChain {
+    /// ID of the edge replacing the chain.
+    pub edge_id: u32,
+    /// Nodes at both ends of the chain, kept in the graph, in chain order.
+    pub end_nodes: (u32, u32),
+    /// Removed nodes in chain order.
+    pub removed_nodes: Vec<u32>,
+    /// Removed edges in chain order.
+    pub removed_edges: Vec<u32>,
+}
@@ -0,0 +1,140 @@
+use crate::graph::{graph::Graph, graph_error::GraphError};
+
+/// Subdivision of an edge into a chain of edges through new nodes, the inverse of
+/// `ChainContractionAlgorithm`.
+pub struct EdgeSubdivisionAlgorithm;
+
+impl EdgeSubdivisionAlgorithm {
+    /// Replace given edge with a chain running from its node1 through new nodes with given
+    /// infos, in order, to its node2. Every edge of the chain allows the same directions as
+    /// the replaced edge.
+    ///
+    /// The split function receives the info of the replaced edge and the infos of the new
+    /// nodes, and must return one edge info per edge of the chain, that is one more than
+    /// the number of new nodes, or `EdgeInfoCountMismatch` is returned and the graph is left
+    /// unchanged. If adding the chain fails, the new nodes and edges are removed and the
+    /// replaced edge is restored before the error is returned. Returns the IDs of the new
+    /// nodes and edges in chain order.
+    pub fn subdivide_edge<TNodeInfo, TEdgeInfo, SplitFuncT>(
+        graph: &mut Graph<TNodeInfo, TEdgeInfo>,
+        edge_id: u32,
+        node_infos: Vec<TNodeInfo>,
+        split_func: SplitFuncT,
+    ) -> Result<(Vec<u32>, Vec<u32>), GraphError>
+    where
+        SplitFuncT: Fn(&TEdgeInfo, &[TNodeInfo]) -> Vec<TEdgeInfo>,
+    {
+        let edge = match graph.get_edge_by_id(&edge_id) {
+            Some(e) => e,
+            None => return Err(GraphError::NoSuchEdge),
+        };
+        let node1 = edge.node1();
+        let node2 = edge.node2();
+        let can_move_forward = edge.can_move_forward();
+        let can_move_backward = edge.can_move_backward();
+
+        let edge_infos = split_func(edge.edge_info(), &node_infos);
+        if edge_infos.len() != node_infos.len() + 1 {
+            return Err(GraphError::EdgeInfoCountMismatch {
+                expected: node_infos.len() + 1,
+                actual: edge_infos.len(),
+            });
+        }
+
+        let removed_edge = graph.take_edge(&edge_id)?;
+
+        let mut chain_nodes: Vec<u32> = vec![node1];
+        for node_info in node_infos {
+            chain_nodes.push(graph.add_node(node_info));
+        }
+        chain_nodes.push(node2);
+
+        let mut new_edges: Vec<u32> = Vec::with_capacity(edge_infos.len());
+        let new_nodes = chain_nodes[1..chain_nodes.len() - 1].to_vec();
+        for (i, edge_info) in edge_infos.into_iter().enumerate() {
+            match graph.add_directed_edge(
+                chain_nodes[i],
+                chain_nodes[i + 1],
+                can_move_forward,
+                can_move_backward,
+                edge_info,
+            ) {
+                Ok(e) => new_edges.push(e),
+                Err(error) => {
+                    for new_edge_id in new_edges.iter() {
+                        graph.remove_edge(new_edge_id).unwrap();
+                    }
+                    for new_node_id in new_nodes.iter() {
+                        graph.remove_node(new_node_id).unwrap();
+                    }
+                    graph.insert_edge(removed_edge).unwrap();
+                    return Err(error);
+                }
+            }
+        }
+
+        return Ok((new_nodes, new_edges));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subdivides_edge_keeping_directions() {
+        let mut graph: Graph<u32, f64> = Graph::new(false, false);
+        let a = graph.add_node(0);
+        let b = graph.add_node(0);
+        let edge_id = graph.add_directed_edge(a, b, false, true, 6_f64).unwrap();
+
+        let (new_nodes, new_edges) =
+            EdgeSubdivisionAlgorithm::subdivide_edge(&mut graph, edge_id, vec![1, 2], |w, n| {
+                return vec![*w / (n.len() + 1) as f64; n.len() + 1];
+            })
+            .unwrap();
+
+        assert_eq!(new_nodes.len(), 2);
+        assert_eq!(new_edges.len(), 3);
+        assert!(graph.get_edge_by_id(&edge_id).is_none());
+        let chain = [a, new_nodes[0], new_nodes[1], b];
+        for (i, new_edge_id) in new_edges.iter().enumerate() {
+            let edge = graph.get_edge_by_id(new_edge_id).unwrap();
+            assert_eq!((edge.node1(), edge.node2()), (chain[i], chain[i + 1]));
+            assert_eq!(
+                (edge.can_move_forward(), edge.can_move_backward()),
+                (false, true)
+            );
+            assert_eq!(*edge.edge_info(), 2_f64);
+        }
+        assert_eq!(*graph.get_node_by_id(&new_nodes[1]).unwrap().node_info(), 2);
+        assert_eq!(graph.validate(), Ok(()));
+    }
+
+    #[test]
+    fn wrong_number_of_edge_infos_leaves_graph_unchanged() {
+        let mut graph: Graph<u32, f64> = Graph::new(false, false);
+        let a = graph.add_node(0);
+        let b = graph.add_node(0);
+        let edge_id = graph.add_edge(a, b, 6_f64).unwrap();
+
+        let result =
+            EdgeSubdivisionAlgorithm::subdivide_edge(&mut graph, edge_id, vec![1, 2], |w, _| {
+                return vec![*w];
+            });
+
+        assert_eq!(
+            result,
+            Err(GraphError::EdgeInfoCountMismatch {
+                expected: 3,
+                actual: 1
+            })
+        );
+        assert_eq!(graph.get_node_count(), 2);
+        assert!(graph.get_edge_by_id(&edge_id).is_some());
+        assert_eq!(
+            EdgeSubdivisionAlgorithm::subdivide_edge(&mut graph, 9, vec![], |w, _| vec![*w]),
+            Err(GraphError::NoSuchEdge)
+        );
+    }
+}
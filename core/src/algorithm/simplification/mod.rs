@@ -0,0 +1,3 @@
+pub mod chain_contraction_algorithm;
+pub mod contracted_chain;
+pub mod edge_subdivision_algorithm;
@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+
+/// Union-find structure over node or edge IDs, with path compression and union by rank.
+/// IDs are added on first use.
+#[derive(Clone, Debug, Default)]
+pub struct DisjointSet {
+    parents: HashMap<u32, u32>,
+    ranks: HashMap<u32, u32>,
+}
+
+impl DisjointSet {
+    pub fn new() -> Self {
+        return Self {
+            parents: HashMap::new(),
+            ranks: HashMap::new(),
+        };
+    }
+
+    /// Representative ID of the set containing given ID.
+    pub fn find(&mut self, id: u32) -> u32 {
+        let mut root = id;
+        while let Some(parent) = self.parents.get(&root) {
+            if *parent == root {
+                break;
+            }
+            root = *parent;
+        }
+
+        // Point every ID on the way directly to the root.
+        let mut current = id;
+        while current != root {
+            let next = match self.parents.insert(current, root) {
+                Some(p) => p,
+                None => break,
+            };
+            current = next;
+        }
+
+        return root;
+    }
+
+    /// Merge sets containing given IDs.
+    /// Returns false if they already belonged to the same set.
+    pub fn union(&mut self, id1: u32, id2: u32) -> bool {
+        let root1 = self.find(id1);
+        let root2 = self.find(id2);
+        if root1 == root2 {
+            return false;
+        }
+
+        let rank1 = *self.ranks.get(&root1).unwrap_or(&0);
+        let rank2 = *self.ranks.get(&root2).unwrap_or(&0);
+        if rank1 < rank2 {
+            self.parents.insert(root1, root2);
+        } else if rank1 > rank2 {
+            self.parents.insert(root2, root1);
+        } else {
+            self.parents.insert(root2, root1);
+            self.ranks.insert(root1, rank1 + 1);
+        }
+
+        return true;
+    }
+}
@@ -0,0 +1,249 @@
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use crate::{
+    algorithm::dijkstra::cost_queue_entry::CostQueueEntry,
+    graph::{graph::Graph, graph_error::GraphError, graph_view::GraphView},
+};
+
+use super::disjoint_set::DisjointSet;
+
+/// Minimum spanning tree of `Graph`.
+///
+/// Edge weights are computed from edge info by a caller-supplied function and may be
+/// negative, but not NaN. Edge directions are ignored and self-loops are never part of
+/// the tree. On a disconnected graph a minimum spanning forest is built, one tree per
+/// connected component. The result keeps every node and the chosen edges with their
+/// original IDs.
+pub struct MinimumSpanningTreeAlgorithm;
+
+impl MinimumSpanningTreeAlgorithm {
+    /// Build minimum spanning tree with Kruskal's algorithm.
+    pub fn kruskal<GraphT, WeightFuncT>(
+        graph: &GraphT,
+        weight_func: WeightFuncT,
+    ) -> Result<Graph<GraphT::NodeInfo, GraphT::EdgeInfo>, GraphError>
+    where
+        GraphT: GraphView,
+        GraphT::NodeInfo: Clone,
+        GraphT::EdgeInfo: Clone,
+        WeightFuncT: Fn(&GraphT::EdgeInfo) -> f64,
+    {
+        let edge_ids = MinimumSpanningTreeAlgorithm::kruskal_edge_ids(graph, weight_func)?;
+        return MinimumSpanningTreeAlgorithm::build_tree(graph, &edge_ids);
+    }
+
+    /// Build minimum spanning tree with Prim's algorithm.
+    pub fn prim<GraphT, WeightFuncT>(
+        graph: &GraphT,
+        weight_func: WeightFuncT,
+    ) -> Result<Graph<GraphT::NodeInfo, GraphT::EdgeInfo>, GraphError>
+    where
+        GraphT: GraphView,
+        GraphT::NodeInfo: Clone,
+        GraphT::EdgeInfo: Clone,
+        WeightFuncT: Fn(&GraphT::EdgeInfo) -> f64,
+    {
+        let edge_ids = MinimumSpanningTreeAlgorithm::prim_edge_ids(graph, weight_func)?;
+        return MinimumSpanningTreeAlgorithm::build_tree(graph, &edge_ids);
+    }
+
+    /// IDs of the edges of the minimum spanning tree found by Kruskal's algorithm.
+    /// Edges of equal weight are taken in ascending ID order.
+    pub fn kruskal_edge_ids<GraphT, WeightFuncT>(
+        graph: &GraphT,
+        weight_func: WeightFuncT,
+    ) -> Result<Vec<u32>, GraphError>
+    where
+        GraphT: GraphView,
+        WeightFuncT: Fn(&GraphT::EdgeInfo) -> f64,
+    {
+        let mut weighted_edges: Vec<(f64, u32)> = Vec::with_capacity(graph.get_edge_count());
+        for edge_id in graph.edge_ids() {
+            let edge = graph.get_edge_by_id(&edge_id).unwrap();
+            if edge.node1() == edge.node2() {
+                continue;
+            }
+
+            let weight = weight_func(edge.edge_info());
+            if weight.is_nan() {
+                return Err(GraphError::InvalidEdgeCost);
+            }
+            weighted_edges.push((weight, edge_id));
+        }
+        weighted_edges.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+
+        let mut components = DisjointSet::new();
+        let mut tree_edges: Vec<u32> = Vec::new();
+        for (_, edge_id) in weighted_edges {
+            let edge = graph.get_edge_by_id(&edge_id).unwrap();
+            if components.union(edge.node1(), edge.node2()) {
+                tree_edges.push(edge_id);
+            }
+        }
+
+        return Ok(tree_edges);
+    }
+
+    /// IDs of the edges of the minimum spanning tree found by Prim's algorithm.
+    /// Trees are grown from nodes in ascending ID order.
+    pub fn prim_edge_ids<GraphT, WeightFuncT>(
+        graph: &GraphT,
+        weight_func: WeightFuncT,
+    ) -> Result<Vec<u32>, GraphError>
+    where
+        GraphT: GraphView,
+        WeightFuncT: Fn(&GraphT::EdgeInfo) -> f64,
+    {
+        let mut root_nodes: Vec<u32> = graph.node_ids().collect();
+        root_nodes.sort();
+
+        let mut tree_edges: Vec<u32> = Vec::new();
+        let mut visited: HashSet<u32> = HashSet::new();
+        // Lightest known edge connecting each node outside the tree, as (weight, edge ID).
+        let mut best_edges: HashMap<u32, (f64, u32)> = HashMap::new();
+        let mut queue: BinaryHeap<CostQueueEntry> = BinaryHeap::new();
+
+        for root_node in root_nodes {
+            if visited.contains(&root_node) {
+                continue;
+            }
+
+            queue.push(CostQueueEntry {
+                cost: f64::NEG_INFINITY,
+                node_id: root_node,
+            });
+
+            while let Some(CostQueueEntry { cost, node_id }) = queue.pop() {
+                if visited.contains(&node_id) {
+                    continue;
+                }
+                if let Some((weight, edge_id)) = best_edges.get(&node_id) {
+                    // Skip stale entries superseded by a lighter edge.
+                    if *weight != cost {
+                        continue;
+                    }
+                    tree_edges.push(*edge_id);
+                }
+                visited.insert(node_id);
+
+                for (edge_id, next_node_id) in graph.connected_edges(node_id) {
+                    if visited.contains(&next_node_id) {
+                        continue;
+                    }
+
+                    let edge = graph.get_edge_by_id(&edge_id).unwrap();
+                    let weight = weight_func(edge.edge_info());
+                    if weight.is_nan() {
+                        return Err(GraphError::InvalidEdgeCost);
+                    }
+
+                    let is_improved = match best_edges.get(&next_node_id) {
+                        Some((w, e)) => weight < *w || (weight == *w && edge_id < *e),
+                        None => true,
+                    };
+                    if is_improved {
+                        best_edges.insert(next_node_id, (weight, edge_id));
+                        queue.push(CostQueueEntry {
+                            cost: weight,
+                            node_id: next_node_id,
+                        });
+                    }
+                }
+            }
+        }
+
+        return Ok(tree_edges);
+    }
+
+    fn build_tree<GraphT>(
+        graph: &GraphT,
+        edge_ids: &[u32],
+    ) -> Result<Graph<GraphT::NodeInfo, GraphT::EdgeInfo>, GraphError>
+    where
+        GraphT: GraphView,
+        GraphT::NodeInfo: Clone,
+        GraphT::EdgeInfo: Clone,
+    {
+        let mut node_ids: Vec<u32> = graph.node_ids().collect();
+        node_ids.sort();
+        return graph.clone_subgraph(&node_ids, edge_ids);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Five nodes with a self-loop and a parallel edge, plus a separate pair of nodes
+    /// joined by a negative edge. Returns the graph and the expected tree edges.
+    fn sample_graph() -> (Graph<(), f64>, Vec<u32>) {
+        let mut graph: Graph<(), f64> = Graph::new(true, true);
+        let n: Vec<u32> = (0..7).map(|_| graph.add_node(())).collect();
+        let mut add = |a: usize, b: usize, weight: f64| {
+            return graph.add_edge(n[a], n[b], weight).unwrap();
+        };
+        add(0, 1, 4_f64);
+        let ac = add(0, 2, 1_f64);
+        let bc = add(1, 2, 2_f64);
+        let bd = add(1, 3, 5_f64);
+        add(2, 3, 8_f64);
+        add(2, 4, 10_f64);
+        let de = add(3, 4, 2_f64);
+        add(3, 3, -5_f64);
+        add(0, 2, 3_f64);
+        let fg = add(5, 6, -1_f64);
+        return (graph, vec![ac, bc, bd, de, fg]);
+    }
+
+    fn sorted(mut edge_ids: Vec<u32>) -> Vec<u32> {
+        edge_ids.sort();
+        return edge_ids;
+    }
+
+    #[test]
+    fn kruskal_and_prim_find_the_same_minimum_forest() {
+        let (graph, expected) = sample_graph();
+        let kruskal = MinimumSpanningTreeAlgorithm::kruskal_edge_ids(&graph, |w| *w).unwrap();
+        let prim = MinimumSpanningTreeAlgorithm::prim_edge_ids(&graph, |w| *w).unwrap();
+        assert_eq!(sorted(kruskal), expected);
+        assert_eq!(sorted(prim), expected);
+
+        let tree = MinimumSpanningTreeAlgorithm::prim(&graph, |w| *w).unwrap();
+        assert_eq!(tree.get_node_count(), 7);
+        assert_eq!(tree.get_edge_count(), 5);
+        let weight: f64 = tree.get_edges().values().map(|e| *e.edge_info()).sum();
+        assert_eq!(weight, 9_f64);
+        assert_eq!(tree.get_node_id_alloc(), graph.get_node_id_alloc());
+        assert_eq!(tree.validate(), Ok(()));
+    }
+
+    #[test]
+    fn equal_weights_prefer_lower_edge_ids() {
+        // Square with a diagonal, all of the same weight.
+        let mut graph: Graph<(), f64> = Graph::new(false, false);
+        let n: Vec<u32> = (0..4).map(|_| graph.add_node(())).collect();
+        let e01 = graph.add_edge(n[0], n[1], 1_f64).unwrap();
+        let e02 = graph.add_edge(n[0], n[2], 1_f64).unwrap();
+        graph.add_edge(n[1], n[2], 1_f64).unwrap();
+        let e23 = graph.add_edge(n[2], n[3], 1_f64).unwrap();
+        graph.add_edge(n[3], n[0], 1_f64).unwrap();
+
+        let kruskal = MinimumSpanningTreeAlgorithm::kruskal_edge_ids(&graph, |w| *w).unwrap();
+        assert_eq!(kruskal, vec![e01, e02, e23]);
+        let prim = MinimumSpanningTreeAlgorithm::prim_edge_ids(&graph, |w| *w).unwrap();
+        assert_eq!(sorted(prim), vec![e01, e02, e23]);
+    }
+
+    #[test]
+    fn nan_weights_are_rejected() {
+        let (graph, _) = sample_graph();
+        assert_eq!(
+            MinimumSpanningTreeAlgorithm::kruskal_edge_ids(&graph, |_| f64::NAN).err(),
+            Some(GraphError::InvalidEdgeCost)
+        );
+        assert_eq!(
+            MinimumSpanningTreeAlgorithm::prim_edge_ids(&graph, |_| f64::NAN).err(),
+            Some(GraphError::InvalidEdgeCost)
+        );
+    }
+}
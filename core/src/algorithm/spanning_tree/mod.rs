@@ -0,0 +1,3 @@
+pub mod disjoint_set;
+pub mod minimum_spanning_tree_algorithm;
+pub mod steiner_tree_algorithm;
@@ -0,0 +1,204 @@
+use std::collections::HashSet;
+
+use crate::{
+    algorithm::dijkstra::{
+        dijkstra_algorithm::DijkstraAlgorithm, shortest_path_tree::ShortestPathTree,
+    },
+    graph::{graph::Graph, graph_error::GraphError, graph_view::GraphView},
+};
+
+use super::{
+    disjoint_set::DisjointSet, minimum_spanning_tree_algorithm::MinimumSpanningTreeAlgorithm,
+};
+
+/// Tree built from a graph of type `GraphT`, keeping its node and edge infos.
+pub type SteinerTree<GraphT> =
+    Graph<<GraphT as GraphView>::NodeInfo, <GraphT as GraphView>::EdgeInfo>;
+
+/// Approximate Steiner tree of `Graph`, connecting a chosen set of terminal nodes with
+/// small total edge weight.
+///
+/// Uses the algorithm of Kou, Markowsky and Berman, whose result weighs at most twice the
+/// optimum: terminals are joined along the minimum spanning tree of their shortest path
+/// distances, the union of those paths is reduced to its minimum spanning tree, and
+/// branches ending at non-terminal nodes are pruned.
+///
+/// Edge weights must be non-negative. Edge directions are ignored.
+pub struct SteinerTreeAlgorithm;
+
+impl SteinerTreeAlgorithm {
+    /// Build tree connecting given terminal nodes, keeping the IDs of its nodes and edges.
+    /// Returns None if the terminals are not all connected to each other.
+    pub fn approximate<GraphT, WeightFuncT>(
+        graph: &GraphT,
+        terminal_nodes: &[u32],
+        weight_func: WeightFuncT,
+    ) -> Result<Option<SteinerTree<GraphT>>, GraphError>
+    where
+        GraphT: GraphView,
+        GraphT::NodeInfo: Clone,
+        GraphT::EdgeInfo: Clone,
+        WeightFuncT: Fn(&GraphT::EdgeInfo) -> f64,
+    {
+        let mut terminals: Vec<u32> = Vec::new();
+        for node_id in terminal_nodes {
+            if graph.get_node_by_id(node_id).is_none() {
+                return Err(GraphError::NoSuchNode);
+            }
+            if !terminals.contains(node_id) {
+                terminals.push(*node_id);
+            }
+        }
+
+        // Shortest paths between every pair of terminals.
+        let mut trees: Vec<ShortestPathTree> = Vec::with_capacity(terminals.len());
+        for terminal in terminals.iter() {
+            trees.push(DijkstraAlgorithm::undirected_shortest_path_tree(
+                graph,
+                *terminal,
+                &weight_func,
+            )?);
+        }
+
+        let mut closure_edges: Vec<(f64, usize, usize)> = Vec::new();
+        for (i, tree) in trees.iter().enumerate() {
+            for (j, terminal) in terminals.iter().enumerate().skip(i + 1) {
+                match tree.distance_to(*terminal) {
+                    Some(distance) => closure_edges.push((distance, i, j)),
+                    None => return Ok(None),
+                }
+            }
+        }
+        closure_edges.sort_by(|a, b| a.0.total_cmp(&b.0).then((a.1, a.2).cmp(&(b.1, b.2))));
+
+        // Expand the minimum spanning tree of the terminal distances into graph edges.
+        let mut components = DisjointSet::new();
+        let mut path_nodes: HashSet<u32> = terminals.iter().copied().collect();
+        let mut path_edges: HashSet<u32> = HashSet::new();
+        for (_, i, j) in closure_edges {
+            if !components.union(i as u32, j as u32) {
+                continue;
+            }
+
+            let path = trees[i].path_to(terminals[j]).unwrap();
+            path_nodes.extend(path.nodes.iter());
+            path_edges.extend(path.edges.iter());
+        }
+
+        let mut node_ids: Vec<u32> = path_nodes.into_iter().collect();
+        node_ids.sort();
+        let mut edge_ids: Vec<u32> = path_edges.into_iter().collect();
+        edge_ids.sort();
+        let union_graph = graph.clone_subgraph(&node_ids, &edge_ids)?;
+        let mut tree = MinimumSpanningTreeAlgorithm::kruskal(&union_graph, &weight_func)?;
+
+        // Prune branches that do not lead to any terminal.
+        let terminal_set: HashSet<u32> = terminals.iter().copied().collect();
+        let mut leaves: Vec<u32> = tree
+            .get_nodes()
+            .values()
+            .filter(|n| n.degree() <= 1 && !terminal_set.contains(&n.get_id()))
+            .map(|n| n.get_id())
+            .collect();
+        while let Some(leaf) = leaves.pop() {
+            let neighbor = tree
+                .get_node_by_id(&leaf)
+                .unwrap()
+                .connected_edges()
+                .values()
+                .next()
+                .copied();
+            tree.remove_node(&leaf)?;
+
+            if let Some(neighbor) = neighbor {
+                let degree = tree.get_node_by_id(&neighbor).unwrap().degree();
+                if degree <= 1 && !terminal_set.contains(&neighbor) {
+                    leaves.push(neighbor);
+                }
+            }
+        }
+
+        return Ok(Some(tree));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree_weight(tree: &Graph<(), f64>) -> f64 {
+        return tree.get_edges().values().map(|e| *e.edge_info()).sum();
+    }
+
+    #[test]
+    fn connects_terminals_through_steiner_nodes_and_prunes_branches() {
+        // Terminals t1, t2 and t3 meet at x; y hangs off x and t1-t3 is a long detour.
+        let mut graph: Graph<(), f64> = Graph::new(false, false);
+        let t1 = graph.add_node(());
+        let x = graph.add_node(());
+        let t2 = graph.add_node(());
+        let t3 = graph.add_node(());
+        let y = graph.add_node(());
+        let t1x = graph.add_edge(t1, x, 1_f64).unwrap();
+        let xt2 = graph.add_edge(x, t2, 1_f64).unwrap();
+        let xt3 = graph.add_directed_edge(t3, x, true, false, 2_f64).unwrap();
+        graph.add_edge(x, y, 0.5_f64).unwrap();
+        graph.add_edge(t1, t3, 10_f64).unwrap();
+
+        let tree = SteinerTreeAlgorithm::approximate(&graph, &[t1, t2, t3, t2], |w| *w)
+            .unwrap()
+            .unwrap();
+        let mut node_ids: Vec<u32> = tree.node_ids().collect();
+        node_ids.sort();
+        assert_eq!(node_ids, vec![t1, x, t2, t3]);
+        let mut edge_ids: Vec<u32> = tree.edge_ids().collect();
+        edge_ids.sort();
+        assert_eq!(edge_ids, vec![t1x, xt2, xt3]);
+        assert_eq!(tree_weight(&tree), 4_f64);
+    }
+
+    #[test]
+    fn stays_within_twice_the_optimum() {
+        // Three terminals around a center at distance 1, and 1.9 from each other. The
+        // optimum of 3 uses the center; the terminal distance tree weighs 3.8.
+        let mut graph: Graph<(), f64> = Graph::new(false, false);
+        let center = graph.add_node(());
+        let terminals: Vec<u32> = (0..3).map(|_| graph.add_node(())).collect();
+        for (i, terminal) in terminals.iter().enumerate() {
+            graph.add_edge(center, *terminal, 1_f64).unwrap();
+            graph
+                .add_edge(*terminal, terminals[(i + 1) % 3], 1.9_f64)
+                .unwrap();
+        }
+
+        let tree = SteinerTreeAlgorithm::approximate(&graph, &terminals, |w| *w)
+            .unwrap()
+            .unwrap();
+        assert_eq!(tree.get_edge_count(), 2);
+        assert!((tree_weight(&tree) - 3.8_f64).abs() < 1e-9);
+        assert!(tree_weight(&tree) <= 2_f64 * 3_f64);
+    }
+
+    #[test]
+    fn single_disconnected_and_unknown_terminals() {
+        let mut graph: Graph<(), f64> = Graph::new(false, false);
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        let c = graph.add_node(());
+        graph.add_edge(a, b, 1_f64).unwrap();
+
+        let tree = SteinerTreeAlgorithm::approximate(&graph, &[b], |w| *w)
+            .unwrap()
+            .unwrap();
+        assert_eq!(tree.node_ids().collect::<Vec<u32>>(), vec![b]);
+        assert_eq!(tree.get_edge_count(), 0);
+
+        assert!(SteinerTreeAlgorithm::approximate(&graph, &[a, c], |w| *w)
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            SteinerTreeAlgorithm::approximate(&graph, &[a, 9], |w| *w).err(),
+            Some(GraphError::NoSuchNode)
+        );
+    }
+}
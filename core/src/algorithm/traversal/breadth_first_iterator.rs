@@ -0,0 +1,124 @@
+use std::collections::{HashSet, VecDeque};
+
+use crate::graph::{graph_error::GraphError, graph_view::GraphView};
+
+use super::traversal_direction::TraversalDirection;
+
+/// Lazy breadth-first traversal yielding node IDs reachable from a start node,
+/// together with their depth in edges. The start node is yielded first with depth 0.
+pub struct BreadthFirstIterator<'g, GraphT> {
+    graph: &'g GraphT,
+    direction: TraversalDirection,
+    queue: VecDeque<(u32, usize)>,
+    visited: HashSet<u32>,
+}
+
+impl<'g, GraphT: GraphView> BreadthFirstIterator<'g, GraphT> {
+    pub fn new(
+        graph: &'g GraphT,
+        start_node: u32,
+        direction: TraversalDirection,
+    ) -> Result<Self, GraphError> {
+        if graph.get_node_by_id(&start_node).is_none() {
+            return Err(GraphError::NoSuchNode);
+        }
+
+        return Ok(Self {
+            graph: graph,
+            direction: direction,
+            queue: VecDeque::from([(start_node, 0)]),
+            visited: HashSet::from([start_node]),
+        });
+    }
+}
+
+impl<'g, GraphT: GraphView> Iterator for BreadthFirstIterator<'g, GraphT> {
+    type Item = (u32, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (node_id, depth) = self.queue.pop_front()?;
+
+        for (edge_id, next_node_id) in self.graph.connected_edges(node_id) {
+            let edge = self.graph.get_edge_by_id(&edge_id).unwrap();
+            if !self.direction.can_traverse(edge, node_id) {
+                continue;
+            }
+            if self.visited.insert(next_node_id) {
+                self.queue.push_back((next_node_id, depth + 1));
+            }
+        }
+
+        return Some((node_id, depth));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::graph::Graph;
+
+    #[test]
+    fn yields_nodes_with_depth_in_given_direction() {
+        // a - b -> c - d, with e attached to a.
+        let mut graph: Graph<(), ()> = Graph::new(false, false);
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        let c = graph.add_node(());
+        let d = graph.add_node(());
+        let e = graph.add_node(());
+        graph.add_edge(a, b, ()).unwrap();
+        graph.add_directed_edge(b, c, true, false, ()).unwrap();
+        graph.add_edge(c, d, ()).unwrap();
+        graph.add_edge(e, a, ()).unwrap();
+
+        let mut forward: Vec<(u32, usize)> =
+            BreadthFirstIterator::new(&graph, a, TraversalDirection::Forward)
+                .unwrap()
+                .collect();
+        assert_eq!(forward[0], (a, 0));
+        forward.sort();
+        assert_eq!(forward, vec![(a, 0), (b, 1), (c, 2), (d, 3), (e, 1)]);
+
+        let mut backward: Vec<(u32, usize)> =
+            BreadthFirstIterator::new(&graph, c, TraversalDirection::Backward)
+                .unwrap()
+                .collect();
+        backward.sort();
+        assert_eq!(backward, vec![(a, 2), (b, 1), (c, 0), (d, 1), (e, 3)]);
+
+        let forward_from_d: Vec<u32> =
+            BreadthFirstIterator::new(&graph, d, TraversalDirection::Forward)
+                .unwrap()
+                .map(|(n, _)| n)
+                .collect();
+        assert_eq!(forward_from_d, vec![d, c]);
+
+        let undirected_count = BreadthFirstIterator::new(&graph, d, TraversalDirection::Undirected)
+            .unwrap()
+            .count();
+        assert_eq!(undirected_count, 5);
+    }
+
+    #[test]
+    fn depth_is_length_of_shortest_path() {
+        // Square a-b-c-d-a with a diagonal a-c.
+        let mut graph: Graph<(), ()> = Graph::new(false, false);
+        let nodes: Vec<u32> = (0..4).map(|_| graph.add_node(())).collect();
+        for i in 0..4 {
+            graph.add_edge(nodes[i], nodes[(i + 1) % 4], ()).unwrap();
+        }
+        graph.add_edge(nodes[0], nodes[2], ()).unwrap();
+
+        let depths: Vec<(u32, usize)> =
+            BreadthFirstIterator::new(&graph, nodes[0], TraversalDirection::Undirected)
+                .unwrap()
+                .collect();
+        assert_eq!(depths.len(), 4);
+        for (node_id, depth) in depths {
+            let expected = if node_id == nodes[0] { 0 } else { 1 };
+            assert_eq!(depth, expected);
+        }
+
+        assert!(BreadthFirstIterator::new(&graph, 99, TraversalDirection::Forward).is_err());
+    }
+}
@@ -0,0 +1,248 @@
+use std::collections::{hash_map::Entry, HashMap, HashSet};
+
+use crate::graph::{graph_error::GraphError, graph_view::GraphView};
+
+use super::{
+    breadth_first_iterator::BreadthFirstIterator, traversal_direction::TraversalDirection,
+};
+
+/// Connected component analysis on `Graph`.
+pub struct ConnectedComponentsAlgorithm;
+
+impl ConnectedComponentsAlgorithm {
+    /// Find components of nodes connected by edges of any direction.
+    pub fn undirected_components<GraphT: GraphView>(graph: &GraphT) -> Vec<Vec<u32>> {
+        let mut components: Vec<Vec<u32>> = Vec::new();
+        let mut visited: HashSet<u32> = HashSet::new();
+
+        for node_id in graph.node_ids() {
+            if visited.contains(&node_id) {
+                continue;
+            }
+
+            let component: Vec<u32> =
+                BreadthFirstIterator::new(graph, node_id, TraversalDirection::Undirected)
+                    .unwrap()
+                    .map(|(n, _)| n)
+                    .collect();
+            visited.extend(component.iter());
+            components.push(component);
+        }
+
+        return components;
+    }
+
+    /// Find strongly connected components with Tarjan's algorithm, following edges only
+    /// in the directions they allow. Every node of a component can reach every other node
+    /// of the same component.
+    pub fn strongly_connected_components<GraphT: GraphView>(graph: &GraphT) -> Vec<Vec<u32>> {
+        let mut components: Vec<Vec<u32>> = Vec::new();
+        let mut indices: HashMap<u32, usize> = HashMap::new();
+        let mut low_links: HashMap<u32, usize> = HashMap::new();
+        let mut on_stack: HashSet<u32> = HashSet::new();
+        let mut stack: Vec<u32> = Vec::new();
+        let mut next_index: usize = 0;
+
+        // Explicit call stack of (node, successors, next successor position), so that
+        // long corridors of the map do not overflow the thread stack.
+        let mut call_stack: Vec<(u32, Vec<u32>, usize)> = Vec::new();
+
+        for root_node in graph.node_ids() {
+            if indices.contains_key(&root_node) {
+                continue;
+            }
+
+            indices.insert(root_node, next_index);
+            low_links.insert(root_node, next_index);
+            next_index += 1;
+            stack.push(root_node);
+            on_stack.insert(root_node);
+            call_stack.push((
+                root_node,
+                ConnectedComponentsAlgorithm::successors(graph, root_node),
+                0,
+            ));
+
+            while let Some((node_id, successors, position)) = call_stack.last_mut() {
+                let node_id = *node_id;
+                if *position < successors.len() {
+                    let next_node_id = successors[*position];
+                    *position += 1;
+
+                    if let Entry::Vacant(entry) = indices.entry(next_node_id) {
+                        entry.insert(next_index);
+                        low_links.insert(next_node_id, next_index);
+                        next_index += 1;
+                        stack.push(next_node_id);
+                        on_stack.insert(next_node_id);
+                        call_stack.push((
+                            next_node_id,
+                            ConnectedComponentsAlgorithm::successors(graph, next_node_id),
+                            0,
+                        ));
+                    } else if on_stack.contains(&next_node_id) {
+                        let low_link = usize::min(low_links[&node_id], indices[&next_node_id]);
+                        low_links.insert(node_id, low_link);
+                    }
+                    continue;
+                }
+
+                call_stack.pop();
+                let node_low_link = low_links[&node_id];
+                if node_low_link == indices[&node_id] {
+                    let mut component: Vec<u32> = Vec::new();
+                    loop {
+                        let member = stack.pop().unwrap();
+                        on_stack.remove(&member);
+                        component.push(member);
+                        if member == node_id {
+                            break;
+                        }
+                    }
+                    components.push(component);
+                }
+
+                if let Some((parent_id, _, _)) = call_stack.last() {
+                    let low_link = usize::min(low_links[parent_id], node_low_link);
+                    low_links.insert(*parent_id, low_link);
+                }
+            }
+        }
+
+        return components;
+    }
+
+    /// Check if every given node can reach every other given node, honoring edge directions.
+    pub fn are_mutually_reachable<GraphT: GraphView>(
+        graph: &GraphT,
+        node_ids: &[u32],
+    ) -> Result<bool, GraphError> {
+        let first_node = match node_ids.first() {
+            Some(n) => *n,
+            None => return Ok(true),
+        };
+
+        // Every node must be reachable from the first node, and the first node from every node.
+        for direction in [TraversalDirection::Forward, TraversalDirection::Backward] {
+            let reached: HashSet<u32> = BreadthFirstIterator::new(graph, first_node, direction)?
+                .map(|(n, _)| n)
+                .collect();
+            for node_id in node_ids {
+                if graph.get_node_by_id(node_id).is_none() {
+                    return Err(GraphError::NoSuchNode);
+                }
+                if !reached.contains(node_id) {
+                    return Ok(false);
+                }
+            }
+        }
+
+        return Ok(true);
+    }
+
+    fn successors<GraphT: GraphView>(graph: &GraphT, node_id: u32) -> Vec<u32> {
+        return graph
+            .connected_edges(node_id)
+            .filter(|(edge_id, _)| {
+                let edge = graph.get_edge_by_id(edge_id).unwrap();
+                return edge.can_move_from(node_id);
+            })
+            .map(|(_, next_node_id)| next_node_id)
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::graph::Graph;
+
+    fn sorted(mut components: Vec<Vec<u32>>) -> Vec<Vec<u32>> {
+        for component in components.iter_mut() {
+            component.sort();
+        }
+        components.sort();
+        return components;
+    }
+
+    /// Two one-way rings a-b-c and d-e-f joined by the one-way edge c -> d, and the
+    /// isolated node g.
+    fn two_rings() -> (Graph<(), ()>, Vec<u32>) {
+        let mut graph: Graph<(), ()> = Graph::new(false, false);
+        let nodes: Vec<u32> = (0..7).map(|_| graph.add_node(())).collect();
+        for ring in [&nodes[0..3], &nodes[3..6]] {
+            for i in 0..3 {
+                graph
+                    .add_directed_edge(ring[i], ring[(i + 1) % 3], true, false, ())
+                    .unwrap();
+            }
+        }
+        graph
+            .add_directed_edge(nodes[2], nodes[3], true, false, ())
+            .unwrap();
+        return (graph, nodes);
+    }
+
+    #[test]
+    fn undirected_components_ignore_directions() {
+        let (graph, nodes) = two_rings();
+        assert_eq!(
+            sorted(ConnectedComponentsAlgorithm::undirected_components(&graph)),
+            vec![nodes[0..6].to_vec(), vec![nodes[6]]]
+        );
+    }
+
+    #[test]
+    fn strongly_connected_components_of_rings() {
+        let (graph, nodes) = two_rings();
+        let components = ConnectedComponentsAlgorithm::strongly_connected_components(&graph);
+        assert_eq!(
+            sorted(components.clone()),
+            vec![nodes[0..3].to_vec(), nodes[3..6].to_vec(), vec![nodes[6]]]
+        );
+
+        // Tarjan's algorithm completes the downstream ring before the upstream one.
+        let position = |node_id: u32| {
+            return components
+                .iter()
+                .position(|c| c.contains(&node_id))
+                .unwrap();
+        };
+        assert!(position(nodes[3]) < position(nodes[0]));
+
+        assert_eq!(
+            ConnectedComponentsAlgorithm::are_mutually_reachable(&graph, &nodes[0..3]),
+            Ok(true)
+        );
+        assert_eq!(
+            ConnectedComponentsAlgorithm::are_mutually_reachable(&graph, &nodes[2..4]),
+            Ok(false)
+        );
+        assert_eq!(
+            ConnectedComponentsAlgorithm::are_mutually_reachable(&graph, &[nodes[0], 99]),
+            Err(GraphError::NoSuchNode)
+        );
+    }
+
+    #[test]
+    fn strongly_connected_components_of_long_ring_do_not_overflow_stack() {
+        let node_count = 100_000;
+        let mut graph: Graph<(), ()> = Graph::new(false, false);
+        let nodes: Vec<u32> = (0..node_count).map(|_| graph.add_node(())).collect();
+        let mut closing_edge = 0;
+        for i in 0..node_count {
+            closing_edge = graph
+                .add_directed_edge(nodes[i], nodes[(i + 1) % node_count], true, false, ())
+                .unwrap();
+        }
+
+        let components = ConnectedComponentsAlgorithm::strongly_connected_components(&graph);
+        assert_eq!(components.len(), 1);
+        assert_eq!(components[0].len(), node_count);
+
+        // Opening the ring splits it into single nodes.
+        graph.remove_edge(&closing_edge).unwrap();
+        let components = ConnectedComponentsAlgorithm::strongly_connected_components(&graph);
+        assert_eq!(components.len(), node_count);
+    }
+}
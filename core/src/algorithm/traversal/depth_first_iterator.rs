@@ -0,0 +1,116 @@
+use std::collections::HashSet;
+
+use crate::graph::{graph_error::GraphError, graph_view::GraphView};
+
+use super::traversal_direction::TraversalDirection;
+
+/// Lazy depth-first traversal yielding node IDs reachable from a start node in pre-order.
+/// The start node is yielded first.
+pub struct DepthFirstIterator<'g, GraphT> {
+    graph: &'g GraphT,
+    direction: TraversalDirection,
+    stack: Vec<u32>,
+    visited: HashSet<u32>,
+}
+
+impl<'g, GraphT: GraphView> DepthFirstIterator<'g, GraphT> {
+    pub fn new(
+        graph: &'g GraphT,
+        start_node: u32,
+        direction: TraversalDirection,
+    ) -> Result<Self, GraphError> {
+        if graph.get_node_by_id(&start_node).is_none() {
+            return Err(GraphError::NoSuchNode);
+        }
+
+        return Ok(Self {
+            graph: graph,
+            direction: direction,
+            stack: vec![start_node],
+            visited: HashSet::new(),
+        });
+    }
+}
+
+impl<'g, GraphT: GraphView> Iterator for DepthFirstIterator<'g, GraphT> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Nodes may be pushed more than once before they are visited; skip the repeats.
+        let node_id = loop {
+            let candidate = self.stack.pop()?;
+            if self.visited.insert(candidate) {
+                break candidate;
+            }
+        };
+
+        for (edge_id, next_node_id) in self.graph.connected_edges(node_id) {
+            let edge = self.graph.get_edge_by_id(&edge_id).unwrap();
+            if self.direction.can_traverse(edge, node_id) && !self.visited.contains(&next_node_id) {
+                self.stack.push(next_node_id);
+            }
+        }
+
+        return Some(node_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::graph::Graph;
+
+    #[test]
+    fn follows_each_branch_to_its_end_before_the_next() {
+        // Two branches a-b-c and a-d-e from a.
+        let mut graph: Graph<(), ()> = Graph::new(false, false);
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        let c = graph.add_node(());
+        let d = graph.add_node(());
+        let e = graph.add_node(());
+        graph.add_edge(a, b, ()).unwrap();
+        graph.add_edge(b, c, ()).unwrap();
+        graph.add_edge(a, d, ()).unwrap();
+        graph.add_edge(d, e, ()).unwrap();
+
+        let order: Vec<u32> = DepthFirstIterator::new(&graph, a, TraversalDirection::Forward)
+            .unwrap()
+            .collect();
+        assert_eq!(order.len(), 5);
+        assert_eq!(order[0], a);
+        assert!(order[1..] == [b, c, d, e] || order[1..] == [d, e, b, c]);
+    }
+
+    #[test]
+    fn visits_nodes_of_cycles_once_and_honors_directions() {
+        // One-way ring a -> b -> c -> a, with d reachable only against the ring.
+        let mut graph: Graph<(), ()> = Graph::new(false, false);
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        let c = graph.add_node(());
+        let d = graph.add_node(());
+        graph.add_directed_edge(a, b, true, false, ()).unwrap();
+        graph.add_directed_edge(b, c, true, false, ()).unwrap();
+        graph.add_directed_edge(c, a, true, false, ()).unwrap();
+        graph.add_directed_edge(d, b, true, false, ()).unwrap();
+
+        let forward: Vec<u32> = DepthFirstIterator::new(&graph, a, TraversalDirection::Forward)
+            .unwrap()
+            .collect();
+        assert_eq!(forward, vec![a, b, c]);
+
+        let backward: Vec<u32> = DepthFirstIterator::new(&graph, b, TraversalDirection::Backward)
+            .unwrap()
+            .collect();
+        assert_eq!(backward.len(), 4);
+        assert_eq!(backward[0], b);
+
+        let mut undirected: Vec<u32> =
+            DepthFirstIterator::new(&graph, d, TraversalDirection::Undirected)
+                .unwrap()
+                .collect();
+        undirected.sort();
+        assert_eq!(undirected, vec![a, b, c, d]);
+    }
+}
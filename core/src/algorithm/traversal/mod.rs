@@ -0,0 +1,4 @@
+pub mod breadth_first_iterator;
+pub mod connected_components_algorithm;
+pub mod depth_first_iterator;
+pub mod traversal_direction;
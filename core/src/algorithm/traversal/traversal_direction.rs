@@ -0,0 +1,28 @@
+use crate::graph::edge::Edge;
+
+/// How edge direction flags are treated while traversing a graph.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TraversalDirection {
+    /// Follow edges only in the directions they allow.
+    Forward,
+    /// Follow edges only against the directions they allow, finding nodes that can reach the start.
+    Backward,
+    /// Ignore direction flags.
+    Undirected,
+}
+
+impl TraversalDirection {
+    /// Check if given edge can be traversed from given end node.
+    pub fn can_traverse<TEdgeInfo>(&self, edge: &Edge<TEdgeInfo>, from_node: u32) -> bool {
+        let to_node = match from_node == edge.node1() {
+            true => edge.node2(),
+            false => edge.node1(),
+        };
+
+        return match self {
+            TraversalDirection::Forward => edge.can_move_from(from_node),
+            TraversalDirection::Backward => edge.can_move_from(to_node),
+            TraversalDirection::Undirected => true,
+        };
+    }
+}
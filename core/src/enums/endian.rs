@@ -1,5 +1,18 @@
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Endian {
     LittleEndian,
     BigEndian,
 }
+
+#[allow(non_upper_case_globals)]
+impl Endian {
+    /// Byte order of the target platform.
+    #[cfg(target_endian = "little")]
+    pub const Native: Endian = Endian::LittleEndian;
+    /// Byte order of the target platform.
+    #[cfg(target_endian = "big")]
+    pub const Native: Endian = Endian::BigEndian;
+
+    /// Network byte order, which is big endian.
+    pub const Network: Endian = Endian::BigEndian;
+}
@@ -47,6 +47,18 @@ impl<TEdgeInfo> Edge<TEdgeInfo> {
         return self.can_move_backward;
     }
 
+    /// Check if the edge can be traversed starting from given end node.
+    /// Returns false if the node is not an end of the edge.
+    pub fn can_move_from(&self, node_id: u32) -> bool {
+        if node_id == self.node1 && self.can_move_forward {
+            return true;
+        }
+        if node_id == self.node2 && self.can_move_backward {
+            return true;
+        }
+        return false;
+    }
+
     pub fn edge_info(&self) -> &TEdgeInfo {
         return &self.edge_info;
     }
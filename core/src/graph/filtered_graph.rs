@@ -0,0 +1,205 @@
+use super::{edge::Edge, graph_change::GraphChange, graph_view::GraphView, node::Node};
+
+/// View of a graph showing only the nodes and edges accepted by given predicates, such as
+/// "every edge that is not blocked" or "only nodes inside zone A". Nothing is copied;
+/// the predicates are evaluated on every access.
+///
+/// An edge is visible if the edge predicate accepts it and both of its end nodes are
+/// visible. Views can be stacked, since a view of any `GraphView` is itself a `GraphView`.
+pub struct FilteredGraph<'g, GraphT, NodePredicateT, EdgePredicateT> {
+    graph: &'g GraphT,
+    node_predicate: NodePredicateT,
+    edge_predicate: EdgePredicateT,
+}
+
+impl<'g, GraphT, NodePredicateT, EdgePredicateT>
+    FilteredGraph<'g, GraphT, NodePredicateT, EdgePredicateT>
+where
+    GraphT: GraphView,
+    NodePredicateT: Fn(&Node<GraphT::NodeInfo>) -> bool,
+    EdgePredicateT: Fn(&Edge<GraphT::EdgeInfo>) -> bool,
+{
+    pub fn new(
+        graph: &'g GraphT,
+        node_predicate: NodePredicateT,
+        edge_predicate: EdgePredicateT,
+    ) -> Self {
+        return Self {
+            graph: graph,
+            node_predicate: node_predicate,
+            edge_predicate: edge_predicate,
+        };
+    }
+
+    /// Underlying graph, including hidden nodes and edges.
+    pub fn graph(&self) -> &'g GraphT {
+        return self.graph;
+    }
+}
+
+impl<'g, GraphT, NodePredicateT, EdgePredicateT> GraphView
+    for FilteredGraph<'g, GraphT, NodePredicateT, EdgePredicateT>
+where
+    GraphT: GraphView,
+    NodePredicateT: Fn(&Node<GraphT::NodeInfo>) -> bool,
+    EdgePredicateT: Fn(&Edge<GraphT::EdgeInfo>) -> bool,
+{
+    type NodeInfo = GraphT::NodeInfo;
+    type EdgeInfo = GraphT::EdgeInfo;
+
+    fn get_node_by_id(&self, node_id: &u32) -> Option<&Node<GraphT::NodeInfo>> {
+        return self
+            .graph
+            .get_node_by_id(node_id)
+            .filter(|n| (self.node_predicate)(n));
+    }
+
+    fn get_edge_by_id(&self, edge_id: &u32) -> Option<&Edge<GraphT::EdgeInfo>> {
+        let edge = self.graph.get_edge_by_id(edge_id)?;
+        if !(self.edge_predicate)(edge)
+            || self.get_node_by_id(&edge.node1()).is_none()
+            || self.get_node_by_id(&edge.node2()).is_none()
+        {
+            return None;
+        }
+
+        return Some(edge);
+    }
+
+    fn node_ids(&self) -> Box<dyn Iterator<Item = u32> + '_> {
+        return Box::new(
+            self.graph
+                .node_ids()
+                .filter(|n| self.get_node_by_id(n).is_some()),
+        );
+    }
+
+    fn edge_ids(&self) -> Box<dyn Iterator<Item = u32> + '_> {
+        return Box::new(
+            self.graph
+                .edge_ids()
+                .filter(|e| self.get_edge_by_id(e).is_some()),
+        );
+    }
+
+    fn connected_edges(&self, node_id: u32) -> Box<dyn Iterator<Item = (u32, u32)> + '_> {
+        if self.get_node_by_id(&node_id).is_none() {
+            return Box::new(std::iter::empty());
+        }
+
+        return Box::new(
+            self.graph
+                .connected_edges(node_id)
+                .filter(|(e, _)| self.get_edge_by_id(e).is_some()),
+        );
+    }
+
+    fn allows_cyclic_edges(&self) -> bool {
+        return self.graph.allows_cyclic_edges();
+    }
+
+    fn allows_duplicate_edges(&self) -> bool {
+        return self.graph.allows_duplicate_edges();
+    }
+
+    fn get_node_id_alloc(&self) -> u32 {
+        return self.graph.get_node_id_alloc();
+    }
+
+    fn get_edge_id_alloc(&self) -> u32 {
+        return self.graph.get_edge_id_alloc();
+    }
+
+    fn get_revision(&self) -> u64 {
+        return self.graph.get_revision();
+    }
+
+    /// Changes of the underlying graph only. Nodes and edges hidden or shown because the
+    /// predicates now judge them differently are not reported.
+    fn changes_since(&self, revision: u64) -> Option<&[GraphChange]> {
+        return self.graph.changes_since(revision);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::dijkstra::dijkstra_algorithm::DijkstraAlgorithm;
+    use crate::algorithm::traversal::{
+        breadth_first_iterator::BreadthFirstIterator, traversal_direction::TraversalDirection,
+    };
+    use crate::graph::{graph::Graph, graph_error::GraphError};
+
+    /// Diamond a-b-d / a-c-d with a shortcut through zone node z, which is the only node
+    /// with info `true`. Edge infos are (cost, blocked).
+    fn sample_graph() -> (Graph<bool, (f64, bool)>, [u32; 5]) {
+        let mut graph: Graph<bool, (f64, bool)> = Graph::new(false, false);
+        let a = graph.add_node(false);
+        let b = graph.add_node(false);
+        let c = graph.add_node(false);
+        let d = graph.add_node(false);
+        let z = graph.add_node(true);
+        graph.add_edge(a, b, (1_f64, false)).unwrap();
+        graph.add_edge(b, d, (1_f64, true)).unwrap();
+        graph.add_edge(a, c, (2_f64, false)).unwrap();
+        graph.add_edge(c, d, (2_f64, false)).unwrap();
+        graph.add_edge(a, z, (0.1_f64, false)).unwrap();
+        graph.add_edge(z, d, (0.1_f64, false)).unwrap();
+        return (graph, [a, b, c, d, z]);
+    }
+
+    #[test]
+    fn dijkstra_routes_around_hidden_nodes_and_edges() {
+        let (graph, [a, b, c, d, _]) = sample_graph();
+        let cost = |info: &(f64, bool)| info.0;
+
+        let path = DijkstraAlgorithm::shortest_path(&graph, a, d, cost)
+            .unwrap()
+            .unwrap();
+        assert!((path.cost - 0.2_f64).abs() < 1e-9);
+
+        let outside_zone = FilteredGraph::new(&graph, |n| !*n.node_info(), |_| true);
+        let path = DijkstraAlgorithm::shortest_path(&outside_zone, a, d, cost)
+            .unwrap()
+            .unwrap();
+        assert_eq!(path.nodes, vec![a, b, d]);
+
+        // Views stack: also hide blocked edges.
+        let open = FilteredGraph::new(&outside_zone, |_| true, |e| !e.edge_info().1);
+        let path = DijkstraAlgorithm::shortest_path(&open, a, d, cost)
+            .unwrap()
+            .unwrap();
+        assert_eq!(path.nodes, vec![a, c, d]);
+        assert_eq!(path.cost, 4_f64);
+
+        let no_c = FilteredGraph::new(&open, |n| n.get_id() != c, |_| true);
+        assert_eq!(
+            DijkstraAlgorithm::shortest_path(&no_c, a, d, cost),
+            Ok(None)
+        );
+        assert_eq!(
+            DijkstraAlgorithm::shortest_path(&no_c, a, c, cost),
+            Err(GraphError::NoSuchNode)
+        );
+    }
+
+    #[test]
+    fn traversal_only_reaches_visible_nodes() {
+        let (graph, [a, b, c, d, z]) = sample_graph();
+        let view = FilteredGraph::new(&graph, |n| n.get_id() != c, |e| !e.edge_info().1);
+
+        let mut reached: Vec<u32> =
+            BreadthFirstIterator::new(&view, a, TraversalDirection::Forward)
+                .unwrap()
+                .map(|(n, _)| n)
+                .collect();
+        reached.sort();
+        assert_eq!(reached, vec![a, b, d, z]);
+
+        assert!(BreadthFirstIterator::new(&view, c, TraversalDirection::Forward).is_err());
+        assert_eq!(view.connected_edges(c).count(), 0);
+        assert_eq!(view.connected_edges(d).count(), 1);
+        assert_eq!(view.node_ids().count(), 4);
+        assert_eq!(view.edge_ids().count(), 3);
+    }
+}
@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+
+use super::{edge::Edge, graph_change::GraphChange, graph_view::GraphView, node::Node};
+
+/// Immutable snapshot of a graph in compressed sparse row form, made by `Graph::freeze`
+/// for fast repeated queries on large maps.
+///
+/// Nodes and edges get dense indices in ascending ID order, and the connections of every
+/// node are stored in one contiguous slice. `FrozenSearchAlgorithm` searches on the dense
+/// indices directly; the snapshot also implements `GraphView`, so every other algorithm
+/// runs on it unchanged. Nodes returned by the snapshot list no connections of their own.
+#[derive(Debug)]
+pub struct FrozenGraph<TNodeInfo, TEdgeInfo> {
+    nodes: Vec<Node<TNodeInfo>>,
+    edges: Vec<Edge<TEdgeInfo>>,
+    node_indices: HashMap<u32, u32>,
+    edge_indices: HashMap<u32, u32>,
+
+    // Connections of node `i` are at `arc_offsets[i]..arc_offsets[i + 1]`.
+    arc_offsets: Vec<u32>,
+    arc_edges: Vec<u32>,
+    arc_targets: Vec<u32>,
+    arc_is_outgoing: Vec<bool>,
+
+    allow_cyclic_edges: bool,
+    allow_duplicate_edges: bool,
+    node_id_alloc: u32,
+    edge_id_alloc: u32,
+    revision: u64,
+}
+
+impl<TNodeInfo, TEdgeInfo> FrozenGraph<TNodeInfo, TEdgeInfo> {
+    /// Snapshot every visible node and edge of given graph or view.
+    pub fn from_view<GraphT>(graph: &GraphT) -> Self
+    where
+        GraphT: GraphView<NodeInfo = TNodeInfo, EdgeInfo = TEdgeInfo>,
+        TNodeInfo: Clone,
+        TEdgeInfo: Clone,
+    {
+        let mut node_ids: Vec<u32> = graph.node_ids().collect();
+        node_ids.sort();
+        let mut edge_ids: Vec<u32> = graph.edge_ids().collect();
+        edge_ids.sort();
+
+        let node_indices: HashMap<u32, u32> = node_ids
+            .iter()
+            .enumerate()
+            .map(|(i, n)| (*n, i as u32))
+            .collect();
+        let edge_indices: HashMap<u32, u32> = edge_ids
+            .iter()
+            .enumerate()
+            .map(|(i, e)| (*e, i as u32))
+            .collect();
+
+        let mut nodes: Vec<Node<TNodeInfo>> = Vec::with_capacity(node_ids.len());
+        let mut arc_offsets: Vec<u32> = Vec::with_capacity(node_ids.len() + 1);
+        let mut arc_edges: Vec<u32> = Vec::new();
+        let mut arc_targets: Vec<u32> = Vec::new();
+        let mut arc_is_outgoing: Vec<bool> = Vec::new();
+        for node_id in node_ids.iter() {
+            let node = graph.get_node_by_id(node_id).unwrap();
+            nodes.push(Node::new(*node_id, node.node_info().clone()));
+
+            // Connections in ascending edge ID order, so that searches are deterministic.
+            let mut connections: Vec<(u32, u32)> = graph.connected_edges(*node_id).collect();
+            connections.sort();
+            arc_offsets.push(arc_edges.len() as u32);
+            for (edge_id, next_node_id) in connections {
+                let edge = graph.get_edge_by_id(&edge_id).unwrap();
+                arc_edges.push(edge_indices[&edge_id]);
+                arc_targets.push(node_indices[&next_node_id]);
+                arc_is_outgoing.push(edge.can_move_from(*node_id));
+            }
+        }
+        arc_offsets.push(arc_edges.len() as u32);
+
+        let edges: Vec<Edge<TEdgeInfo>> = edge_ids
+            .iter()
+            .map(|edge_id| {
+                let edge = graph.get_edge_by_id(edge_id).unwrap();
+                return Edge::new(
+                    *edge_id,
+                    edge.node1(),
+                    edge.node2(),
+                    edge.can_move_forward(),
+                    edge.can_move_backward(),
+                    edge.edge_info().clone(),
+                );
+            })
+            .collect();
+
+        return Self {
+            nodes: nodes,
+            edges: edges,
+            node_indices: node_indices,
+            edge_indices: edge_indices,
+            arc_offsets: arc_offsets,
+            arc_edges: arc_edges,
+            arc_targets: arc_targets,
+            arc_is_outgoing: arc_is_outgoing,
+            allow_cyclic_edges: graph.allows_cyclic_edges(),
+            allow_duplicate_edges: graph.allows_duplicate_edges(),
+            node_id_alloc: graph.get_node_id_alloc(),
+            edge_id_alloc: graph.get_edge_id_alloc(),
+            revision: graph.get_revision(),
+        };
+    }
+
+    pub fn get_node_count(&self) -> usize {
+        return self.nodes.len();
+    }
+
+    pub fn get_edge_count(&self) -> usize {
+        return self.edges.len();
+    }
+
+    /// Dense index of given node, or None if it is not in the snapshot.
+    pub fn get_node_index(&self, node_id: u32) -> Option<usize> {
+        return self.node_indices.get(&node_id).map(|i| *i as usize);
+    }
+
+    /// Dense index of given edge, or None if it is not in the snapshot.
+    pub fn get_edge_index(&self, edge_id: u32) -> Option<usize> {
+        return self.edge_indices.get(&edge_id).map(|i| *i as usize);
+    }
+
+    /// Original ID of the node at given dense index.
+    pub fn get_node_id(&self, node_index: usize) -> u32 {
+        return self.nodes[node_index].get_id();
+    }
+
+    /// Original ID of the edge at given dense index.
+    pub fn get_edge_id(&self, edge_index: usize) -> u32 {
+        return self.edges[edge_index].get_id();
+    }
+
+    pub fn node_at(&self, node_index: usize) -> &Node<TNodeInfo> {
+        return &self.nodes[node_index];
+    }
+
+    pub fn edge_at(&self, edge_index: usize) -> &Edge<TEdgeInfo> {
+        return &self.edges[edge_index];
+    }
+
+    /// Edges connected to the node at given dense index, as (edge index, opposite node
+    /// index) pairs in ascending edge ID order.
+    pub fn connections(&self, node_index: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let range = self.arc_range(node_index);
+        return self.arc_edges[range.clone()]
+            .iter()
+            .zip(self.arc_targets[range].iter())
+            .map(|(e, n)| (*e as usize, *n as usize));
+    }
+
+    /// Edges that can be traversed leaving the node at given dense index, as (edge index,
+    /// opposite node index) pairs in ascending edge ID order.
+    pub fn outgoing_connections(
+        &self,
+        node_index: usize,
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let range = self.arc_range(node_index);
+        return self.arc_edges[range.clone()]
+            .iter()
+            .zip(self.arc_targets[range.clone()].iter())
+            .zip(self.arc_is_outgoing[range].iter())
+            .filter(|(_, is_outgoing)| **is_outgoing)
+            .map(|((e, n), _)| (*e as usize, *n as usize));
+    }
+
+    fn arc_range(&self, node_index: usize) -> std::ops::Range<usize> {
+        return self.arc_offsets[node_index] as usize..self.arc_offsets[node_index + 1] as usize;
+    }
+}
+
+impl<TNodeInfo, TEdgeInfo> GraphView for FrozenGraph<TNodeInfo, TEdgeInfo> {
+    type NodeInfo = TNodeInfo;
+    type EdgeInfo = TEdgeInfo;
+
+    fn get_node_by_id(&self, node_id: &u32) -> Option<&Node<TNodeInfo>> {
+        let index = self.get_node_index(*node_id)?;
+        return Some(&self.nodes[index]);
+    }
+
+    fn get_edge_by_id(&self, edge_id: &u32) -> Option<&Edge<TEdgeInfo>> {
+        let index = self.get_edge_index(*edge_id)?;
+        return Some(&self.edges[index]);
+    }
+
+    fn node_ids(&self) -> Box<dyn Iterator<Item = u32> + '_> {
+        return Box::new(self.nodes.iter().map(|n| n.get_id()));
+    }
+
+    fn edge_ids(&self) -> Box<dyn Iterator<Item = u32> + '_> {
+        return Box::new(self.edges.iter().map(|e| e.get_id()));
+    }
+
+    fn connected_edges(&self, node_id: u32) -> Box<dyn Iterator<Item = (u32, u32)> + '_> {
+        return match self.get_node_index(node_id) {
+            Some(i) => Box::new(
+                self.connections(i)
+                    .map(|(e, n)| (self.edges[e].get_id(), self.nodes[n].get_id())),
+            ),
+            None => Box::new(std::iter::empty()),
+        };
+    }
+
+    fn allows_cyclic_edges(&self) -> bool {
+        return self.allow_cyclic_edges;
+    }
+
+    fn allows_duplicate_edges(&self) -> bool {
+        return self.allow_duplicate_edges;
+    }
+
+    fn get_node_id_alloc(&self) -> u32 {
+        return self.node_id_alloc;
+    }
+
+    fn get_edge_id_alloc(&self) -> u32 {
+        return self.edge_id_alloc;
+    }
+
+    /// Revision of the original graph when the snapshot was taken.
+    fn get_revision(&self) -> u64 {
+        return self.revision;
+    }
+
+    /// A snapshot never changes, so there are no changes after its own revision.
+    fn changes_since(&self, revision: u64) -> Option<&[GraphChange]> {
+        if revision == self.revision {
+            return Some(&[]);
+        }
+        return None;
+    }
+
+    fn get_node_count(&self) -> usize {
+        return self.nodes.len();
+    }
+
+    fn get_edge_count(&self) -> usize {
+        return self.edges.len();
+    }
+}
@@ -1,6 +1,13 @@
 use std::collections::{HashMap, HashSet, LinkedList};
 
-use super::{edge::Edge, graph_error::GraphError, node::Node};
+use super::{
+    edge::Edge, frozen_graph::FrozenGraph, graph_change::GraphChange, graph_error::GraphError,
+    graph_inconsistency::GraphInconsistency, node::Node,
+};
+
+/// Edge given to bulk construction as
+/// `(edge_id, (node1_id, node2_id), (can_move_forward, can_move_backward), edge_info)`.
+pub type EntityEdge<TEdgeInfo> = (u32, (u32, u32), (bool, bool), TEdgeInfo);
 
 #[derive(Debug)]
 pub struct Graph<TNodeInfo, TEdgeInfo> {
@@ -12,6 +19,10 @@ pub struct Graph<TNodeInfo, TEdgeInfo> {
 
     node_id_alloc: u32,
     edge_id_alloc: u32,
+
+    revision: u64,
+    change_log: Option<Vec<GraphChange>>,
+    change_log_start: u64,
 }
 
 impl<TNodeInfo, TEdgeInfo> Graph<TNodeInfo, TEdgeInfo> {
@@ -23,80 +34,110 @@ impl<TNodeInfo, TEdgeInfo> Graph<TNodeInfo, TEdgeInfo> {
             allow_duplicate_edges: allow_duplicate_edges,
             node_id_alloc: 1_u32,
             edge_id_alloc: 1_u32,
+            revision: 0,
+            change_log: None,
+            change_log_start: 0,
         };
     }
 
+    /// Build graph from nodes and edges with given IDs.
+    /// The ID allocators continue after the largest given IDs, so `u32::MAX` is rejected.
+    ///
+    /// Migrating from `from_entities(nodes, edges, assume_bidirectional)`: give each edge
+    /// the direction flags `(true, assume_bidirectional)` and pass `false` for both policies,
+    /// which the old function enforced implicitly.
     pub fn from_entities(
         nodes: Vec<(u32, TNodeInfo)>,
-        edges: Vec<(u32, (u32, u32), TEdgeInfo)>,
-        assume_bidirectional: bool,
+        edges: Vec<EntityEdge<TEdgeInfo>>,
+        allow_cyclic_edges: bool,
+        allow_duplicate_edges: bool,
     ) -> Result<Self, GraphError> {
-        // Check if there are any nodes with duplicate IDs.
-        let mut unique_node_ids: HashSet<u32> = HashSet::new();
+        let mut graph: Graph<TNodeInfo, TEdgeInfo> =
+            Graph::new(allow_cyclic_edges, allow_duplicate_edges);
+        graph.nodes.reserve(nodes.len());
+        graph.edges.reserve(edges.len());
+
+        // Build list of nodes, rejecting duplicate IDs.
         let mut max_node_id: u32 = 0;
-        for (node_id, _) in nodes.iter() {
-            if !unique_node_ids.insert(*node_id) {
-                return Err(GraphError::DuplicateNodeId);
+        for (node_id, node_info) in nodes {
+            if graph.nodes.contains_key(&node_id) {
+                return Err(GraphError::DuplicateNodeId(node_id));
             }
 
-            max_node_id = u32::max(max_node_id, *node_id);
+            graph.nodes.insert(node_id, Node::new(node_id, node_info));
+            max_node_id = u32::max(max_node_id, node_id);
         }
 
-        // Check if there are any edges with duplicate IDs.
-        let mut unique_edge_ids: HashSet<u32> = HashSet::new();
+        // Build list of edges, checking each against the node list and the edge policies.
         let mut max_edge_id: u32 = 0;
-        for (edge_id, (n1, n2), _) in edges.iter() {
-            if !unique_edge_ids.insert(*edge_id) {
-                return Err(GraphError::DuplicateEdgeId);
+        for (edge_id, (n1, n2), (can_move_forward, can_move_backward), edge_info) in edges {
+            if graph.edges.contains_key(&edge_id) {
+                return Err(GraphError::DuplicateEdgeId(edge_id));
             }
 
-            max_edge_id = u32::max(max_edge_id, *edge_id);
-        }
+            for node_id in [n1, n2] {
+                if !graph.nodes.contains_key(&node_id) {
+                    return Err(GraphError::EdgeEndNodeNotFound {
+                        edge_id: edge_id,
+                        node_id: node_id,
+                    });
+                }
+            }
 
-        // Build list of nodes.
-        let mut _nodes: HashMap<u32, Node<TNodeInfo>> = HashMap::new();
-        for (node_id, node_info) in nodes {
-            _nodes.insert(node_id, Node::new(node_id, node_info));
-        }
+            graph.check_edge_policies(Some(edge_id), n1, n2)?;
 
-        // Build list of edges.
-        let mut _edges: HashMap<u32, Edge<TEdgeInfo>> = HashMap::new();
-        for (edge_id, (n1, n2), edge_info) in edges {
-            _edges.insert(
+            graph.edges.insert(
                 edge_id,
-                Edge::new(edge_id, n1, n2, true, assume_bidirectional, edge_info),
+                Edge::new(
+                    edge_id,
+                    n1,
+                    n2,
+                    can_move_forward,
+                    can_move_backward,
+                    edge_info,
+                ),
             );
-            _nodes.get_mut(&n1).unwrap().add_connection(n2, edge_id);
-            _nodes.get_mut(&n2).unwrap().add_connection(n1, edge_id);
+            graph
+                .nodes
+                .get_mut(&n1)
+                .unwrap()
+                .add_connection(n2, edge_id);
+            graph
+                .nodes
+                .get_mut(&n2)
+                .unwrap()
+                .add_connection(n1, edge_id);
+            max_edge_id = u32::max(max_edge_id, edge_id);
         }
 
-        return Ok(Self {
-            nodes: _nodes,
-            edges: _edges,
-            allow_cyclic_edges: false,
-            allow_duplicate_edges: false,
-            node_id_alloc: max_node_id + 1,
-            edge_id_alloc: max_edge_id + 1,
-        });
+        graph.node_id_alloc = match max_node_id.checked_add(1) {
+            Some(i) => i,
+            None => return Err(GraphError::NodeIdOutOfRange(max_node_id)),
+        };
+        graph.edge_id_alloc = match max_edge_id.checked_add(1) {
+            Some(i) => i,
+            None => return Err(GraphError::EdgeIdOutOfRange(max_edge_id)),
+        };
+        return Ok(graph);
     }
 
-    pub fn from_entities_list(
-        nodes: LinkedList<(u32, TNodeInfo)>,
-        edges: LinkedList<(u32, (u32, u32), TEdgeInfo)>,
-        assume_bidirectional: bool,
-    ) -> Result<Self, GraphError> {
-        let mut _nodes: Vec<(u32, TNodeInfo)> = Vec::with_capacity(nodes.len());
-        let mut _edges: Vec<(u32, (u32, u32), TEdgeInfo)> = Vec::with_capacity(edges.len());
-
-        for (node_id, node_info) in nodes {
-            _nodes.push((node_id, node_info));
-        }
-
-        for (edge_id, (n1, n2), edge_info) in edges {
-            _edges.push((edge_id, (n1, n2), edge_info));
-        }
-
-        return Graph::from_entities(_nodes, _edges, assume_bidirectional);
+    /// Same as `from_entities`, taking any iterable collections such as `LinkedList`.
+    pub fn from_entities_list<NodesT, EdgesT>(
+        nodes: NodesT,
+        edges: EdgesT,
+        allow_cyclic_edges: bool,
+        allow_duplicate_edges: bool,
+    ) -> Result<Self, GraphError>
+    where
+        NodesT: IntoIterator<Item = (u32, TNodeInfo)>,
+        EdgesT: IntoIterator<Item = EntityEdge<TEdgeInfo>>,
+    {
+        return Graph::from_entities(
+            nodes.into_iter().collect(),
+            edges.into_iter().collect(),
+            allow_cyclic_edges,
+            allow_duplicate_edges,
+        );
     }
 
     /// Add node and return its ID.
@@ -105,6 +146,7 @@ impl<TNodeInfo, TEdgeInfo> Graph<TNodeInfo, TEdgeInfo> {
         self.node_id_alloc += 1;
         let node = Node::new(node_id, node_info);
         self.nodes.insert(node_id, node);
+        self.record_change(GraphChange::NodeAdded { node_id: node_id });
         return node_id;
     }
 
@@ -130,6 +172,8 @@ impl<TNodeInfo, TEdgeInfo> Graph<TNodeInfo, TEdgeInfo> {
             return Err(GraphError::NoSuchNode);
         }
 
+        self.check_edge_policies(None, node1_id, node2_id)?;
+
         let edge_id = self.edge_id_alloc;
         self.edge_id_alloc += 1;
         let edge = Edge::new(
@@ -150,10 +194,69 @@ impl<TNodeInfo, TEdgeInfo> Graph<TNodeInfo, TEdgeInfo> {
             .get_mut(&node2_id)
             .unwrap()
             .add_connection(node1_id, edge_id);
+        self.record_change(GraphChange::EdgeAdded {
+            edge_id: edge_id,
+            node1_id: node1_id,
+            node2_id: node2_id,
+        });
 
         return Ok(edge_id);
     }
 
+    /// Check if an edge between given nodes is allowed by the cyclic and duplicate edge policies.
+    /// Both nodes must exist.
+    fn check_edge_policies(
+        &self,
+        edge_id: Option<u32>,
+        node1_id: u32,
+        node2_id: u32,
+    ) -> Result<(), GraphError> {
+        if !self.allow_cyclic_edges && node1_id == node2_id {
+            return Err(GraphError::CyclicEdgeNotAllowed {
+                edge_id: edge_id,
+                node_id: node1_id,
+            });
+        }
+
+        if !self.allow_duplicate_edges {
+            if let Some(edge_set) = self.nodes[&node1_id].adjacent_nodes().get(&node2_id) {
+                return Err(GraphError::DuplicateEdgeNotAllowed {
+                    edge_id: edge_id,
+                    existing_edge_id: *edge_set.iter().min().unwrap(),
+                    node1_id: node1_id,
+                    node2_id: node2_id,
+                });
+            }
+        }
+
+        return Ok(());
+    }
+
+    pub fn allows_cyclic_edges(&self) -> bool {
+        return self.allow_cyclic_edges;
+    }
+
+    pub fn allows_duplicate_edges(&self) -> bool {
+        return self.allow_duplicate_edges;
+    }
+
+    /// ID that will be given to the next added node.
+    pub fn get_node_id_alloc(&self) -> u32 {
+        return self.node_id_alloc;
+    }
+
+    /// ID that will be given to the next added edge.
+    pub fn get_edge_id_alloc(&self) -> u32 {
+        return self.edge_id_alloc;
+    }
+
+    /// Move the ID allocators forward so that new nodes and edges get IDs at or above
+    /// the given values. Allocators are never moved backward.
+    pub fn advance_id_alloc(&mut self, node_id_alloc: u32, edge_id_alloc: u32) {
+        self.node_id_alloc = u32::max(self.node_id_alloc, node_id_alloc);
+        self.edge_id_alloc = u32::max(self.edge_id_alloc, edge_id_alloc);
+    }
+
     pub fn get_node_count(&self) -> usize {
         return self.nodes.len();
     }
@@ -178,7 +281,40 @@ impl<TNodeInfo, TEdgeInfo> Graph<TNodeInfo, TEdgeInfo> {
         return self.edges.get(edge_id);
     }
 
+    /// Mutable access to the info of given node, for example to update it in place.
+    pub fn get_node_by_id_mut(&mut self, node_id: &u32) -> Option<&mut TNodeInfo> {
+        return self.nodes.get_mut(node_id).map(|n| n.node_info_mut());
+    }
+
+    /// Mutable access to the info of given edge, for example to update it in place.
+    /// The change is not recorded; use `update_edge_info` instead when edge costs derived
+    /// from the info may change, so that incremental planners notice.
+    pub fn get_edge_by_id_mut(&mut self, edge_id: &u32) -> Option<&mut TEdgeInfo> {
+        return self.edges.get_mut(edge_id).map(|e| e.edge_info_mut());
+    }
+
+    /// Take an immutable compressed snapshot of the graph for fast repeated queries,
+    /// see `FrozenGraph`.
+    pub fn freeze(&self) -> FrozenGraph<TNodeInfo, TEdgeInfo>
+    where
+        TNodeInfo: Clone,
+        TEdgeInfo: Clone,
+    {
+        return FrozenGraph::from_view(self);
+    }
+
     pub fn remove_node(&mut self, node_id: &u32) -> Result<u32, GraphError> {
+        self.take_node(node_id)?;
+        return Ok(*node_id);
+    }
+
+    /// Remove given node together with its edges, returning the node info and the removed
+    /// edges sorted by ID, for example to restore them later with `insert_node` and
+    /// `insert_edge`.
+    pub fn take_node(
+        &mut self,
+        node_id: &u32,
+    ) -> Result<(TNodeInfo, Vec<Edge<TEdgeInfo>>), GraphError> {
         // Remove corresponding node.
         let removed_node = match self.nodes.remove(node_id) {
             Some(n) => n,
@@ -191,20 +327,38 @@ impl<TNodeInfo, TEdgeInfo> Graph<TNodeInfo, TEdgeInfo> {
             rm_list.push_back((*rm_edge, *rm_node));
         }
 
+        let mut removed_edges: Vec<Edge<TEdgeInfo>> = Vec::with_capacity(rm_list.len());
         for (rm_edge, rm_node) in rm_list {
+            if let Some(edge) = self.edges.remove(&rm_edge) {
+                self.record_change(GraphChange::EdgeRemoved {
+                    edge_id: rm_edge,
+                    node1_id: edge.node1(),
+                    node2_id: edge.node2(),
+                });
+                removed_edges.push(edge);
+            }
+
+            // The removed node itself is no longer found here if the edge is a self-loop.
             let node = match self.nodes.get_mut(&rm_node) {
                 Some(n) => n,
                 None => continue,
             };
 
             node.remove_connection(rm_edge);
-            self.edges.remove(&rm_edge);
         }
 
-        return Ok(removed_node.get_id());
+        self.record_change(GraphChange::NodeRemoved { node_id: *node_id });
+        removed_edges.sort_by_key(|e| e.get_id());
+        return Ok((removed_node.into_node_info(), removed_edges));
     }
 
     pub fn remove_edge(&mut self, edge_id: &u32) -> Result<u32, GraphError> {
+        self.take_edge(edge_id)?;
+        return Ok(*edge_id);
+    }
+
+    /// Remove given edge and return it, for example to restore it later with `insert_edge`.
+    pub fn take_edge(&mut self, edge_id: &u32) -> Result<Edge<TEdgeInfo>, GraphError> {
         // Remove corresponding edge.
         let removed_edge = match self.edges.remove(edge_id) {
             Some(e) => e,
@@ -223,6 +377,692 @@ impl<TNodeInfo, TEdgeInfo> Graph<TNodeInfo, TEdgeInfo> {
         };
         n2.remove_connection(*edge_id);
 
-        return Ok(removed_edge.get_id());
+        self.record_change(GraphChange::EdgeRemoved {
+            edge_id: *edge_id,
+            node1_id: removed_edge.node1(),
+            node2_id: removed_edge.node2(),
+        });
+        return Ok(removed_edge);
+    }
+
+    /// Check that a node with given ID could be added with `insert_node`.
+    pub fn check_node_insertion(&self, node_id: u32) -> Result<(), GraphError> {
+        if node_id == u32::MAX {
+            return Err(GraphError::NodeIdOutOfRange(node_id));
+        }
+        if self.nodes.contains_key(&node_id) {
+            return Err(GraphError::DuplicateNodeId(node_id));
+        }
+        return Ok(());
+    }
+
+    /// Add node with given ID, for example to restore a node removed with `take_node`.
+    /// The node ID allocator is moved past the ID.
+    pub fn insert_node(&mut self, node_id: u32, node_info: TNodeInfo) -> Result<(), GraphError> {
+        self.check_node_insertion(node_id)?;
+
+        self.nodes.insert(node_id, Node::new(node_id, node_info));
+        self.node_id_alloc = u32::max(self.node_id_alloc, node_id + 1);
+        self.record_change(GraphChange::NodeAdded { node_id: node_id });
+        return Ok(());
+    }
+
+    /// Check that given edge could be added with `insert_edge`, without consuming it.
+    pub fn check_edge_insertion(&self, edge: &Edge<TEdgeInfo>) -> Result<(), GraphError> {
+        let edge_id = edge.get_id();
+        if edge_id == u32::MAX {
+            return Err(GraphError::EdgeIdOutOfRange(edge_id));
+        }
+        if self.edges.contains_key(&edge_id) {
+            return Err(GraphError::DuplicateEdgeId(edge_id));
+        }
+
+        for node_id in [edge.node1(), edge.node2()] {
+            if !self.nodes.contains_key(&node_id) {
+                return Err(GraphError::EdgeEndNodeNotFound {
+                    edge_id: edge_id,
+                    node_id: node_id,
+                });
+            }
+        }
+
+        return self.check_edge_policies(Some(edge_id), edge.node1(), edge.node2());
+    }
+
+    /// Add given edge keeping its ID, for example to restore an edge removed with
+    /// `take_edge`. The edge ID allocator is moved past the ID.
+    pub fn insert_edge(&mut self, edge: Edge<TEdgeInfo>) -> Result<(), GraphError> {
+        self.check_edge_insertion(&edge)?;
+
+        let edge_id = edge.get_id();
+        let (node1_id, node2_id) = (edge.node1(), edge.node2());
+        self.edges.insert(edge_id, edge);
+        self.nodes
+            .get_mut(&node1_id)
+            .unwrap()
+            .add_connection(node2_id, edge_id);
+        self.nodes
+            .get_mut(&node2_id)
+            .unwrap()
+            .add_connection(node1_id, edge_id);
+        self.edge_id_alloc = u32::max(self.edge_id_alloc, edge_id + 1);
+        self.record_change(GraphChange::EdgeAdded {
+            edge_id: edge_id,
+            node1_id: node1_id,
+            node2_id: node2_id,
+        });
+        return Ok(());
+    }
+
+    /// Keep only nodes accepted by given predicate, removing the others with their edges.
+    pub fn retain_nodes<PredicateT>(&mut self, mut predicate: PredicateT)
+    where
+        PredicateT: FnMut(&Node<TNodeInfo>) -> bool,
+    {
+        let mut removed_node_ids: Vec<u32> = self
+            .nodes
+            .values()
+            .filter(|n| !predicate(n))
+            .map(|n| n.get_id())
+            .collect();
+        removed_node_ids.sort();
+
+        for node_id in removed_node_ids {
+            self.remove_node(&node_id).unwrap();
+        }
+    }
+
+    /// Keep only edges accepted by given predicate, removing the others.
+    pub fn retain_edges<PredicateT>(&mut self, mut predicate: PredicateT)
+    where
+        PredicateT: FnMut(&Edge<TEdgeInfo>) -> bool,
+    {
+        let mut removed_edge_ids: Vec<u32> = self
+            .edges
+            .values()
+            .filter(|e| !predicate(e))
+            .map(|e| e.get_id())
+            .collect();
+        removed_edge_ids.sort();
+
+        for edge_id in removed_edge_ids {
+            self.remove_edge(&edge_id).unwrap();
+        }
+    }
+
+    /// Modify info of given edge, recording the change so that incremental planners such
+    /// as `DStarLitePlanner` pick up new edge costs.
+    pub fn update_edge_info<UpdateFuncT>(
+        &mut self,
+        edge_id: &u32,
+        update_func: UpdateFuncT,
+    ) -> Result<(), GraphError>
+    where
+        UpdateFuncT: FnOnce(&mut TEdgeInfo),
+    {
+        let edge = match self.edges.get_mut(edge_id) {
+            Some(e) => e,
+            None => return Err(GraphError::NoSuchEdge),
+        };
+        update_func(edge.edge_info_mut());
+
+        let change = GraphChange::EdgeUpdated {
+            edge_id: *edge_id,
+            node1_id: edge.node1(),
+            node2_id: edge.node2(),
+        };
+        self.record_change(change);
+        return Ok(());
+    }
+
+    /// Number of changes made to the graph so far.
+    pub fn get_revision(&self) -> u64 {
+        return self.revision;
+    }
+
+    /// Start recording changes, so that they can be read with `changes_since`.
+    ///
+    /// The log keeps every change until `trim_change_log` is called, so it grows without
+    /// bound on a graph that changes constantly. Readers do not register themselves:
+    /// the owner of the graph must trim the log up to the oldest revision still needed by
+    /// any reader, such as `DStarLitePlanner::get_revision`.
+    pub fn enable_change_log(&mut self) {
+        if self.change_log.is_none() {
+            self.change_log = Some(Vec::new());
+            self.change_log_start = self.revision;
+        }
+    }
+
+    /// Stop recording changes and drop the recorded ones.
+    pub fn disable_change_log(&mut self) {
+        self.change_log = None;
+    }
+
+    /// Changes made after given revision, oldest first.
+    /// Returns None if the change log is disabled or no longer holds all of them.
+    pub fn changes_since(&self, revision: u64) -> Option<&[GraphChange]> {
+        let change_log = self.change_log.as_ref()?;
+        if revision < self.change_log_start || revision > self.revision {
+            return None;
+        }
+
+        return Some(&change_log[(revision - self.change_log_start) as usize..]);
+    }
+
+    /// Drop recorded changes made up to given revision, once every reader has seen them.
+    pub fn trim_change_log(&mut self, revision: u64) {
+        if let Some(change_log) = self.change_log.as_mut() {
+            let revision = u64::min(revision, self.revision);
+            if revision > self.change_log_start {
+                change_log.drain(..(revision - self.change_log_start) as usize);
+                self.change_log_start = revision;
+            }
+        }
+    }
+
+    fn record_change(&mut self, change: GraphChange) {
+        self.revision += 1;
+        if let Some(change_log) = self.change_log.as_mut() {
+            change_log.push(change);
+        }
+    }
+
+    /// Check every invariant of the graph and report all inconsistencies found:
+    /// node and edge adjacency against the edge table, the cyclic and duplicate edge
+    /// policies, and the ID allocators. Inconsistencies are reported in ascending order of
+    /// node IDs, then edge IDs, so that the output is deterministic.
+    pub fn validate(&self) -> Result<(), Vec<GraphInconsistency>> {
+        let mut inconsistencies: Vec<GraphInconsistency> = Vec::new();
+
+        let mut nodes: Vec<(&u32, &Node<TNodeInfo>)> = self.nodes.iter().collect();
+        nodes.sort_by_key(|(node_id, _)| **node_id);
+        for (node_id, node) in nodes {
+            if *node_id != node.get_id() {
+                inconsistencies.push(GraphInconsistency::NodeIdMismatch {
+                    key: *node_id,
+                    node_id: node.get_id(),
+                });
+            }
+            if *node_id >= self.node_id_alloc {
+                inconsistencies.push(GraphInconsistency::NodeIdNotAllocated { node_id: *node_id });
+            }
+
+            // Every connection must refer to an existing edge ending at this node.
+            let mut connections: Vec<(&u32, &u32)> = node.connected_edges().iter().collect();
+            connections.sort();
+            for (edge_id, adjacent_node_id) in connections {
+                let edge = match self.edges.get(edge_id) {
+                    Some(e) => e,
+                    None => {
+                        inconsistencies.push(GraphInconsistency::DanglingConnection {
+                            node_id: *node_id,
+                            edge_id: *edge_id,
+                        });
+                        continue;
+                    }
+                };
+
+                let expected_node_id = if edge.node1() == *node_id {
+                    Some(edge.node2())
+                } else if edge.node2() == *node_id {
+                    Some(edge.node1())
+                } else {
+                    None
+                };
+                if expected_node_id != Some(*adjacent_node_id) {
+                    inconsistencies.push(GraphInconsistency::WrongConnection {
+                        node_id: *node_id,
+                        edge_id: *edge_id,
+                        adjacent_node_id: *adjacent_node_id,
+                    });
+                }
+
+                let is_in_adjacency = match node.adjacent_nodes().get(adjacent_node_id) {
+                    Some(edge_set) => edge_set.contains(edge_id),
+                    None => false,
+                };
+                if !is_in_adjacency {
+                    inconsistencies.push(GraphInconsistency::AdjacencyMismatch {
+                        node_id: *node_id,
+                        adjacent_node_id: *adjacent_node_id,
+                        edge_id: *edge_id,
+                    });
+                }
+            }
+
+            // Every adjacency entry must be backed by a connection.
+            let mut adjacencies: Vec<(&u32, &HashSet<u32>)> =
+                node.adjacent_nodes().iter().collect();
+            adjacencies.sort_by_key(|(adjacent_node_id, _)| **adjacent_node_id);
+            for (adjacent_node_id, edge_set) in adjacencies {
+                if edge_set.is_empty() {
+                    inconsistencies.push(GraphInconsistency::EmptyAdjacency {
+                        node_id: *node_id,
+                        adjacent_node_id: *adjacent_node_id,
+                    });
+                }
+                let mut edge_ids: Vec<u32> = edge_set.iter().copied().collect();
+                edge_ids.sort();
+                for edge_id in edge_ids.iter() {
+                    if node.connected_edges().get(edge_id) != Some(adjacent_node_id) {
+                        inconsistencies.push(GraphInconsistency::AdjacencyMismatch {
+                            node_id: *node_id,
+                            adjacent_node_id: *adjacent_node_id,
+                            edge_id: *edge_id,
+                        });
+                    }
+                }
+
+                if !self.allow_duplicate_edges && edge_set.len() > 1 && node_id <= adjacent_node_id
+                {
+                    inconsistencies.push(GraphInconsistency::DuplicateEdge {
+                        node1_id: *node_id,
+                        node2_id: *adjacent_node_id,
+                        edge_ids: edge_ids,
+                    });
+                }
+            }
+        }
+
+        let mut edges: Vec<(&u32, &Edge<TEdgeInfo>)> = self.edges.iter().collect();
+        edges.sort_by_key(|(edge_id, _)| **edge_id);
+        for (edge_id, edge) in edges {
+            if *edge_id != edge.get_id() {
+                inconsistencies.push(GraphInconsistency::EdgeIdMismatch {
+                    key: *edge_id,
+                    edge_id: edge.get_id(),
+                });
+            }
+            if *edge_id >= self.edge_id_alloc {
+                inconsistencies.push(GraphInconsistency::EdgeIdNotAllocated { edge_id: *edge_id });
+            }
+            if !self.allow_cyclic_edges && edge.node1() == edge.node2() {
+                inconsistencies.push(GraphInconsistency::CyclicEdge { edge_id: *edge_id });
+            }
+
+            // Both end nodes must exist and know about the edge.
+            for node_id in [edge.node1(), edge.node2()] {
+                match self.nodes.get(&node_id) {
+                    Some(n) => {
+                        if !n.connected_edges().contains_key(edge_id) {
+                            inconsistencies.push(GraphInconsistency::MissingConnection {
+                                node_id: node_id,
+                                edge_id: *edge_id,
+                            });
+                        }
+                    }
+                    None => inconsistencies.push(GraphInconsistency::MissingEndNode {
+                        edge_id: *edge_id,
+                        node_id: node_id,
+                    }),
+                }
+            }
+        }
+
+        if inconsistencies.is_empty() {
+            return Ok(());
+        }
+        return Err(inconsistencies);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        algorithm::traversal::traversal_direction::TraversalDirection, graph::graph_view::GraphView,
+    };
+
+    /// Node 1 with a one-way edge to 2, parallel edges to 3, a self-loop and a one-way
+    /// edge from 3, and an edge between 2 and 4.
+    fn direction_sample_graph() -> Graph<(), ()> {
+        let mut graph: Graph<(), ()> = Graph::new(true, true);
+        for _ in 0..4 {
+            graph.add_node(());
+        }
+        graph.add_directed_edge(1, 2, true, false, ()).unwrap();
+        graph.add_edge(1, 3, ()).unwrap();
+        graph.add_edge(1, 3, ()).unwrap();
+        graph.add_edge(1, 1, ()).unwrap();
+        graph.add_directed_edge(3, 1, true, false, ()).unwrap();
+        graph.add_edge(2, 4, ()).unwrap();
+        return graph;
+    }
+
+    fn sorted<T: Ord>(iter: impl Iterator<Item = T>) -> Vec<T> {
+        let mut items: Vec<T> = iter.collect();
+        items.sort();
+        return items;
+    }
+
+    #[test]
+    fn cyclic_edge_policy() {
+        let mut graph: Graph<(), ()> = Graph::new(false, true);
+        let a = graph.add_node(());
+        assert_eq!(
+            graph.add_directed_edge(a, a, true, false, ()),
+            Err(GraphError::CyclicEdgeNotAllowed {
+                edge_id: None,
+                node_id: a,
+            })
+        );
+        assert_eq!(graph.get_edge_count(), 0);
+        assert_eq!(graph.get_edge_id_alloc(), 1);
+
+        let mut graph: Graph<(), ()> = Graph::new(true, false);
+        let a = graph.add_node(());
+        let edge_id = graph.add_edge(a, a, ()).unwrap();
+        assert_eq!(graph.get_node_by_id(&a).unwrap().degree(), 1);
+        assert_eq!(graph.validate(), Ok(()));
+
+        // A second self-loop on the same node is a duplicate edge.
+        assert_eq!(
+            graph.add_edge(a, a, ()),
+            Err(GraphError::DuplicateEdgeNotAllowed {
+                edge_id: None,
+                existing_edge_id: edge_id,
+                node1_id: a,
+                node2_id: a,
+            })
+        );
+    }
+
+    #[test]
+    fn duplicate_edge_policy() {
+        let mut graph: Graph<(), ()> = Graph::new(false, false);
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        let edge_id = graph.add_directed_edge(a, b, true, false, ()).unwrap();
+
+        // Parallel edges are rejected in either orientation and regardless of direction flags.
+        for (node1_id, node2_id) in [(a, b), (b, a)] {
+            assert_eq!(
+                graph.add_directed_edge(node1_id, node2_id, false, true, ()),
+                Err(GraphError::DuplicateEdgeNotAllowed {
+                    edge_id: None,
+                    existing_edge_id: edge_id,
+                    node1_id: node1_id,
+                    node2_id: node2_id,
+                })
+            );
+        }
+        assert_eq!(graph.get_edge_count(), 1);
+
+        let mut graph: Graph<(), ()> = Graph::new(false, true);
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        graph.add_edge(a, b, ()).unwrap();
+        graph.add_edge(b, a, ()).unwrap();
+        assert_eq!(graph.get_edge_count(), 2);
+        assert_eq!(
+            graph.get_node_by_id(&a).unwrap().adjacent_nodes()[&b].len(),
+            2
+        );
+        assert_eq!(graph.validate(), Ok(()));
+
+        assert_eq!(graph.add_edge(a, 9, ()), Err(GraphError::NoSuchNode));
+    }
+
+    #[test]
+    fn validate_reports_inconsistencies_in_id_order() {
+        let mut graph: Graph<(), ()> = Graph::new(false, false);
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        graph.add_edge(a, b, ()).unwrap();
+        assert_eq!(graph.validate(), Ok(()));
+
+        graph.nodes.insert(40, Node::new(41, ()));
+        graph.nodes.insert(7, Node::new(7, ()));
+        graph
+            .edges
+            .insert(30, Edge::new(30, 90, 91, true, true, ()));
+        graph.edges.insert(20, Edge::new(20, a, a, true, true, ()));
+
+        assert_eq!(
+            graph.validate(),
+            Err(vec![
+                GraphInconsistency::NodeIdNotAllocated { node_id: 7 },
+                GraphInconsistency::NodeIdMismatch {
+                    key: 40,
+                    node_id: 41,
+                },
+                GraphInconsistency::NodeIdNotAllocated { node_id: 40 },
+                GraphInconsistency::EdgeIdNotAllocated { edge_id: 20 },
+                GraphInconsistency::CyclicEdge { edge_id: 20 },
+                GraphInconsistency::MissingConnection {
+                    node_id: a,
+                    edge_id: 20,
+                },
+                GraphInconsistency::MissingConnection {
+                    node_id: a,
+                    edge_id: 20,
+                },
+                GraphInconsistency::EdgeIdNotAllocated { edge_id: 30 },
+                GraphInconsistency::MissingEndNode {
+                    edge_id: 30,
+                    node_id: 90,
+                },
+                GraphInconsistency::MissingEndNode {
+                    edge_id: 30,
+                    node_id: 91,
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn from_entities_checks_ids_and_continues_allocators() {
+        let graph: Graph<(), ()> = Graph::from_entities(
+            vec![(3, ()), (8, ())],
+            vec![(5, (3, 8), (true, false), ())],
+            false,
+            false,
+        )
+        .unwrap();
+        assert_eq!(graph.get_node_id_alloc(), 9);
+        assert_eq!(graph.get_edge_id_alloc(), 6);
+        assert!(!graph.get_edge_by_id(&5).unwrap().can_move_from(8));
+        assert_eq!(graph.validate(), Ok(()));
+
+        assert_eq!(
+            Graph::<(), ()>::from_entities(vec![(u32::MAX, ())], vec![], false, false).err(),
+            Some(GraphError::NodeIdOutOfRange(u32::MAX))
+        );
+        assert_eq!(
+            Graph::<(), ()>::from_entities(
+                vec![(1, ()), (2, ())],
+                vec![(u32::MAX, (1, 2), (true, true), ())],
+                false,
+                false
+            )
+            .err(),
+            Some(GraphError::EdgeIdOutOfRange(u32::MAX))
+        );
+        assert_eq!(
+            Graph::<(), ()>::from_entities(
+                vec![(1, ())],
+                vec![(4, (1, 2), (true, true), ())],
+                false,
+                false
+            )
+            .err(),
+            Some(GraphError::EdgeEndNodeNotFound {
+                edge_id: 4,
+                node_id: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn insert_checks_ids_and_continues_allocators() {
+        let mut graph: Graph<(), ()> = Graph::new(false, false);
+        graph.insert_node(4, ()).unwrap();
+        graph.insert_node(2, ()).unwrap();
+        graph
+            .insert_edge(Edge::new(7, 4, 2, true, true, ()))
+            .unwrap();
+        assert_eq!(graph.get_node_id_alloc(), 5);
+        assert_eq!(graph.get_edge_id_alloc(), 8);
+
+        assert_eq!(
+            graph.insert_node(u32::MAX, ()),
+            Err(GraphError::NodeIdOutOfRange(u32::MAX))
+        );
+        assert_eq!(
+            graph.insert_node(4, ()),
+            Err(GraphError::DuplicateNodeId(4))
+        );
+        assert_eq!(
+            graph.insert_edge(Edge::new(u32::MAX, 4, 2, true, true, ())),
+            Err(GraphError::EdgeIdOutOfRange(u32::MAX))
+        );
+        assert_eq!(
+            graph.check_edge_insertion(&Edge::new(8, 4, 3, true, true, ())),
+            Err(GraphError::EdgeEndNodeNotFound {
+                edge_id: 8,
+                node_id: 3,
+            })
+        );
+        assert_eq!(graph.get_node_id_alloc(), 5);
+        assert_eq!(graph.get_edge_id_alloc(), 8);
+        assert_eq!(graph.validate(), Ok(()));
+    }
+
+    #[test]
+    fn direction_aware_queries_follow_edge_flags() {
+        let graph = direction_sample_graph();
+
+        assert_eq!(
+            sorted(graph.outgoing_edges(1)),
+            vec![(1, 2), (2, 3), (3, 3), (4, 1)]
+        );
+        assert_eq!(
+            sorted(graph.incoming_edges(1)),
+            vec![(2, 3), (3, 3), (4, 1), (5, 3)]
+        );
+        assert_eq!(sorted(graph.outgoing_edges(2)), vec![(6, 4)]);
+        assert_eq!(sorted(graph.incoming_edges(2)), vec![(1, 1), (6, 4)]);
+
+        // The self-loop makes node 1 its own neighbor, listed once.
+        assert_eq!(
+            sorted(graph.neighbors(1, TraversalDirection::Forward)),
+            vec![1, 2, 3]
+        );
+        assert_eq!(
+            sorted(graph.neighbors(1, TraversalDirection::Backward)),
+            vec![1, 3]
+        );
+        assert_eq!(
+            sorted(graph.neighbors(1, TraversalDirection::Undirected)),
+            vec![1, 2, 3]
+        );
+        assert_eq!(
+            sorted(graph.neighbors(2, TraversalDirection::Forward)),
+            vec![4]
+        );
+        assert_eq!(
+            sorted(graph.neighbors(2, TraversalDirection::Backward)),
+            vec![1, 4]
+        );
+        assert_eq!(
+            sorted(graph.neighbors(2, TraversalDirection::Undirected)),
+            vec![1, 4]
+        );
+
+        assert_eq!(graph.edges_between(1, 3), vec![2, 3, 5]);
+        assert_eq!(graph.edges_between(3, 1), vec![2, 3, 5]);
+        assert_eq!(graph.edges_between(1, 1), vec![4]);
+        assert_eq!(graph.edges_between(3, 4), Vec::<u32>::new());
+
+        assert_eq!(graph.edge_endpoint_opposite(1, 1), Some(2));
+        assert_eq!(graph.edge_endpoint_opposite(1, 2), Some(1));
+        assert_eq!(graph.edge_endpoint_opposite(4, 1), Some(1));
+        assert_eq!(graph.edge_endpoint_opposite(1, 3), None);
+        assert_eq!(graph.edge_endpoint_opposite(9, 1), None);
+    }
+
+    #[test]
+    fn retain_removes_entities_and_records_changes() {
+        let mut graph = direction_sample_graph();
+        graph.enable_change_log();
+        let revision = graph.get_revision();
+
+        graph.retain_nodes(|n| n.get_id() != 1);
+        assert_eq!(sorted(graph.node_ids()), vec![2, 3, 4]);
+        assert_eq!(sorted(graph.edge_ids()), vec![6]);
+        assert_eq!(graph.validate(), Ok(()));
+
+        let mut changes: Vec<GraphChange> = graph.changes_since(revision).unwrap().to_vec();
+        assert_eq!(changes.pop(), Some(GraphChange::NodeRemoved { node_id: 1 }));
+        changes.sort_by_key(|c| match c {
+            GraphChange::EdgeRemoved { edge_id, .. } => *edge_id,
+            _ => u32::MAX,
+        });
+        let expected_ends = [(1, 2), (1, 3), (1, 3), (1, 1), (3, 1)];
+        assert_eq!(changes.len(), expected_ends.len());
+        for (i, change) in changes.iter().enumerate() {
+            assert_eq!(
+                *change,
+                GraphChange::EdgeRemoved {
+                    edge_id: i as u32 + 1,
+                    node1_id: expected_ends[i].0,
+                    node2_id: expected_ends[i].1,
+                }
+            );
+        }
+
+        let mut graph = direction_sample_graph();
+        graph.enable_change_log();
+        let revision = graph.get_revision();
+        graph.retain_edges(|e| e.can_move_backward());
+        assert_eq!(graph.get_node_count(), 4);
+        assert_eq!(sorted(graph.edge_ids()), vec![2, 3, 4, 6]);
+        assert_eq!(graph.validate(), Ok(()));
+        assert_eq!(
+            graph.changes_since(revision).unwrap(),
+            &[
+                GraphChange::EdgeRemoved {
+                    edge_id: 1,
+                    node1_id: 1,
+                    node2_id: 2,
+                },
+                GraphChange::EdgeRemoved {
+                    edge_id: 5,
+                    node1_id: 3,
+                    node2_id: 1,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn mutable_accessors_update_infos_without_recording_changes() {
+        let mut graph: Graph<u32, f64> = Graph::new(false, false);
+        graph.enable_change_log();
+        let a = graph.add_node(1);
+        let b = graph.add_node(2);
+        let edge_id = graph.add_edge(a, b, 1_f64).unwrap();
+        let revision = graph.get_revision();
+
+        *graph.get_node_by_id_mut(&a).unwrap() = 10;
+        *graph.get_edge_by_id_mut(&edge_id).unwrap() = 5_f64;
+        assert_eq!(*graph.get_node_by_id(&a).unwrap().node_info(), 10);
+        assert_eq!(*graph.get_edge_by_id(&edge_id).unwrap().edge_info(), 5_f64);
+        assert_eq!(graph.get_revision(), revision);
+        assert!(graph.get_node_by_id_mut(&99).is_none());
+        assert!(graph.get_edge_by_id_mut(&99).is_none());
+
+        graph.update_edge_info(&edge_id, |w| *w = 2_f64).unwrap();
+        assert_eq!(
+            graph.changes_since(revision),
+            Some(
+                &[GraphChange::EdgeUpdated {
+                    edge_id: edge_id,
+                    node1_id: a,
+                    node2_id: b,
+                }][..]
+            )
+        );
+        assert_eq!(graph.validate(), Ok(()));
     }
 }
@@ -0,0 +1,27 @@
+/// Change made to a `Graph`, as recorded in its change log.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GraphChange {
+    NodeAdded {
+        node_id: u32,
+    },
+    NodeRemoved {
+        node_id: u32,
+    },
+    EdgeAdded {
+        edge_id: u32,
+        node1_id: u32,
+        node2_id: u32,
+    },
+    /// Edge was removed, either directly or together with one of its end nodes.
+    EdgeRemoved {
+        edge_id: u32,
+        node1_id: u32,
+        node2_id: u32,
+    },
+    /// Edge info was modified, possibly changing the cost of the edge.
+    EdgeUpdated {
+        edge_id: u32,
+        node1_id: u32,
+        node2_id: u32,
+    },
+}
@@ -0,0 +1,109 @@
+use super::{edge::Edge, graph::Graph, graph_error::GraphError};
+
+/// Edit that could not be applied, returned intact together with the reason.
+pub type FailedGraphEdit<TNodeInfo, TEdgeInfo> = (GraphEdit<TNodeInfo, TEdgeInfo>, GraphError);
+
+/// Single reversible operation on a `Graph`, as recorded by `GraphEditor`.
+#[derive(Debug)]
+pub enum GraphEdit<TNodeInfo, TEdgeInfo> {
+    /// Insert node with given ID together with given edges, which connect it to existing
+    /// nodes or to itself.
+    InsertNode {
+        node_id: u32,
+        node_info: TNodeInfo,
+        edges: Vec<Edge<TEdgeInfo>>,
+    },
+    /// Remove node together with its edges.
+    RemoveNode {
+        node_id: u32,
+    },
+    /// Insert edge keeping its ID.
+    InsertEdge {
+        edge: Edge<TEdgeInfo>,
+    },
+    RemoveEdge {
+        edge_id: u32,
+    },
+}
+
+impl<TNodeInfo, TEdgeInfo> GraphEdit<TNodeInfo, TEdgeInfo> {
+    /// Apply the edit to given graph and return the edit that reverts it.
+    /// If the edit fails, the graph is left unchanged and the edit is returned intact
+    /// with the error.
+    pub fn apply(
+        self,
+        graph: &mut Graph<TNodeInfo, TEdgeInfo>,
+    ) -> Result<GraphEdit<TNodeInfo, TEdgeInfo>, FailedGraphEdit<TNodeInfo, TEdgeInfo>> {
+        match self {
+            GraphEdit::InsertNode {
+                node_id,
+                node_info,
+                edges,
+            } => {
+                if let Err(e) = graph.check_node_insertion(node_id) {
+                    return Err((
+                        GraphEdit::InsertNode {
+                            node_id: node_id,
+                            node_info: node_info,
+                            edges: edges,
+                        },
+                        e,
+                    ));
+                }
+                graph.insert_node(node_id, node_info).unwrap();
+
+                // Edges are checked before being moved into the graph, so that on failure
+                // the inserted ones can be taken back together with the node.
+                let mut failure: Option<GraphError> = None;
+                let mut remaining_edges: Vec<Edge<TEdgeInfo>> = Vec::new();
+                for edge in edges {
+                    if failure.is_none() {
+                        failure = graph.check_edge_insertion(&edge).err();
+                    }
+                    match failure {
+                        Some(_) => remaining_edges.push(edge),
+                        None => graph.insert_edge(edge).unwrap(),
+                    }
+                }
+
+                match failure {
+                    Some(e) => {
+                        let (node_info, mut edges) = graph.take_node(&node_id).unwrap();
+                        edges.append(&mut remaining_edges);
+                        return Err((
+                            GraphEdit::InsertNode {
+                                node_id: node_id,
+                                node_info: node_info,
+                                edges: edges,
+                            },
+                            e,
+                        ));
+                    }
+                    None => return Ok(GraphEdit::RemoveNode { node_id: node_id }),
+                }
+            }
+            GraphEdit::RemoveNode { node_id } => match graph.take_node(&node_id) {
+                Ok((node_info, edges)) => {
+                    return Ok(GraphEdit::InsertNode {
+                        node_id: node_id,
+                        node_info: node_info,
+                        edges: edges,
+                    })
+                }
+                Err(e) => return Err((GraphEdit::RemoveNode { node_id: node_id }, e)),
+            },
+            GraphEdit::InsertEdge { edge } => {
+                if let Err(e) = graph.check_edge_insertion(&edge) {
+                    return Err((GraphEdit::InsertEdge { edge: edge }, e));
+                }
+                let edge_id = edge.get_id();
+                graph.insert_edge(edge).unwrap();
+                return Ok(GraphEdit::RemoveEdge { edge_id: edge_id });
+            }
+            GraphEdit::RemoveEdge { edge_id } => match graph.take_edge(&edge_id) {
+                Ok(edge) => return Ok(GraphEdit::InsertEdge { edge: edge }),
+                Err(e) => return Err((GraphEdit::RemoveEdge { edge_id: edge_id }, e)),
+            },
+        }
+    }
+}
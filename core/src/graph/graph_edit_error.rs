@@ -0,0 +1,21 @@
+use super::graph_error::GraphError;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GraphEditError {
+    /// A transaction was begun while another one is open.
+    TransactionAlreadyOpen,
+    /// Commit or rollback was requested without an open transaction.
+    NoOpenTransaction,
+    /// Undo or redo was requested while a transaction is open.
+    TransactionOpen,
+    NothingToUndo,
+    NothingToRedo,
+    /// Recorded edits could not be applied to the graph.
+    Graph(GraphError),
+}
+
+impl From<GraphError> for GraphEditError {
+    fn from(error: GraphError) -> Self {
+        return GraphEditError::Graph(error);
+    }
+}
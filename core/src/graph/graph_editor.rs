@@ -0,0 +1,432 @@
+use super::{
+    graph::Graph, graph_edit::GraphEdit, graph_edit_error::GraphEditError, graph_error::GraphError,
+};
+
+type GraphEditBatch<TNodeInfo, TEdgeInfo> = Vec<GraphEdit<TNodeInfo, TEdgeInfo>>;
+type FailedGraphEditBatch<TNodeInfo, TEdgeInfo> =
+    (GraphEditBatch<TNodeInfo, TEdgeInfo>, GraphError);
+
+/// Editing layer around a `Graph` that records every operation so that it can be undone.
+///
+/// Operations made between `begin_transaction` and `commit_transaction` form one batch,
+/// which is undone and redone as a whole, or reverted with `rollback_transaction`.
+/// Operations made outside of a transaction form a batch each. Undone nodes and edges are
+/// restored with their original IDs, and a removed node is restored with all of its edges.
+pub struct GraphEditor<TNodeInfo, TEdgeInfo> {
+    graph: Graph<TNodeInfo, TEdgeInfo>,
+
+    /// Edits reverting the operations of the open transaction, in the order of the operations.
+    transaction: Option<GraphEditBatch<TNodeInfo, TEdgeInfo>>,
+    /// Batches of edits, each applied from last to first to undo or redo it.
+    undo_stack: Vec<GraphEditBatch<TNodeInfo, TEdgeInfo>>,
+    redo_stack: Vec<GraphEditBatch<TNodeInfo, TEdgeInfo>>,
+}
+
+impl<TNodeInfo, TEdgeInfo> GraphEditor<TNodeInfo, TEdgeInfo> {
+    pub fn new(graph: Graph<TNodeInfo, TEdgeInfo>) -> Self {
+        return Self {
+            graph: graph,
+            transaction: None,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+        };
+    }
+
+    pub fn get_graph(&self) -> &Graph<TNodeInfo, TEdgeInfo> {
+        return &self.graph;
+    }
+
+    /// Take the edited graph, dropping the history and any open transaction.
+    pub fn into_graph(self) -> Graph<TNodeInfo, TEdgeInfo> {
+        return self.graph;
+    }
+
+    pub fn add_node(&mut self, node_info: TNodeInfo) -> u32 {
+        let node_id = self.graph.add_node(node_info);
+        self.record(GraphEdit::RemoveNode { node_id: node_id });
+        return node_id;
+    }
+
+    pub fn add_edge(
+        &mut self,
+        node1_id: u32,
+        node2_id: u32,
+        edge_info: TEdgeInfo,
+    ) -> Result<u32, GraphError> {
+        return self.add_directed_edge(node1_id, node2_id, true, true, edge_info);
+    }
+
+    pub fn add_directed_edge(
+        &mut self,
+        node1_id: u32,
+        node2_id: u32,
+        can_move_forward: bool,
+        can_move_backward: bool,
+        edge_info: TEdgeInfo,
+    ) -> Result<u32, GraphError> {
+        let edge_id = self.graph.add_directed_edge(
+            node1_id,
+            node2_id,
+            can_move_forward,
+            can_move_backward,
+            edge_info,
+        )?;
+        self.record(GraphEdit::RemoveEdge { edge_id: edge_id });
+        return Ok(edge_id);
+    }
+
+    /// Remove node with its edges, keeping them so that undo restores them.
+    pub fn remove_node(&mut self, node_id: &u32) -> Result<u32, GraphError> {
+        let (node_info, edges) = self.graph.take_node(node_id)?;
+        self.record(GraphEdit::InsertNode {
+            node_id: *node_id,
+            node_info: node_info,
+            edges: edges,
+        });
+        return Ok(*node_id);
+    }
+
+    pub fn remove_edge(&mut self, edge_id: &u32) -> Result<u32, GraphError> {
+        let edge = self.graph.take_edge(edge_id)?;
+        self.record(GraphEdit::InsertEdge { edge: edge });
+        return Ok(*edge_id);
+    }
+
+    /// Start collecting operations into one batch. Transactions cannot be nested.
+    pub fn begin_transaction(&mut self) -> Result<(), GraphEditError> {
+        if self.transaction.is_some() {
+            return Err(GraphEditError::TransactionAlreadyOpen);
+        }
+
+        self.transaction = Some(Vec::new());
+        return Ok(());
+    }
+
+    /// Close the open transaction, making its operations one undoable batch.
+    pub fn commit_transaction(&mut self) -> Result<(), GraphEditError> {
+        let batch = match self.transaction.take() {
+            Some(b) => b,
+            None => return Err(GraphEditError::NoOpenTransaction),
+        };
+
+        if !batch.is_empty() {
+            self.undo_stack.push(batch);
+        }
+        return Ok(());
+    }
+
+    /// Close the open transaction, reverting all of its operations. If they cannot be
+    /// reverted, the graph is left unchanged and the transaction stays open.
+    pub fn rollback_transaction(&mut self) -> Result<(), GraphEditError> {
+        let batch = match self.transaction.take() {
+            Some(b) => b,
+            None => return Err(GraphEditError::NoOpenTransaction),
+        };
+
+        if let Err((batch, e)) = GraphEditor::apply_batch(&mut self.graph, batch) {
+            self.transaction = Some(batch);
+            return Err(GraphEditError::Graph(e));
+        }
+        return Ok(());
+    }
+
+    pub fn is_transaction_open(&self) -> bool {
+        return self.transaction.is_some();
+    }
+
+    /// Revert the last batch of operations. If it cannot be reverted, the graph is left
+    /// unchanged and the batch stays undoable.
+    pub fn undo(&mut self) -> Result<(), GraphEditError> {
+        if self.transaction.is_some() {
+            return Err(GraphEditError::TransactionOpen);
+        }
+
+        let batch = match self.undo_stack.pop() {
+            Some(b) => b,
+            None => return Err(GraphEditError::NothingToUndo),
+        };
+        match GraphEditor::apply_batch(&mut self.graph, batch) {
+            Ok(redo_batch) => self.redo_stack.push(redo_batch),
+            Err((batch, e)) => {
+                self.undo_stack.push(batch);
+                return Err(GraphEditError::Graph(e));
+            }
+        }
+        return Ok(());
+    }
+
+    /// Apply the last undone batch again. Any new operation discards the undone batches.
+    /// If it cannot be applied, the graph is left unchanged and the batch stays redoable.
+    pub fn redo(&mut self) -> Result<(), GraphEditError> {
+        if self.transaction.is_some() {
+            return Err(GraphEditError::TransactionOpen);
+        }
+
+        let batch = match self.redo_stack.pop() {
+            Some(b) => b,
+            None => return Err(GraphEditError::NothingToRedo),
+        };
+        match GraphEditor::apply_batch(&mut self.graph, batch) {
+            Ok(undo_batch) => self.undo_stack.push(undo_batch),
+            Err((batch, e)) => {
+                self.redo_stack.push(batch);
+                return Err(GraphEditError::Graph(e));
+            }
+        }
+        return Ok(());
+    }
+
+    pub fn can_undo(&self) -> bool {
+        return self.transaction.is_none() && !self.undo_stack.is_empty();
+    }
+
+    pub fn can_redo(&self) -> bool {
+        return self.transaction.is_none() && !self.redo_stack.is_empty();
+    }
+
+    /// Forget all undoable and redoable batches. An open transaction stays open.
+    pub fn clear_history(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+    }
+
+    fn record(&mut self, edit: GraphEdit<TNodeInfo, TEdgeInfo>) {
+        self.redo_stack.clear();
+        match self.transaction.as_mut() {
+            Some(t) => t.push(edit),
+            None => self.undo_stack.push(vec![edit]),
+        }
+    }
+
+    /// Apply given edits from last to first and return the edits reverting them, in the
+    /// order they were applied. If an edit fails, the edits applied before it are reverted
+    /// and the batch is returned as given, together with the error.
+    fn apply_batch(
+        graph: &mut Graph<TNodeInfo, TEdgeInfo>,
+        batch: GraphEditBatch<TNodeInfo, TEdgeInfo>,
+    ) -> Result<GraphEditBatch<TNodeInfo, TEdgeInfo>, FailedGraphEditBatch<TNodeInfo, TEdgeInfo>>
+    {
+        let mut edits = batch;
+        let mut reverse_batch: GraphEditBatch<TNodeInfo, TEdgeInfo> =
+            Vec::with_capacity(edits.len());
+        while let Some(edit) = edits.pop() {
+            match edit.apply(graph) {
+                Ok(reverse_edit) => reverse_batch.push(reverse_edit),
+                Err((edit, e)) => {
+                    // Reverting the applied edits gives them back in their original order.
+                    let mut restored_edits = match GraphEditor::apply_batch(graph, reverse_batch) {
+                        Ok(r) => r,
+                        Err(_) => unreachable!("edits that were just applied can be reverted"),
+                    };
+                    edits.push(edit);
+                    edits.append(&mut restored_edits);
+                    return Err((edits, e));
+                }
+            }
+        }
+        return Ok(reverse_batch);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{edge::Edge, graph_view::GraphView};
+
+    type NodeSnapshot = (u32, char, Vec<(u32, u32)>);
+    type EdgeSnapshot = (u32, u32, u32, bool, bool, char);
+    type Snapshot = (Vec<NodeSnapshot>, Vec<EdgeSnapshot>);
+
+    /// Nodes with their sorted connections and edges with their ends, flags and infos,
+    /// in ID order.
+    fn snapshot(graph: &Graph<char, char>) -> Snapshot {
+        let mut nodes: Vec<NodeSnapshot> = graph
+            .node_ids()
+            .map(|n| {
+                let mut connections: Vec<(u32, u32)> = graph.connected_edges(n).collect();
+                connections.sort();
+                return (
+                    n,
+                    *graph.get_node_by_id(&n).unwrap().node_info(),
+                    connections,
+                );
+            })
+            .collect();
+        nodes.sort();
+        let mut edges: Vec<EdgeSnapshot> = graph
+            .edge_ids()
+            .map(|e| {
+                let edge = graph.get_edge_by_id(&e).unwrap();
+                return (
+                    e,
+                    edge.node1(),
+                    edge.node2(),
+                    edge.can_move_forward(),
+                    edge.can_move_backward(),
+                    *edge.edge_info(),
+                );
+            })
+            .collect();
+        edges.sort();
+        return (nodes, edges);
+    }
+
+    /// Nodes a, b and c with a self-loop on a, parallel and one-way edges.
+    fn sample_graph() -> Graph<char, char> {
+        let mut graph: Graph<char, char> = Graph::new(true, true);
+        let a = graph.add_node('a');
+        let b = graph.add_node('b');
+        let c = graph.add_node('c');
+        graph.add_edge(a, b, 'p').unwrap();
+        graph.add_directed_edge(b, a, true, false, 'q').unwrap();
+        graph.add_edge(a, a, 'r').unwrap();
+        graph.add_edge(a, c, 's').unwrap();
+        graph.add_edge(a, c, 't').unwrap();
+        graph.add_directed_edge(b, c, false, true, 'u').unwrap();
+        return graph;
+    }
+
+    #[test]
+    fn undo_remove_node_restores_incident_edges_and_self_loops() {
+        let mut editor = GraphEditor::new(sample_graph());
+        let before = snapshot(editor.get_graph());
+
+        editor.remove_node(&1).unwrap();
+        let after = snapshot(editor.get_graph());
+        assert_eq!(editor.get_graph().get_node_count(), 2);
+        assert_eq!(editor.get_graph().get_edge_count(), 1);
+
+        editor.undo().unwrap();
+        assert_eq!(snapshot(editor.get_graph()), before);
+        assert_eq!(editor.get_graph().validate(), Ok(()));
+        assert!(!editor.can_undo());
+
+        editor.redo().unwrap();
+        assert_eq!(snapshot(editor.get_graph()), after);
+        editor.undo().unwrap();
+        assert_eq!(snapshot(editor.get_graph()), before);
+        assert!(editor.can_redo());
+        editor.redo().unwrap();
+        assert_eq!(editor.redo(), Err(GraphEditError::NothingToRedo));
+    }
+
+    #[test]
+    fn transactions_are_undone_redone_and_rolled_back_as_a_whole() {
+        let mut editor = GraphEditor::new(sample_graph());
+        let before = snapshot(editor.get_graph());
+
+        editor.begin_transaction().unwrap();
+        assert_eq!(
+            editor.begin_transaction(),
+            Err(GraphEditError::TransactionAlreadyOpen)
+        );
+        let d = editor.add_node('d');
+        editor.add_edge(d, 2, 'v').unwrap();
+        editor.remove_edge(&4).unwrap();
+        editor.remove_node(&3).unwrap();
+        assert_eq!(editor.undo(), Err(GraphEditError::TransactionOpen));
+        editor.commit_transaction().unwrap();
+        let after = snapshot(editor.get_graph());
+
+        editor.undo().unwrap();
+        assert_eq!(snapshot(editor.get_graph()), before);
+        editor.redo().unwrap();
+        assert_eq!(snapshot(editor.get_graph()), after);
+
+        // A new operation discards the undone batches.
+        editor.undo().unwrap();
+        editor.add_node('e');
+        assert!(!editor.can_redo());
+        editor.undo().unwrap();
+        assert_eq!(snapshot(editor.get_graph()), before);
+
+        editor.begin_transaction().unwrap();
+        editor.remove_node(&1).unwrap();
+        let e = editor.add_node('e');
+        editor.add_edge(e, 2, 'w').unwrap();
+        editor.remove_edge(&6).unwrap();
+        editor.rollback_transaction().unwrap();
+        assert_eq!(snapshot(editor.get_graph()), before);
+        assert!(!editor.can_undo());
+        assert_eq!(
+            editor.rollback_transaction(),
+            Err(GraphEditError::NoOpenTransaction)
+        );
+    }
+
+    #[test]
+    fn failed_batch_restores_applied_edits() {
+        let mut graph = sample_graph();
+        let before = snapshot(&graph);
+
+        // Applied from last to first: the node and edge removals succeed, then the edge
+        // to a missing node fails.
+        let batch: GraphEditBatch<char, char> = vec![
+            GraphEdit::RemoveEdge { edge_id: 1 },
+            GraphEdit::InsertEdge {
+                edge: Edge::new(9, 2, 8, true, true, 'x'),
+            },
+            GraphEdit::RemoveEdge { edge_id: 6 },
+            GraphEdit::RemoveNode { node_id: 1 },
+        ];
+        let (batch, error) = GraphEditor::apply_batch(&mut graph, batch).err().unwrap();
+        assert_eq!(
+            error,
+            GraphError::EdgeEndNodeNotFound {
+                edge_id: 9,
+                node_id: 8,
+            }
+        );
+        assert_eq!(snapshot(&graph), before);
+
+        assert_eq!(batch.len(), 4);
+        assert!(matches!(batch[0], GraphEdit::RemoveEdge { edge_id: 1 }));
+        assert!(matches!(&batch[1], GraphEdit::InsertEdge { edge } if edge.get_id() == 9));
+        assert!(matches!(batch[2], GraphEdit::RemoveEdge { edge_id: 6 }));
+        assert!(matches!(batch[3], GraphEdit::RemoveNode { node_id: 1 }));
+    }
+
+    #[test]
+    fn failed_insert_node_returns_node_info_and_edges() {
+        let mut graph = sample_graph();
+        let before = snapshot(&graph);
+
+        let edit = GraphEdit::InsertNode {
+            node_id: 7,
+            node_info: 'g',
+            edges: vec![
+                Edge::new(10, 7, 2, true, false, 'y'),
+                Edge::new(11, 7, 7, true, true, 'z'),
+                Edge::new(12, 7, 9, true, true, 'w'),
+            ],
+        };
+        let (edit, error) = edit.apply(&mut graph).err().unwrap();
+        assert_eq!(
+            error,
+            GraphError::EdgeEndNodeNotFound {
+                edge_id: 12,
+                node_id: 9,
+            }
+        );
+        assert_eq!(snapshot(&graph), before);
+
+        match edit {
+            GraphEdit::InsertNode {
+                node_id,
+                node_info,
+                edges,
+            } => {
+                assert_eq!((node_id, node_info), (7, 'g'));
+                let edges: Vec<(u32, u32, u32, char)> = edges
+                    .iter()
+                    .map(|e| (e.get_id(), e.node1(), e.node2(), *e.edge_info()))
+                    .collect();
+                assert_eq!(
+                    edges,
+                    vec![(10, 7, 2, 'y'), (11, 7, 7, 'z'), (12, 7, 9, 'w')]
+                );
+            }
+            _ => panic!("expected the insert node edit back"),
+        }
+    }
+}
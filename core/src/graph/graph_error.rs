@@ -2,6 +2,47 @@
 pub enum GraphError {
     NoSuchNode,
     NoSuchEdge,
-    DuplicateNodeId,
-    DuplicateEdgeId,
-}
\ No newline at end of file
+    DuplicateNodeId(u32),
+    DuplicateEdgeId(u32),
+    /// Node ID `u32::MAX` is rejected, because the ID allocator could not continue after it.
+    NodeIdOutOfRange(u32),
+    /// Edge ID `u32::MAX` is rejected, like `NodeIdOutOfRange`.
+    EdgeIdOutOfRange(u32),
+    /// Edge given to bulk construction refers to a node that is not in the node list.
+    EdgeEndNodeNotFound {
+        edge_id: u32,
+        node_id: u32,
+    },
+    InvalidEdgeCost,
+    /// Precomputed data such as a `ContractionHierarchy` does not match the graph or the
+    /// edge costs it is used with.
+    FingerprintMismatch {
+        expected: u64,
+        actual: u64,
+    },
+    /// More stops than an exact solver accepts, such as `HELD_KARP_STOP_LIMIT`.
+    TooManyStops {
+        stop_count: usize,
+        limit: usize,
+    },
+    /// Caller-supplied function returned a different number of edge infos than the
+    /// edges it was asked for.
+    EdgeInfoCountMismatch {
+        expected: usize,
+        actual: usize,
+    },
+    /// Self-loop rejected by a graph that does not allow cyclic edges.
+    /// `edge_id` is None when the edge was not given an ID yet.
+    CyclicEdgeNotAllowed {
+        edge_id: Option<u32>,
+        node_id: u32,
+    },
+    /// Parallel edge rejected by a graph that does not allow duplicate edges.
+    /// `edge_id` is None when the edge was not given an ID yet.
+    DuplicateEdgeNotAllowed {
+        edge_id: Option<u32>,
+        existing_edge_id: u32,
+        node1_id: u32,
+        node2_id: u32,
+    },
+}
@@ -0,0 +1,40 @@
+/// Broken graph invariant reported by `Graph::validate`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GraphInconsistency {
+    /// Node is stored under a key different from its own ID.
+    NodeIdMismatch { key: u32, node_id: u32 },
+    /// Edge is stored under a key different from its own ID.
+    EdgeIdMismatch { key: u32, edge_id: u32 },
+    /// Node ID is not below the node ID allocator, so a future node could reuse it.
+    NodeIdNotAllocated { node_id: u32 },
+    /// Edge ID is not below the edge ID allocator, so a future edge could reuse it.
+    EdgeIdNotAllocated { edge_id: u32 },
+    /// Edge refers to an end node that does not exist.
+    MissingEndNode { edge_id: u32, node_id: u32 },
+    /// End node of an edge does not list the edge among its connections.
+    MissingConnection { node_id: u32, edge_id: u32 },
+    /// Node lists a connection through an edge that does not exist.
+    DanglingConnection { node_id: u32, edge_id: u32 },
+    /// Node lists a connection through an edge that does not join it with the listed node.
+    WrongConnection {
+        node_id: u32,
+        edge_id: u32,
+        adjacent_node_id: u32,
+    },
+    /// Connected edges and adjacent nodes of a node disagree about an edge.
+    AdjacencyMismatch {
+        node_id: u32,
+        adjacent_node_id: u32,
+        edge_id: u32,
+    },
+    /// Node keeps an adjacency entry without any edge.
+    EmptyAdjacency { node_id: u32, adjacent_node_id: u32 },
+    /// Self-loop in a graph that does not allow cyclic edges.
+    CyclicEdge { edge_id: u32 },
+    /// Parallel edges in a graph that does not allow duplicate edges.
+    DuplicateEdge {
+        node1_id: u32,
+        node2_id: u32,
+        edge_ids: Vec<u32>,
+    },
+}
@@ -0,0 +1,19 @@
+/// Path found by a graph search.
+/// `nodes` starts at the start node and ends at the goal node, and `edges[i]`
+/// connects `nodes[i]` to `nodes[i + 1]`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GraphPath {
+    pub nodes: Vec<u32>,
+    pub edges: Vec<u32>,
+    pub cost: f64,
+}
+
+impl GraphPath {
+    pub fn start_node(&self) -> u32 {
+        return *self.nodes.first().unwrap();
+    }
+
+    pub fn goal_node(&self) -> u32 {
+        return *self.nodes.last().unwrap();
+    }
+}
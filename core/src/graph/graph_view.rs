@@ -0,0 +1,271 @@
+use std::collections::HashSet;
+
+use crate::algorithm::traversal::traversal_direction::TraversalDirection;
+
+use super::{
+    edge::Edge,
+    graph::{EntityEdge, Graph},
+    graph_change::GraphChange,
+    graph_error::GraphError,
+    node::Node,
+};
+
+/// Read-only access to the nodes and edges of a graph.
+///
+/// Implemented by `Graph` and by `FilteredGraph`, so that traversal and path algorithms
+/// run unchanged on a whole graph or on a filtered view of it. Algorithms must find the
+/// connections of a node with `connected_edges` of the view, since `Node::connected_edges`
+/// also lists connections hidden by a filter.
+pub trait GraphView {
+    type NodeInfo;
+    type EdgeInfo;
+
+    /// Node with given ID, or None if it does not exist or is hidden.
+    fn get_node_by_id(&self, node_id: &u32) -> Option<&Node<Self::NodeInfo>>;
+
+    /// Edge with given ID, or None if it does not exist or is hidden.
+    fn get_edge_by_id(&self, edge_id: &u32) -> Option<&Edge<Self::EdgeInfo>>;
+
+    /// IDs of visible nodes in no particular order.
+    fn node_ids(&self) -> Box<dyn Iterator<Item = u32> + '_>;
+
+    /// IDs of visible edges in no particular order.
+    fn edge_ids(&self) -> Box<dyn Iterator<Item = u32> + '_>;
+
+    /// Visible edges connected to given node, as (edge ID, opposite node ID) pairs.
+    fn connected_edges(&self, node_id: u32) -> Box<dyn Iterator<Item = (u32, u32)> + '_>;
+
+    fn allows_cyclic_edges(&self) -> bool;
+
+    fn allows_duplicate_edges(&self) -> bool;
+
+    fn get_node_id_alloc(&self) -> u32;
+
+    fn get_edge_id_alloc(&self) -> u32;
+
+    /// Number of changes made to the underlying graph so far.
+    fn get_revision(&self) -> u64;
+
+    /// Changes made to the underlying graph after given revision, as recorded by
+    /// `Graph::enable_change_log`. Changes of filter predicates are not recorded.
+    fn changes_since(&self, revision: u64) -> Option<&[GraphChange]>;
+
+    fn get_node_count(&self) -> usize {
+        return self.node_ids().count();
+    }
+
+    fn get_edge_count(&self) -> usize {
+        return self.edge_ids().count();
+    }
+
+    /// Visible edges that can be traversed leaving given node, as (edge ID, opposite node
+    /// ID) pairs.
+    fn outgoing_edges(&self, node_id: u32) -> Box<dyn Iterator<Item = (u32, u32)> + '_> {
+        return Box::new(self.connected_edges(node_id).filter(move |(edge_id, _)| {
+            let edge = self.get_edge_by_id(edge_id).unwrap();
+            return TraversalDirection::Forward.can_traverse(edge, node_id);
+        }));
+    }
+
+    /// Visible edges that can be traversed arriving at given node, as (edge ID, opposite
+    /// node ID) pairs.
+    fn incoming_edges(&self, node_id: u32) -> Box<dyn Iterator<Item = (u32, u32)> + '_> {
+        return Box::new(self.connected_edges(node_id).filter(move |(edge_id, _)| {
+            let edge = self.get_edge_by_id(edge_id).unwrap();
+            return TraversalDirection::Backward.can_traverse(edge, node_id);
+        }));
+    }
+
+    /// Distinct nodes reached from given node through one visible edge, following edges in
+    /// given direction. A node with a self-loop is its own neighbor.
+    fn neighbors(
+        &self,
+        node_id: u32,
+        direction: TraversalDirection,
+    ) -> Box<dyn Iterator<Item = u32> + '_> {
+        let mut seen: HashSet<u32> = HashSet::new();
+        return Box::new(
+            self.connected_edges(node_id)
+                .filter(move |(edge_id, next_node_id)| {
+                    let edge = self.get_edge_by_id(edge_id).unwrap();
+                    return direction.can_traverse(edge, node_id) && seen.insert(*next_node_id);
+                })
+                .map(|(_, next_node_id)| next_node_id),
+        );
+    }
+
+    /// IDs of visible edges connecting given nodes in any direction, sorted.
+    fn edges_between(&self, node1_id: u32, node2_id: u32) -> Vec<u32> {
+        let mut edge_ids: Vec<u32> = self
+            .connected_edges(node1_id)
+            .filter(|(_, next_node_id)| *next_node_id == node2_id)
+            .map(|(edge_id, _)| edge_id)
+            .collect();
+        edge_ids.sort();
+        return edge_ids;
+    }
+
+    /// End node of given edge opposite to given node; the node itself for a self-loop.
+    /// Returns None if the edge does not exist or does not end at the node.
+    fn edge_endpoint_opposite(&self, edge_id: u32, node_id: u32) -> Option<u32> {
+        let edge = self.get_edge_by_id(&edge_id)?;
+        if edge.node1() == node_id {
+            return Some(edge.node2());
+        }
+        if edge.node2() == node_id {
+            return Some(edge.node1());
+        }
+        return None;
+    }
+
+    /// Copy given nodes and edges into a new graph, keeping their IDs, movement flags and
+    /// the edge policies. Both end nodes of every given edge must be among the given nodes.
+    /// The ID allocators continue from those of the original graph, so IDs added to the
+    /// copy never collide with IDs of the original.
+    fn clone_subgraph(
+        &self,
+        node_ids: &[u32],
+        edge_ids: &[u32],
+    ) -> Result<Graph<Self::NodeInfo, Self::EdgeInfo>, GraphError>
+    where
+        Self::NodeInfo: Clone,
+        Self::EdgeInfo: Clone,
+    {
+        let mut nodes: Vec<(u32, Self::NodeInfo)> = Vec::with_capacity(node_ids.len());
+        for node_id in node_ids {
+            let node = match self.get_node_by_id(node_id) {
+                Some(n) => n,
+                None => return Err(GraphError::NoSuchNode),
+            };
+            nodes.push((*node_id, node.node_info().clone()));
+        }
+
+        let mut edges: Vec<EntityEdge<Self::EdgeInfo>> = Vec::with_capacity(edge_ids.len());
+        for edge_id in edge_ids {
+            let edge = match self.get_edge_by_id(edge_id) {
+                Some(e) => e,
+                None => return Err(GraphError::NoSuchEdge),
+            };
+            edges.push((
+                *edge_id,
+                (edge.node1(), edge.node2()),
+                (edge.can_move_forward(), edge.can_move_backward()),
+                edge.edge_info().clone(),
+            ));
+        }
+
+        let mut subgraph = Graph::from_entities(
+            nodes,
+            edges,
+            self.allows_cyclic_edges(),
+            self.allows_duplicate_edges(),
+        )?;
+        subgraph.advance_id_alloc(self.get_node_id_alloc(), self.get_edge_id_alloc());
+        return Ok(subgraph);
+    }
+
+    /// Copy given nodes and every visible edge between them into a new graph, keeping IDs
+    /// like `clone_subgraph`.
+    fn induced_subgraph(
+        &self,
+        node_ids: &[u32],
+    ) -> Result<Graph<Self::NodeInfo, Self::EdgeInfo>, GraphError>
+    where
+        Self::NodeInfo: Clone,
+        Self::EdgeInfo: Clone,
+    {
+        let node_set: HashSet<u32> = node_ids.iter().copied().collect();
+        let mut edge_ids: Vec<u32> = Vec::new();
+        for node_id in node_set.iter() {
+            if self.get_node_by_id(node_id).is_none() {
+                return Err(GraphError::NoSuchNode);
+            }
+
+            // Take each edge from its node1 only, so that it is listed once.
+            for (edge_id, next_node_id) in self.connected_edges(*node_id) {
+                let edge = self.get_edge_by_id(&edge_id).unwrap();
+                if edge.node1() == *node_id && node_set.contains(&next_node_id) {
+                    edge_ids.push(edge_id);
+                }
+            }
+        }
+        edge_ids.sort();
+
+        let mut node_ids: Vec<u32> = node_set.into_iter().collect();
+        node_ids.sort();
+        return self.clone_subgraph(&node_ids, &edge_ids);
+    }
+
+    /// Copy every visible node and edge into a new graph, keeping IDs like `clone_subgraph`.
+    fn to_graph(&self) -> Graph<Self::NodeInfo, Self::EdgeInfo>
+    where
+        Self::NodeInfo: Clone,
+        Self::EdgeInfo: Clone,
+    {
+        let mut node_ids: Vec<u32> = self.node_ids().collect();
+        node_ids.sort();
+        let mut edge_ids: Vec<u32> = self.edge_ids().collect();
+        edge_ids.sort();
+        return self.clone_subgraph(&node_ids, &edge_ids).unwrap();
+    }
+}
+
+impl<TNodeInfo, TEdgeInfo> GraphView for Graph<TNodeInfo, TEdgeInfo> {
+    type NodeInfo = TNodeInfo;
+    type EdgeInfo = TEdgeInfo;
+
+    fn get_node_by_id(&self, node_id: &u32) -> Option<&Node<TNodeInfo>> {
+        return Graph::get_node_by_id(self, node_id);
+    }
+
+    fn get_edge_by_id(&self, edge_id: &u32) -> Option<&Edge<TEdgeInfo>> {
+        return Graph::get_edge_by_id(self, edge_id);
+    }
+
+    fn node_ids(&self) -> Box<dyn Iterator<Item = u32> + '_> {
+        return Box::new(self.get_nodes().keys().copied());
+    }
+
+    fn edge_ids(&self) -> Box<dyn Iterator<Item = u32> + '_> {
+        return Box::new(self.get_edges().keys().copied());
+    }
+
+    fn connected_edges(&self, node_id: u32) -> Box<dyn Iterator<Item = (u32, u32)> + '_> {
+        return match Graph::get_node_by_id(self, &node_id) {
+            Some(n) => Box::new(n.connected_edges().iter().map(|(e, n)| (*e, *n))),
+            None => Box::new(std::iter::empty()),
+        };
+    }
+
+    fn allows_cyclic_edges(&self) -> bool {
+        return Graph::allows_cyclic_edges(self);
+    }
+
+    fn allows_duplicate_edges(&self) -> bool {
+        return Graph::allows_duplicate_edges(self);
+    }
+
+    fn get_node_id_alloc(&self) -> u32 {
+        return Graph::get_node_id_alloc(self);
+    }
+
+    fn get_edge_id_alloc(&self) -> u32 {
+        return Graph::get_edge_id_alloc(self);
+    }
+
+    fn get_revision(&self) -> u64 {
+        return Graph::get_revision(self);
+    }
+
+    fn changes_since(&self, revision: u64) -> Option<&[GraphChange]> {
+        return Graph::changes_since(self, revision);
+    }
+
+    fn get_node_count(&self) -> usize {
+        return Graph::get_node_count(self);
+    }
+
+    fn get_edge_count(&self) -> usize {
+        return Graph::get_edge_count(self);
+    }
+}
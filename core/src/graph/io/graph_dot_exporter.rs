@@ -0,0 +1,135 @@
+use std::fs;
+
+use crate::graph::graph::Graph;
+
+/// Export of `Graph` in Graphviz DOT format, for visual inspection.
+///
+/// The graph is written as a `digraph`. Edge directions are written as `dir` attributes,
+/// `both` for bidirectional edges and `none` for edges that cannot be traversed at all.
+/// Node and edge attributes, such as `label` or `pos`, are produced by given formatters.
+pub struct GraphDotExporter;
+
+impl GraphDotExporter {
+    pub fn export<TNodeInfo, TEdgeInfo, NodeFormatterT, EdgeFormatterT>(
+        file_name: &str,
+        graph: &Graph<TNodeInfo, TEdgeInfo>,
+        node_formatter: NodeFormatterT,
+        edge_formatter: EdgeFormatterT,
+    ) -> std::io::Result<()>
+    where
+        NodeFormatterT: Fn(&TNodeInfo) -> Vec<(String, String)>,
+        EdgeFormatterT: Fn(&TEdgeInfo) -> Vec<(String, String)>,
+    {
+        let text = GraphDotExporter::export_to_string(graph, node_formatter, edge_formatter);
+        return fs::write(file_name, text);
+    }
+
+    pub fn export_to_string<TNodeInfo, TEdgeInfo, NodeFormatterT, EdgeFormatterT>(
+        graph: &Graph<TNodeInfo, TEdgeInfo>,
+        node_formatter: NodeFormatterT,
+        edge_formatter: EdgeFormatterT,
+    ) -> String
+    where
+        NodeFormatterT: Fn(&TNodeInfo) -> Vec<(String, String)>,
+        EdgeFormatterT: Fn(&TEdgeInfo) -> Vec<(String, String)>,
+    {
+        let mut text = String::from("digraph {\n");
+
+        let mut node_ids: Vec<&u32> = graph.get_nodes().keys().collect();
+        node_ids.sort();
+        for node_id in node_ids {
+            let node = graph.get_node_by_id(node_id).unwrap();
+            let attributes = node_formatter(node.node_info());
+            text.push_str(&format!(
+                "    {}{};\n",
+                node_id,
+                GraphDotExporter::format_attributes(&attributes)
+            ));
+        }
+
+        let mut edge_ids: Vec<&u32> = graph.get_edges().keys().collect();
+        edge_ids.sort();
+        for edge_id in edge_ids {
+            let edge = graph.get_edge_by_id(edge_id).unwrap();
+            let direction = match (edge.can_move_forward(), edge.can_move_backward()) {
+                (true, true) => "both",
+                (true, false) => "forward",
+                (false, true) => "back",
+                (false, false) => "none",
+            };
+
+            let mut attributes = vec![
+                (String::from("id"), edge_id.to_string()),
+                (String::from("dir"), String::from(direction)),
+            ];
+            attributes.extend(edge_formatter(edge.edge_info()));
+            text.push_str(&format!(
+                "    {} -> {}{};\n",
+                edge.node1(),
+                edge.node2(),
+                GraphDotExporter::format_attributes(&attributes)
+            ));
+        }
+
+        text.push_str("}\n");
+        return text;
+    }
+
+    fn format_attributes(attributes: &[(String, String)]) -> String {
+        if attributes.is_empty() {
+            return String::new();
+        }
+
+        let formatted: Vec<String> = attributes
+            .iter()
+            .map(|(key, value)| format!("{}=\"{}\"", key, GraphDotExporter::escape(value)))
+            .collect();
+        return format!(" [{}]", formatted.join(", "));
+    }
+
+    fn escape(value: &str) -> String {
+        return value
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_nodes_and_edges_in_id_order() {
+        let mut graph: Graph<&str, f64> = Graph::new(true, false);
+        let a = graph.add_node("a \"quoted\"");
+        let b = graph.add_node("b\nline");
+        graph.add_directed_edge(b, a, true, false, 2_f64).unwrap();
+        graph.add_directed_edge(a, a, false, true, 0.5_f64).unwrap();
+        graph.add_directed_edge(b, b, false, false, 0_f64).unwrap();
+
+        let text = GraphDotExporter::export_to_string(
+            &graph,
+            |label| vec![(String::from("label"), label.to_string())],
+            |cost| vec![(String::from("weight"), cost.to_string())],
+        );
+        assert_eq!(
+            text,
+            concat!(
+                "digraph {\n",
+                "    1 [label=\"a \\\"quoted\\\"\"];\n",
+                "    2 [label=\"b\\nline\"];\n",
+                "    2 -> 1 [id=\"1\", dir=\"forward\", weight=\"2\"];\n",
+                "    1 -> 1 [id=\"2\", dir=\"back\", weight=\"0.5\"];\n",
+                "    2 -> 2 [id=\"3\", dir=\"none\", weight=\"0\"];\n",
+                "}\n",
+            )
+        );
+
+        let empty: Graph<(), ()> = Graph::new(false, false);
+        assert_eq!(
+            GraphDotExporter::export_to_string(&empty, |_| vec![], |_| vec![]),
+            "digraph {\n}\n"
+        );
+    }
+}
@@ -0,0 +1,174 @@
+use std::{collections::BTreeSet, fs};
+
+use quick_xml::escape::escape;
+
+use crate::graph::graph::Graph;
+
+/// Prefix of the attributes written for the graph itself rather than by the formatters,
+/// so that formatters may use any other name, including `can_move_forward`.
+pub const RESERVED_KEY_PREFIX: &str = "graph.";
+
+/// Export of `Graph` in GraphML format, readable back with `GraphGraphMLImporter` and
+/// by tools such as yEd, Gephi or NetworkX.
+///
+/// Nodes are written with IDs `n<node_id>` and edges with IDs `e<edge_id>`. The graph
+/// policies and ID allocators are written as graph data, and the movement flags of each
+/// edge as edge data, all named with `RESERVED_KEY_PREFIX`. Attributes produced by the
+/// formatters are declared as string keys named after the attribute.
+pub struct GraphGraphMLExporter;
+
+impl GraphGraphMLExporter {
+    pub fn export<TNodeInfo, TEdgeInfo, NodeFormatterT, EdgeFormatterT>(
+        file_name: &str,
+        graph: &Graph<TNodeInfo, TEdgeInfo>,
+        node_formatter: NodeFormatterT,
+        edge_formatter: EdgeFormatterT,
+    ) -> std::io::Result<()>
+    where
+        NodeFormatterT: Fn(&TNodeInfo) -> Vec<(String, String)>,
+        EdgeFormatterT: Fn(&TEdgeInfo) -> Vec<(String, String)>,
+    {
+        let text = GraphGraphMLExporter::export_to_string(graph, node_formatter, edge_formatter);
+        return fs::write(file_name, text);
+    }
+
+    pub fn export_to_string<TNodeInfo, TEdgeInfo, NodeFormatterT, EdgeFormatterT>(
+        graph: &Graph<TNodeInfo, TEdgeInfo>,
+        node_formatter: NodeFormatterT,
+        edge_formatter: EdgeFormatterT,
+    ) -> String
+    where
+        NodeFormatterT: Fn(&TNodeInfo) -> Vec<(String, String)>,
+        EdgeFormatterT: Fn(&TEdgeInfo) -> Vec<(String, String)>,
+    {
+        let mut node_ids: Vec<&u32> = graph.get_nodes().keys().collect();
+        node_ids.sort();
+        let node_attributes: Vec<(u32, Vec<(String, String)>)> = node_ids
+            .into_iter()
+            .map(|node_id| {
+                let node = graph.get_node_by_id(node_id).unwrap();
+                return (*node_id, node_formatter(node.node_info()));
+            })
+            .collect();
+
+        let mut edge_ids: Vec<&u32> = graph.get_edges().keys().collect();
+        edge_ids.sort();
+        let edge_attributes: Vec<(u32, Vec<(String, String)>)> = edge_ids
+            .into_iter()
+            .map(|edge_id| {
+                let edge = graph.get_edge_by_id(edge_id).unwrap();
+                return (*edge_id, edge_formatter(edge.edge_info()));
+            })
+            .collect();
+
+        let mut text = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        text.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+
+        for (key, attr_type) in [
+            ("allow_cyclic_edges", "boolean"),
+            ("allow_duplicate_edges", "boolean"),
+            ("node_id_alloc", "long"),
+            ("edge_id_alloc", "long"),
+        ] {
+            let key = GraphGraphMLExporter::reserved(key);
+            GraphGraphMLExporter::write_key(&mut text, &key, "graph", &key, attr_type);
+        }
+        for key in ["can_move_forward", "can_move_backward"] {
+            let key = GraphGraphMLExporter::reserved(key);
+            GraphGraphMLExporter::write_key(&mut text, &key, "edge", &key, "boolean");
+        }
+
+        let node_keys: BTreeSet<&String> = node_attributes
+            .iter()
+            .flat_map(|(_, attributes)| attributes.iter().map(|(k, _)| k))
+            .collect();
+        for key in node_keys {
+            let key_id = format!("node.{}", key);
+            GraphGraphMLExporter::write_key(&mut text, &key_id, "node", key, "string");
+        }
+        let edge_keys: BTreeSet<&String> = edge_attributes
+            .iter()
+            .flat_map(|(_, attributes)| attributes.iter().map(|(k, _)| k))
+            .collect();
+        for key in edge_keys {
+            let key_id = format!("edge.{}", key);
+            GraphGraphMLExporter::write_key(&mut text, &key_id, "edge", key, "string");
+        }
+
+        text.push_str("  <graph id=\"G\" edgedefault=\"directed\">\n");
+        let graph_data = [
+            (
+                "allow_cyclic_edges",
+                graph.allows_cyclic_edges().to_string(),
+            ),
+            (
+                "allow_duplicate_edges",
+                graph.allows_duplicate_edges().to_string(),
+            ),
+            ("node_id_alloc", graph.get_node_id_alloc().to_string()),
+            ("edge_id_alloc", graph.get_edge_id_alloc().to_string()),
+        ];
+        for (key, value) in graph_data.iter() {
+            let key = GraphGraphMLExporter::reserved(key);
+            GraphGraphMLExporter::write_data(&mut text, "    ", &key, value);
+        }
+
+        for (node_id, attributes) in node_attributes.iter() {
+            text.push_str(&format!("    <node id=\"n{}\">\n", node_id));
+            for (key, value) in attributes.iter() {
+                let key_id = format!("node.{}", key);
+                GraphGraphMLExporter::write_data(&mut text, "      ", &key_id, value);
+            }
+            text.push_str("    </node>\n");
+        }
+
+        for (edge_id, attributes) in edge_attributes.iter() {
+            let edge = graph.get_edge_by_id(edge_id).unwrap();
+            text.push_str(&format!(
+                "    <edge id=\"e{}\" source=\"n{}\" target=\"n{}\">\n",
+                edge_id,
+                edge.node1(),
+                edge.node2()
+            ));
+            for (key, value) in [
+                ("can_move_forward", edge.can_move_forward()),
+                ("can_move_backward", edge.can_move_backward()),
+            ] {
+                let key = GraphGraphMLExporter::reserved(key);
+                GraphGraphMLExporter::write_data(&mut text, "      ", &key, &value.to_string());
+            }
+            for (key, value) in attributes.iter() {
+                let key_id = format!("edge.{}", key);
+                GraphGraphMLExporter::write_data(&mut text, "      ", &key_id, value);
+            }
+            text.push_str("    </edge>\n");
+        }
+
+        text.push_str("  </graph>\n");
+        text.push_str("</graphml>\n");
+        return text;
+    }
+
+    fn reserved(name: &str) -> String {
+        return format!("{}{}", RESERVED_KEY_PREFIX, name);
+    }
+
+    fn write_key(text: &mut String, key_id: &str, domain: &str, name: &str, attr_type: &str) {
+        text.push_str(&format!(
+            "  <key id=\"{}\" for=\"{}\" attr.name=\"{}\" attr.type=\"{}\"/>\n",
+            escape(key_id),
+            domain,
+            escape(name),
+            attr_type
+        ));
+    }
+
+    fn write_data(text: &mut String, indent: &str, key_id: &str, value: &str) {
+        text.push_str(&format!(
+            "{}<data key=\"{}\">{}</data>\n",
+            indent,
+            escape(key_id),
+            escape(value)
+        ));
+    }
+}
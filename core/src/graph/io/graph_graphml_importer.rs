@@ -0,0 +1,626 @@
+use std::{collections::HashMap, fs};
+
+use quick_xml::{
+    escape::resolve_predefined_entity,
+    events::{BytesStart, Event},
+    Reader,
+};
+
+use crate::graph::graph::{EntityEdge, Graph};
+
+use super::{graph_graphml_exporter::RESERVED_KEY_PREFIX, graph_import_error::GraphImportError};
+
+/// Import of `Graph` from GraphML written by `GraphGraphMLExporter`.
+///
+/// Nodes and edges keep their IDs, and the ID allocators continue from the exported
+/// values. Data of each node and edge is handed to the parsers as a map from attribute
+/// name to value, without the reserved `graph.` attributes. GraphML written by other tools
+/// is accepted as long as node IDs are numbers, optionally prefixed with `n`. Edge IDs are
+/// numbers optionally prefixed with `e`; edges without ID get IDs after the largest given
+/// one, in document order. Edges without movement flags follow their `directed` attribute
+/// or the graph's `edgedefault`.
+pub struct GraphGraphMLImporter;
+
+enum GraphMLElement {
+    Graph,
+    Node {
+        node_id: u32,
+        data: HashMap<String, String>,
+    },
+    Edge {
+        edge_id: Option<u32>,
+        node1: u32,
+        node2: u32,
+        is_directed: Option<bool>,
+        data: HashMap<String, String>,
+    },
+}
+
+/// Edge read from the document, waiting for its ID and info.
+struct GraphMLEdge {
+    edge_id: Option<u32>,
+    nodes: (u32, u32),
+    directions: (bool, bool),
+    data: HashMap<String, String>,
+}
+
+impl GraphGraphMLImporter {
+    pub fn import<TNodeInfo, TEdgeInfo, NodeParserT, EdgeParserT>(
+        file_name: &str,
+        node_parser: NodeParserT,
+        edge_parser: EdgeParserT,
+    ) -> Result<Graph<TNodeInfo, TEdgeInfo>, GraphImportError>
+    where
+        NodeParserT: Fn(&HashMap<String, String>) -> Result<TNodeInfo, String>,
+        EdgeParserT: Fn(&HashMap<String, String>) -> Result<TEdgeInfo, String>,
+    {
+        let text = match fs::read_to_string(file_name) {
+            Ok(t) => t,
+            Err(e) => return Err(GraphImportError::Io(e.to_string())),
+        };
+        return GraphGraphMLImporter::import_from_str(&text, node_parser, edge_parser);
+    }
+
+    pub fn import_from_str<TNodeInfo, TEdgeInfo, NodeParserT, EdgeParserT>(
+        text: &str,
+        node_parser: NodeParserT,
+        edge_parser: EdgeParserT,
+    ) -> Result<Graph<TNodeInfo, TEdgeInfo>, GraphImportError>
+    where
+        NodeParserT: Fn(&HashMap<String, String>) -> Result<TNodeInfo, String>,
+        EdgeParserT: Fn(&HashMap<String, String>) -> Result<TEdgeInfo, String>,
+    {
+        let mut reader = Reader::from_str(text);
+
+        // Attribute names of declared keys, by key ID.
+        let mut key_names: HashMap<String, String> = HashMap::new();
+        let mut graph_data: HashMap<String, String> = HashMap::new();
+        let mut is_directed_default = true;
+        let mut nodes: Vec<(u32, TNodeInfo)> = Vec::new();
+        let mut graphml_edges: Vec<GraphMLEdge> = Vec::new();
+
+        let mut element: Option<GraphMLElement> = None;
+        let mut data_key: Option<String> = None;
+        let mut data_text = String::new();
+
+        loop {
+            let event = match reader.read_event() {
+                Ok(e) => e,
+                Err(e) => return Err(GraphImportError::Syntax(e.to_string())),
+            };
+
+            match event {
+                Event::Start(ref start) | Event::Empty(ref start) => {
+                    let is_empty = matches!(event, Event::Empty(_));
+                    let attributes = GraphGraphMLImporter::attributes(start)?;
+                    match start.local_name().as_ref() {
+                        b"key" => {
+                            let key_id = GraphGraphMLImporter::attribute(&attributes, "id")?;
+                            let name = match attributes.get("attr.name") {
+                                Some(n) => n.clone(),
+                                None => key_id.clone(),
+                            };
+                            key_names.insert(key_id, name);
+                        }
+                        b"graph" => {
+                            if element.is_some() {
+                                return Err(GraphImportError::InvalidStructure(String::from(
+                                    "nested graphs are not supported",
+                                )));
+                            }
+                            is_directed_default = match attributes.get("edgedefault") {
+                                Some(d) => d != "undirected",
+                                None => true,
+                            };
+                            element = Some(GraphMLElement::Graph);
+                        }
+                        b"node" => {
+                            let id = GraphGraphMLImporter::attribute(&attributes, "id")?;
+                            let node_id = GraphGraphMLImporter::parse_id(&id, "n")?;
+                            let node = GraphMLElement::Node {
+                                node_id: node_id,
+                                data: HashMap::new(),
+                            };
+                            if is_empty {
+                                GraphGraphMLImporter::finish_element(
+                                    node,
+                                    is_directed_default,
+                                    &mut nodes,
+                                    &mut graphml_edges,
+                                    &node_parser,
+                                )?;
+                            } else {
+                                element = Some(node);
+                            }
+                        }
+                        b"edge" => {
+                            let edge_id = match attributes.get("id") {
+                                Some(id) => Some(GraphGraphMLImporter::parse_id(id, "e")?),
+                                None => None,
+                            };
+                            let source = GraphGraphMLImporter::attribute(&attributes, "source")?;
+                            let target = GraphGraphMLImporter::attribute(&attributes, "target")?;
+                            let is_directed = match attributes.get("directed") {
+                                Some(d) => Some(GraphGraphMLImporter::parse_bool(d)?),
+                                None => None,
+                            };
+                            let edge = GraphMLElement::Edge {
+                                edge_id: edge_id,
+                                node1: GraphGraphMLImporter::parse_id(&source, "n")?,
+                                node2: GraphGraphMLImporter::parse_id(&target, "n")?,
+                                is_directed: is_directed,
+                                data: HashMap::new(),
+                            };
+                            if is_empty {
+                                GraphGraphMLImporter::finish_element(
+                                    edge,
+                                    is_directed_default,
+                                    &mut nodes,
+                                    &mut graphml_edges,
+                                    &node_parser,
+                                )?;
+                            } else {
+                                element = Some(edge);
+                            }
+                        }
+                        b"data" => {
+                            let key_id = GraphGraphMLImporter::attribute(&attributes, "key")?;
+                            data_text.clear();
+                            if is_empty {
+                                GraphGraphMLImporter::store_data(
+                                    &mut element,
+                                    &mut graph_data,
+                                    &key_names,
+                                    &key_id,
+                                    String::new(),
+                                );
+                            } else {
+                                data_key = Some(key_id);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                Event::Text(text) if data_key.is_some() => match text.xml_content() {
+                    Ok(t) => data_text.push_str(&t),
+                    Err(e) => return Err(GraphImportError::Syntax(e.to_string())),
+                },
+                Event::CData(text) if data_key.is_some() => match text.decode() {
+                    Ok(t) => data_text.push_str(&t),
+                    Err(e) => return Err(GraphImportError::Syntax(e.to_string())),
+                },
+                Event::GeneralRef(reference) if data_key.is_some() => {
+                    let resolved = match reference.resolve_char_ref() {
+                        Ok(Some(c)) => c.to_string(),
+                        Ok(None) => {
+                            let name = match reference.decode() {
+                                Ok(n) => n.to_string(),
+                                Err(e) => return Err(GraphImportError::Syntax(e.to_string())),
+                            };
+                            match resolve_predefined_entity(&name) {
+                                Some(r) => String::from(r),
+                                None => {
+                                    return Err(GraphImportError::Syntax(format!(
+                                        "unknown entity &{};",
+                                        name
+                                    )))
+                                }
+                            }
+                        }
+                        Err(e) => return Err(GraphImportError::Syntax(e.to_string())),
+                    };
+                    data_text.push_str(&resolved);
+                }
+                Event::End(end) => match end.local_name().as_ref() {
+                    b"data" => {
+                        if let Some(key_id) = data_key.take() {
+                            GraphGraphMLImporter::store_data(
+                                &mut element,
+                                &mut graph_data,
+                                &key_names,
+                                &key_id,
+                                data_text.clone(),
+                            );
+                        }
+                    }
+                    b"node" | b"edge" => {
+                        if let Some(finished) = element.take() {
+                            GraphGraphMLImporter::finish_element(
+                                finished,
+                                is_directed_default,
+                                &mut nodes,
+                                &mut graphml_edges,
+                                &node_parser,
+                            )?;
+                        }
+                        element = Some(GraphMLElement::Graph);
+                    }
+                    b"graph" => {
+                        element = None;
+                    }
+                    _ => {}
+                },
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        // Edges without ID continue after the largest given ID.
+        let mut next_edge_id = match graphml_edges.iter().filter_map(|e| e.edge_id).max() {
+            Some(i) => i.saturating_add(1),
+            None => 1,
+        };
+        let mut edges: Vec<EntityEdge<TEdgeInfo>> = Vec::with_capacity(graphml_edges.len());
+        for graphml_edge in graphml_edges {
+            let edge_id = match graphml_edge.edge_id {
+                Some(i) => i,
+                None => {
+                    let edge_id = next_edge_id;
+                    next_edge_id = next_edge_id.saturating_add(1);
+                    edge_id
+                }
+            };
+            match edge_parser(&graphml_edge.data) {
+                Ok(edge_info) => edges.push((
+                    edge_id,
+                    graphml_edge.nodes,
+                    graphml_edge.directions,
+                    edge_info,
+                )),
+                Err(message) => {
+                    return Err(GraphImportError::InvalidEdgeInfo {
+                        edge_id: edge_id,
+                        message: message,
+                    })
+                }
+            }
+        }
+
+        let allow_cyclic_edges =
+            match graph_data.get(&GraphGraphMLImporter::reserved("allow_cyclic_edges")) {
+                Some(v) => GraphGraphMLImporter::parse_bool(v)?,
+                None => false,
+            };
+        let allow_duplicate_edges =
+            match graph_data.get(&GraphGraphMLImporter::reserved("allow_duplicate_edges")) {
+                Some(v) => GraphGraphMLImporter::parse_bool(v)?,
+                None => false,
+            };
+
+        let mut graph =
+            Graph::from_entities(nodes, edges, allow_cyclic_edges, allow_duplicate_edges)?;
+
+        let node_id_alloc = match graph_data.get(&GraphGraphMLImporter::reserved("node_id_alloc")) {
+            Some(v) => GraphGraphMLImporter::parse_id(v, "")?,
+            None => 0,
+        };
+        let edge_id_alloc = match graph_data.get(&GraphGraphMLImporter::reserved("edge_id_alloc")) {
+            Some(v) => GraphGraphMLImporter::parse_id(v, "")?,
+            None => 0,
+        };
+        graph.advance_id_alloc(node_id_alloc, edge_id_alloc);
+        return Ok(graph);
+    }
+
+    fn store_data(
+        element: &mut Option<GraphMLElement>,
+        graph_data: &mut HashMap<String, String>,
+        key_names: &HashMap<String, String>,
+        key_id: &str,
+        value: String,
+    ) {
+        let name = match key_names.get(key_id) {
+            Some(n) => n.clone(),
+            None => String::from(key_id),
+        };
+        match element {
+            Some(GraphMLElement::Graph) => {
+                graph_data.insert(name, value);
+            }
+            Some(GraphMLElement::Node { data, .. }) | Some(GraphMLElement::Edge { data, .. }) => {
+                data.insert(name, value);
+            }
+            None => {}
+        }
+    }
+
+    fn finish_element<TNodeInfo, NodeParserT>(
+        element: GraphMLElement,
+        is_directed_default: bool,
+        nodes: &mut Vec<(u32, TNodeInfo)>,
+        edges: &mut Vec<GraphMLEdge>,
+        node_parser: &NodeParserT,
+    ) -> Result<(), GraphImportError>
+    where
+        NodeParserT: Fn(&HashMap<String, String>) -> Result<TNodeInfo, String>,
+    {
+        match element {
+            GraphMLElement::Graph => {}
+            GraphMLElement::Node { node_id, data } => match node_parser(&data) {
+                Ok(node_info) => nodes.push((node_id, node_info)),
+                Err(message) => {
+                    return Err(GraphImportError::InvalidNodeInfo {
+                        node_id: node_id,
+                        message: message,
+                    })
+                }
+            },
+            GraphMLElement::Edge {
+                edge_id,
+                node1,
+                node2,
+                is_directed,
+                mut data,
+            } => {
+                let is_directed = is_directed.unwrap_or(is_directed_default);
+                let forward_key = GraphGraphMLImporter::reserved("can_move_forward");
+                let can_move_forward = match data.remove(&forward_key) {
+                    Some(v) => GraphGraphMLImporter::parse_bool(&v)?,
+                    None => true,
+                };
+                let backward_key = GraphGraphMLImporter::reserved("can_move_backward");
+                let can_move_backward = match data.remove(&backward_key) {
+                    Some(v) => GraphGraphMLImporter::parse_bool(&v)?,
+                    None => !is_directed,
+                };
+                edges.push(GraphMLEdge {
+                    edge_id: edge_id,
+                    nodes: (node1, node2),
+                    directions: (can_move_forward, can_move_backward),
+                    data: data,
+                });
+            }
+        }
+
+        return Ok(());
+    }
+
+    fn reserved(name: &str) -> String {
+        return format!("{}{}", RESERVED_KEY_PREFIX, name);
+    }
+
+    fn attributes(start: &BytesStart) -> Result<HashMap<String, String>, GraphImportError> {
+        let mut attributes: HashMap<String, String> = HashMap::new();
+        for attribute in start.attributes() {
+            let attribute = match attribute {
+                Ok(a) => a,
+                Err(e) => return Err(GraphImportError::Syntax(e.to_string())),
+            };
+            let key = String::from_utf8_lossy(attribute.key.as_ref()).to_string();
+            let value = match attribute.unescape_value() {
+                Ok(v) => v.to_string(),
+                Err(e) => return Err(GraphImportError::Syntax(e.to_string())),
+            };
+            attributes.insert(key, value);
+        }
+
+        return Ok(attributes);
+    }
+
+    fn attribute(
+        attributes: &HashMap<String, String>,
+        name: &str,
+    ) -> Result<String, GraphImportError> {
+        return match attributes.get(name) {
+            Some(v) => Ok(v.clone()),
+            None => Err(GraphImportError::InvalidStructure(format!(
+                "missing attribute \"{}\"",
+                name
+            ))),
+        };
+    }
+
+    fn parse_id(text: &str, prefix: &str) -> Result<u32, GraphImportError> {
+        let digits = text.trim();
+        let digits = digits.strip_prefix(prefix).unwrap_or(digits);
+        return match digits.parse::<u32>() {
+            Ok(id) => Ok(id),
+            Err(_) => Err(GraphImportError::InvalidStructure(format!(
+                "\"{}\" is not a numeric ID",
+                text
+            ))),
+        };
+    }
+
+    fn parse_bool(text: &str) -> Result<bool, GraphImportError> {
+        return match text.trim() {
+            "true" | "1" => Ok(true),
+            "false" | "0" => Ok(false),
+            _ => Err(GraphImportError::InvalidStructure(format!(
+                "\"{}\" is not a boolean",
+                text
+            ))),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{graph_error::GraphError, io::graph_graphml_exporter::GraphGraphMLExporter};
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Place {
+        x: f64,
+        label: String,
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Road {
+        cost: f64,
+        /// Stored under the name the exporter uses for movement flags without prefix.
+        can_move_forward: String,
+    }
+
+    fn sample_graph() -> Graph<Place, Road> {
+        let mut graph: Graph<Place, Road> = Graph::new(true, false);
+        let mut node_ids: Vec<u32> = Vec::new();
+        for (x, label) in [
+            (0.5, "a & b"),
+            (-2.0, "<c>"),
+            (1e-3, "\"d\""),
+            (7.0, "removed"),
+        ] {
+            node_ids.push(graph.add_node(Place {
+                x: x,
+                label: String::from(label),
+            }));
+        }
+        let road = |cost: f64, note: &str| {
+            return Road {
+                cost: cost,
+                can_move_forward: String::from(note),
+            };
+        };
+        graph
+            .add_directed_edge(node_ids[0], node_ids[1], true, false, road(1.5, "one way"))
+            .unwrap();
+        graph
+            .add_edge(node_ids[1], node_ids[2], road(2.0, "yes"))
+            .unwrap();
+        graph
+            .add_directed_edge(node_ids[2], node_ids[0], false, false, road(0.0, ""))
+            .unwrap();
+        graph
+            .add_edge(node_ids[2], node_ids[2], road(3.25, "loop"))
+            .unwrap();
+        graph
+            .add_edge(node_ids[3], node_ids[0], road(9.0, "x"))
+            .unwrap();
+        graph.remove_node(&node_ids[3]).unwrap();
+        return graph;
+    }
+
+    fn format_place(place: &Place) -> Vec<(String, String)> {
+        return vec![
+            (String::from("x"), place.x.to_string()),
+            (String::from("label"), place.label.clone()),
+        ];
+    }
+
+    fn format_road(road: &Road) -> Vec<(String, String)> {
+        return vec![
+            (String::from("cost"), road.cost.to_string()),
+            (
+                String::from("can_move_forward"),
+                road.can_move_forward.clone(),
+            ),
+        ];
+    }
+
+    fn parse_place(data: &HashMap<String, String>) -> Result<Place, String> {
+        let x = match data.get("x").map(|x| x.parse::<f64>()) {
+            Some(Ok(x)) => x,
+            _ => return Err(String::from("missing x")),
+        };
+        return Ok(Place {
+            x: x,
+            label: data.get("label").cloned().unwrap_or_default(),
+        });
+    }
+
+    fn parse_road(data: &HashMap<String, String>) -> Result<Road, String> {
+        let cost = match data.get("cost").map(|c| c.parse::<f64>()) {
+            Some(Ok(c)) => c,
+            _ => return Err(String::from("missing cost")),
+        };
+        return Ok(Road {
+            cost: cost,
+            can_move_forward: data.get("can_move_forward").cloned().unwrap_or_default(),
+        });
+    }
+
+    #[test]
+    fn export_import_round_trip() {
+        let graph = sample_graph();
+        let text = GraphGraphMLExporter::export_to_string(&graph, format_place, format_road);
+        let imported =
+            GraphGraphMLImporter::import_from_str(&text, parse_place, parse_road).unwrap();
+
+        assert_eq!(imported.allows_cyclic_edges(), graph.allows_cyclic_edges());
+        assert_eq!(
+            imported.allows_duplicate_edges(),
+            graph.allows_duplicate_edges()
+        );
+        assert_eq!(imported.get_node_id_alloc(), graph.get_node_id_alloc());
+        assert_eq!(imported.get_edge_id_alloc(), graph.get_edge_id_alloc());
+        assert_eq!(imported.get_node_count(), graph.get_node_count());
+        assert_eq!(imported.get_edge_count(), graph.get_edge_count());
+        for (node_id, node) in graph.get_nodes() {
+            let imported_node = imported.get_node_by_id(node_id).unwrap();
+            assert_eq!(imported_node.node_info(), node.node_info());
+        }
+        for (edge_id, edge) in graph.get_edges() {
+            let imported_edge = imported.get_edge_by_id(edge_id).unwrap();
+            assert_eq!(imported_edge.node1(), edge.node1());
+            assert_eq!(imported_edge.node2(), edge.node2());
+            assert_eq!(imported_edge.can_move_forward(), edge.can_move_forward());
+            assert_eq!(imported_edge.can_move_backward(), edge.can_move_backward());
+            assert_eq!(imported_edge.edge_info(), edge.edge_info());
+        }
+        assert_eq!(imported.validate(), Ok(()));
+    }
+
+    #[test]
+    fn import_allocates_missing_edge_ids_and_follows_directed_attributes() {
+        let text = r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key id="d0" for="edge" attr.name="cost" attr.type="double"/>
+  <graph id="G" edgedefault="undirected">
+    <node id="n1"/>
+    <node id="n2"/>
+    <node id="3"/>
+    <edge source="n1" target="n2"><data key="d0">1</data></edge>
+    <edge id="e7" source="n2" target="3" directed="true"><data key="d0">2</data></edge>
+    <edge source="3" target="n1"><data key="d0">3</data></edge>
+  </graph>
+</graphml>
+"#;
+        let graph = GraphGraphMLImporter::import_from_str(
+            text,
+            |_| Ok(()),
+            |data| match data.get("cost").map(|c| c.parse::<f64>()) {
+                Some(Ok(c)) => Ok(c),
+                _ => Err(String::from("missing cost")),
+            },
+        )
+        .unwrap();
+
+        let mut edge_ids: Vec<&u32> = graph.get_edges().keys().collect();
+        edge_ids.sort();
+        assert_eq!(edge_ids, vec![&7, &8, &9]);
+        assert_eq!(*graph.get_edge_by_id(&8).unwrap().edge_info(), 1_f64);
+        assert_eq!(*graph.get_edge_by_id(&9).unwrap().edge_info(), 3_f64);
+        assert!(graph.get_edge_by_id(&8).unwrap().can_move_from(2));
+        assert!(!graph.get_edge_by_id(&7).unwrap().can_move_from(3));
+        assert_eq!(graph.get_edge_id_alloc(), 10);
+    }
+
+    #[test]
+    fn import_rejects_malformed_input() {
+        let parse = |text: &str| {
+            return GraphGraphMLImporter::import_from_str(text, |_| Ok(()), |_| Ok(()));
+        };
+
+        assert!(matches!(
+            parse("<graphml><graph><node/></graph></graphml>"),
+            Err(GraphImportError::InvalidStructure(_))
+        ));
+        assert!(matches!(
+            parse("<graphml><graph><node id=\"a\"/></graph></graphml>"),
+            Err(GraphImportError::InvalidStructure(_))
+        ));
+        assert!(matches!(
+            parse("<graphml><graph><node id=\"1\"></graph></graphml>"),
+            Err(GraphImportError::Syntax(_))
+        ));
+        assert_eq!(
+            parse("<graphml><graph><node id=\"1\"/><edge source=\"1\" target=\"2\"/></graph></graphml>")
+                .err(),
+            Some(GraphImportError::Graph(GraphError::EdgeEndNodeNotFound {
+                edge_id: 1,
+                node_id: 2,
+            }))
+        );
+    }
+}
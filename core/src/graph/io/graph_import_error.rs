@@ -0,0 +1,23 @@
+use crate::graph::graph_error::GraphError;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum GraphImportError {
+    /// File could not be read.
+    Io(String),
+    /// Input is not well-formed JSON or XML.
+    Syntax(String),
+    /// Input is well-formed but does not describe a graph, for example a node without ID.
+    InvalidStructure(String),
+    /// Node info parser rejected the info of given node.
+    InvalidNodeInfo { node_id: u32, message: String },
+    /// Edge info parser rejected the info of given edge.
+    InvalidEdgeInfo { edge_id: u32, message: String },
+    /// Nodes and edges do not form a valid graph.
+    Graph(GraphError),
+}
+
+impl From<GraphError> for GraphImportError {
+    fn from(error: GraphError) -> Self {
+        return GraphImportError::Graph(error);
+    }
+}
@@ -0,0 +1,103 @@
+use std::fs;
+
+use serde_json::{json, Value};
+
+use crate::graph::graph::Graph;
+
+/// Export of `Graph` as JSON, readable back with `GraphJsonImporter`.
+///
+/// Layout of the document:
+///
+/// ```text
+/// {
+///   "allow_cyclic_edges": false,
+///   "allow_duplicate_edges": false,
+///   "node_id_alloc": 3,
+///   "edge_id_alloc": 2,
+///   "nodes": [ { "id": 0, "info": ... }, ... ],
+///   "edges": [ { "id": 0, "node1": 0, "node2": 1,
+///                "can_move_forward": true, "can_move_backward": true, "info": ... }, ... ]
+/// }
+/// ```
+///
+/// Nodes and edges are written in ascending ID order, and `info` is produced by given
+/// formatters.
+pub struct GraphJsonExporter;
+
+impl GraphJsonExporter {
+    pub fn export<TNodeInfo, TEdgeInfo, NodeFormatterT, EdgeFormatterT>(
+        file_name: &str,
+        graph: &Graph<TNodeInfo, TEdgeInfo>,
+        node_formatter: NodeFormatterT,
+        edge_formatter: EdgeFormatterT,
+    ) -> std::io::Result<()>
+    where
+        NodeFormatterT: Fn(&TNodeInfo) -> Value,
+        EdgeFormatterT: Fn(&TEdgeInfo) -> Value,
+    {
+        let text = GraphJsonExporter::export_to_string(graph, node_formatter, edge_formatter);
+        return fs::write(file_name, text);
+    }
+
+    pub fn export_to_string<TNodeInfo, TEdgeInfo, NodeFormatterT, EdgeFormatterT>(
+        graph: &Graph<TNodeInfo, TEdgeInfo>,
+        node_formatter: NodeFormatterT,
+        edge_formatter: EdgeFormatterT,
+    ) -> String
+    where
+        NodeFormatterT: Fn(&TNodeInfo) -> Value,
+        EdgeFormatterT: Fn(&TEdgeInfo) -> Value,
+    {
+        let value = GraphJsonExporter::export_to_value(graph, node_formatter, edge_formatter);
+        return serde_json::to_string_pretty(&value).unwrap();
+    }
+
+    pub fn export_to_value<TNodeInfo, TEdgeInfo, NodeFormatterT, EdgeFormatterT>(
+        graph: &Graph<TNodeInfo, TEdgeInfo>,
+        node_formatter: NodeFormatterT,
+        edge_formatter: EdgeFormatterT,
+    ) -> Value
+    where
+        NodeFormatterT: Fn(&TNodeInfo) -> Value,
+        EdgeFormatterT: Fn(&TEdgeInfo) -> Value,
+    {
+        let mut node_ids: Vec<&u32> = graph.get_nodes().keys().collect();
+        node_ids.sort();
+        let nodes: Vec<Value> = node_ids
+            .into_iter()
+            .map(|node_id| {
+                let node = graph.get_node_by_id(node_id).unwrap();
+                return json!({
+                    "id": node_id,
+                    "info": node_formatter(node.node_info()),
+                });
+            })
+            .collect();
+
+        let mut edge_ids: Vec<&u32> = graph.get_edges().keys().collect();
+        edge_ids.sort();
+        let edges: Vec<Value> = edge_ids
+            .into_iter()
+            .map(|edge_id| {
+                let edge = graph.get_edge_by_id(edge_id).unwrap();
+                return json!({
+                    "id": edge_id,
+                    "node1": edge.node1(),
+                    "node2": edge.node2(),
+                    "can_move_forward": edge.can_move_forward(),
+                    "can_move_backward": edge.can_move_backward(),
+                    "info": edge_formatter(edge.edge_info()),
+                });
+            })
+            .collect();
+
+        return json!({
+            "allow_cyclic_edges": graph.allows_cyclic_edges(),
+            "allow_duplicate_edges": graph.allows_duplicate_edges(),
+            "node_id_alloc": graph.get_node_id_alloc(),
+            "edge_id_alloc": graph.get_edge_id_alloc(),
+            "nodes": nodes,
+            "edges": edges,
+        });
+    }
+}
@@ -0,0 +1,234 @@
+use std::fs;
+
+use serde_json::Value;
+
+use crate::graph::graph::{EntityEdge, Graph};
+
+use super::graph_import_error::GraphImportError;
+
+/// Import of `Graph` from JSON written by `GraphJsonExporter`.
+///
+/// Nodes and edges keep their IDs, and the ID allocators continue from the exported
+/// values, so IDs added after a reload do not collide with IDs used before the export.
+pub struct GraphJsonImporter;
+
+impl GraphJsonImporter {
+    pub fn import<TNodeInfo, TEdgeInfo, NodeParserT, EdgeParserT>(
+        file_name: &str,
+        node_parser: NodeParserT,
+        edge_parser: EdgeParserT,
+    ) -> Result<Graph<TNodeInfo, TEdgeInfo>, GraphImportError>
+    where
+        NodeParserT: Fn(&Value) -> Result<TNodeInfo, String>,
+        EdgeParserT: Fn(&Value) -> Result<TEdgeInfo, String>,
+    {
+        let text = match fs::read_to_string(file_name) {
+            Ok(t) => t,
+            Err(e) => return Err(GraphImportError::Io(e.to_string())),
+        };
+        return GraphJsonImporter::import_from_str(&text, node_parser, edge_parser);
+    }
+
+    pub fn import_from_str<TNodeInfo, TEdgeInfo, NodeParserT, EdgeParserT>(
+        text: &str,
+        node_parser: NodeParserT,
+        edge_parser: EdgeParserT,
+    ) -> Result<Graph<TNodeInfo, TEdgeInfo>, GraphImportError>
+    where
+        NodeParserT: Fn(&Value) -> Result<TNodeInfo, String>,
+        EdgeParserT: Fn(&Value) -> Result<TEdgeInfo, String>,
+    {
+        let value: Value = match serde_json::from_str(text) {
+            Ok(v) => v,
+            Err(e) => return Err(GraphImportError::Syntax(e.to_string())),
+        };
+        return GraphJsonImporter::import_from_value(&value, node_parser, edge_parser);
+    }
+
+    pub fn import_from_value<TNodeInfo, TEdgeInfo, NodeParserT, EdgeParserT>(
+        value: &Value,
+        node_parser: NodeParserT,
+        edge_parser: EdgeParserT,
+    ) -> Result<Graph<TNodeInfo, TEdgeInfo>, GraphImportError>
+    where
+        NodeParserT: Fn(&Value) -> Result<TNodeInfo, String>,
+        EdgeParserT: Fn(&Value) -> Result<TEdgeInfo, String>,
+    {
+        let allow_cyclic_edges = GraphJsonImporter::get_bool(value, "allow_cyclic_edges")?;
+        let allow_duplicate_edges = GraphJsonImporter::get_bool(value, "allow_duplicate_edges")?;
+        let node_id_alloc = GraphJsonImporter::get_u32(value, "node_id_alloc")?;
+        let edge_id_alloc = GraphJsonImporter::get_u32(value, "edge_id_alloc")?;
+
+        let mut nodes: Vec<(u32, TNodeInfo)> = Vec::new();
+        for node in GraphJsonImporter::get_array(value, "nodes")? {
+            let node_id = GraphJsonImporter::get_u32(node, "id")?;
+            let node_info = match node_parser(&node["info"]) {
+                Ok(i) => i,
+                Err(message) => {
+                    return Err(GraphImportError::InvalidNodeInfo {
+                        node_id: node_id,
+                        message: message,
+                    })
+                }
+            };
+            nodes.push((node_id, node_info));
+        }
+
+        let mut edges: Vec<EntityEdge<TEdgeInfo>> = Vec::new();
+        for edge in GraphJsonImporter::get_array(value, "edges")? {
+            let edge_id = GraphJsonImporter::get_u32(edge, "id")?;
+            let node1 = GraphJsonImporter::get_u32(edge, "node1")?;
+            let node2 = GraphJsonImporter::get_u32(edge, "node2")?;
+            let can_move_forward = GraphJsonImporter::get_bool(edge, "can_move_forward")?;
+            let can_move_backward = GraphJsonImporter::get_bool(edge, "can_move_backward")?;
+            let edge_info = match edge_parser(&edge["info"]) {
+                Ok(i) => i,
+                Err(message) => {
+                    return Err(GraphImportError::InvalidEdgeInfo {
+                        edge_id: edge_id,
+                        message: message,
+                    })
+                }
+            };
+            edges.push((
+                edge_id,
+                (node1, node2),
+                (can_move_forward, can_move_backward),
+                edge_info,
+            ));
+        }
+
+        let mut graph =
+            Graph::from_entities(nodes, edges, allow_cyclic_edges, allow_duplicate_edges)?;
+        graph.advance_id_alloc(node_id_alloc, edge_id_alloc);
+        return Ok(graph);
+    }
+
+    fn get_u32(value: &Value, key: &str) -> Result<u32, GraphImportError> {
+        return match value[key].as_u64() {
+            Some(v) if v <= u32::MAX as u64 => Ok(v as u32),
+            _ => Err(GraphImportError::InvalidStructure(format!(
+                "\"{}\" must be an unsigned 32-bit integer",
+                key
+            ))),
+        };
+    }
+
+    fn get_bool(value: &Value, key: &str) -> Result<bool, GraphImportError> {
+        return match value[key].as_bool() {
+            Some(v) => Ok(v),
+            None => Err(GraphImportError::InvalidStructure(format!(
+                "\"{}\" must be a boolean",
+                key
+            ))),
+        };
+    }
+
+    fn get_array<'a>(value: &'a Value, key: &str) -> Result<&'a Vec<Value>, GraphImportError> {
+        return match value[key].as_array() {
+            Some(v) => Ok(v),
+            None => Err(GraphImportError::InvalidStructure(format!(
+                "\"{}\" must be an array",
+                key
+            ))),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::graph::io::graph_json_exporter::GraphJsonExporter;
+
+    fn parse_f64(value: &Value) -> Result<f64, String> {
+        return value.as_f64().ok_or(String::from("not a number"));
+    }
+
+    #[test]
+    fn export_import_round_trip() {
+        let mut graph: Graph<(f64, f64), f64> = Graph::new(false, true);
+        let a = graph.add_node((0_f64, 1.5_f64));
+        let b = graph.add_node((-3_f64, 2_f64));
+        let c = graph.add_node((4_f64, 0_f64));
+        graph.add_directed_edge(a, b, true, false, 1_f64).unwrap();
+        graph.add_edge(a, b, 2.5_f64).unwrap();
+        graph.add_directed_edge(c, b, false, true, 0.5_f64).unwrap();
+        let removed = graph.add_edge(a, c, 7_f64).unwrap();
+        graph.remove_edge(&removed).unwrap();
+
+        let text =
+            GraphJsonExporter::export_to_string(&graph, |(x, y)| json!([x, y]), |cost| json!(cost));
+        let imported = GraphJsonImporter::import_from_str(
+            &text,
+            |v| Ok((parse_f64(&v[0])?, parse_f64(&v[1])?)),
+            parse_f64,
+        )
+        .unwrap();
+
+        assert!(!imported.allows_cyclic_edges());
+        assert!(imported.allows_duplicate_edges());
+        assert_eq!(imported.get_node_id_alloc(), graph.get_node_id_alloc());
+        assert_eq!(imported.get_edge_id_alloc(), graph.get_edge_id_alloc());
+        assert_eq!(imported.get_node_count(), 3);
+        assert_eq!(imported.get_edge_count(), 3);
+        for (node_id, node) in graph.get_nodes() {
+            assert_eq!(
+                imported.get_node_by_id(node_id).unwrap().node_info(),
+                node.node_info()
+            );
+        }
+        for (edge_id, edge) in graph.get_edges() {
+            let imported_edge = imported.get_edge_by_id(edge_id).unwrap();
+            assert_eq!(
+                (imported_edge.node1(), imported_edge.node2()),
+                (edge.node1(), edge.node2())
+            );
+            assert_eq!(imported_edge.can_move_forward(), edge.can_move_forward());
+            assert_eq!(imported_edge.can_move_backward(), edge.can_move_backward());
+            assert_eq!(imported_edge.edge_info(), edge.edge_info());
+        }
+        assert_eq!(imported.validate(), Ok(()));
+
+        // Exporting the imported graph gives the same document.
+        let text_again = GraphJsonExporter::export_to_string(
+            &imported,
+            |(x, y)| json!([x, y]),
+            |cost| json!(cost),
+        );
+        assert_eq!(text_again, text);
+    }
+
+    #[test]
+    fn import_reports_invalid_structure_and_info() {
+        let document = json!({
+            "allow_cyclic_edges": false,
+            "allow_duplicate_edges": false,
+            "node_id_alloc": 3,
+            "edge_id_alloc": 2,
+            "nodes": [ { "id": 1, "info": 1.0 }, { "id": 2, "info": "x" } ],
+            "edges": [],
+        });
+        assert_eq!(
+            GraphJsonImporter::import_from_value(&document, parse_f64, parse_f64).err(),
+            Some(GraphImportError::InvalidNodeInfo {
+                node_id: 2,
+                message: String::from("not a number"),
+            })
+        );
+
+        let mut document = document;
+        document["nodes"][1]["info"] = json!(2.0);
+        document["edges"] = json!([ { "id": 1, "node1": 1, "node2": 2, "info": 1.0 } ]);
+        assert!(matches!(
+            GraphJsonImporter::import_from_value(&document, parse_f64, parse_f64),
+            Err(GraphImportError::InvalidStructure(_))
+        ));
+
+        assert!(matches!(
+            GraphJsonImporter::import_from_str("{", parse_f64, parse_f64),
+            Err(GraphImportError::Syntax(_))
+        ));
+    }
+}
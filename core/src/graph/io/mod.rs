@@ -0,0 +1,6 @@
+pub mod graph_dot_exporter;
+pub mod graph_graphml_exporter;
+pub mod graph_graphml_importer;
+pub mod graph_import_error;
+pub mod graph_json_exporter;
+pub mod graph_json_importer;
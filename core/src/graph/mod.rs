@@ -1,4 +1,14 @@
 pub mod edge;
+pub mod filtered_graph;
+pub mod frozen_graph;
 pub mod graph;
+pub mod graph_change;
+pub mod graph_edit;
+pub mod graph_edit_error;
+pub mod graph_editor;
 pub mod graph_error;
+pub mod graph_inconsistency;
+pub mod graph_path;
+pub mod graph_view;
+pub mod io;
 pub mod node;
@@ -42,6 +42,10 @@ impl<TNodeInfo> Node<TNodeInfo> {
         return &mut self.node_info;
     }
 
+    pub fn into_node_info(self) -> TNodeInfo {
+        return self.node_info;
+    }
+
     pub fn add_connection(&mut self, node_id: u32, edge_id: u32) -> bool {
         if self.connected_edges.contains_key(&edge_id) {
             return false;